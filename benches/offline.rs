@@ -0,0 +1,107 @@
+//! Benchmarks of offline matching over large synthetic perception streams.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use strem::compiler::Compiler;
+use strem::datastream::frame::sample::detections::{
+    Annotation, BoundingBox, DetectionRecord, Geometry, Point,
+};
+use strem::datastream::frame::sample::Sample;
+use strem::datastream::frame::Frame;
+use strem::matcher::offline::Matcher;
+use strem::matcher::Matching;
+
+/// Create a synthetic stream of `len` frames.
+///
+/// Every frame contains a `car`. A `pedestrian` only appears in the frames
+/// whose index is a multiple of `period` (if any).
+fn stream(len: usize, period: Option<usize>) -> Vec<Frame> {
+    let annotation = |label: &str| {
+        Annotation::new(
+            String::from(label),
+            1.0,
+            Geometry::BoundingBox(BoundingBox::new(
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 10.0),
+            )),
+        )
+    };
+
+    (0..len)
+        .map(|i| {
+            let mut record = DetectionRecord::new(String::from("cam::front"), i as f64, None);
+
+            let mut labels = vec!["car"];
+            if period.is_some_and(|period| i % period == 0) {
+                labels.push("pedestrian");
+            }
+
+            for label in labels {
+                record
+                    .annotations
+                    .entry(String::from(label))
+                    .or_default()
+                    .push(annotation(label));
+            }
+
+            let mut frame = Frame::new(i, i as f64);
+            frame.samples.push(Sample::ObjectDetection(record));
+            frame
+        })
+        .collect()
+}
+
+fn offline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("offline");
+    group.sample_size(10);
+
+    // Every frame may start a match, but no match ever completes. Restarting
+    // from every offset makes this quadratic.
+    let ast = Compiler::new()
+        .compile("[[:car:]]*[[:pedestrian:]]")
+        .unwrap();
+    let matcher = Matcher::from(&ast);
+
+    for len in [1_000, 10_000, 100_000, 1_000_000] {
+        let symbols = stream(len, None)
+            .iter()
+            .map(|frame| matcher.evaluate(frame))
+            .collect::<Vec<_>>();
+
+        group.bench_with_input(BenchmarkId::new("nomatch", len), &symbols, |b, symbols| {
+            b.iter(|| matcher.find_iter(black_box(symbols)).unwrap().count())
+        });
+
+        // Restarting the anchored search from every offset, for reference.
+        if len <= 1_000 {
+            group.bench_with_input(BenchmarkId::new("restart", len), &symbols, |b, symbols| {
+                b.iter(|| {
+                    (0..symbols.len())
+                        .filter_map(|offset| matcher.leftmost(&symbols[offset..]).unwrap())
+                        .count()
+                })
+            });
+        }
+    }
+
+    // Matches of various lengths are found throughout the stream.
+    let ast = Compiler::new()
+        .compile("[[:pedestrian:]][[:car:]]{2,}")
+        .unwrap();
+    let matcher = Matcher::from(&ast);
+
+    for len in [10_000, 100_000, 1_000_000] {
+        let symbols = stream(len, Some(50))
+            .iter()
+            .map(|frame| matcher.evaluate(frame))
+            .collect::<Vec<_>>();
+
+        group.bench_with_input(BenchmarkId::new("periodic", len), &symbols, |b, symbols| {
+            b.iter(|| matcher.find_iter(black_box(symbols)).unwrap().count())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, offline);
+criterion_main!(benches);
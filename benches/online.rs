@@ -0,0 +1,74 @@
+//! Benchmarks of online matching over large synthetic perception streams.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use strem::datastream::frame::sample::detections::{
+    Annotation, BoundingBox, DetectionRecord, Geometry, Point,
+};
+use strem::datastream::frame::sample::Sample;
+use strem::datastream::frame::Frame;
+use strem::matcher::automata::{Backend, Options};
+use strem::spre::SpRE;
+
+/// Create a synthetic stream of `len` frames.
+///
+/// Every frame contains a `car`. A `pedestrian` only appears in the frames
+/// whose index is a multiple of `period`.
+fn stream(len: usize, period: usize) -> Vec<Frame> {
+    let annotation = |label: &str| {
+        Annotation::new(
+            String::from(label),
+            1.0,
+            Geometry::BoundingBox(BoundingBox::new(
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 10.0),
+            )),
+        )
+    };
+
+    (0..len)
+        .map(|i| {
+            let mut record = DetectionRecord::new(String::from("cam::front"), i as f64, None);
+
+            let mut labels = vec!["car"];
+            if i % period == 0 {
+                labels.push("pedestrian");
+            }
+
+            for label in labels {
+                record
+                    .annotations
+                    .entry(String::from(label))
+                    .or_default()
+                    .push(annotation(label));
+            }
+
+            let mut frame = Frame::new(i, i as f64);
+            frame.samples.push(Sample::ObjectDetection(record));
+            frame
+        })
+        .collect()
+}
+
+fn online(c: &mut Criterion) {
+    let mut group = c.benchmark_group("online");
+    group.sample_size(10);
+
+    let frames = stream(15_000, 5_000);
+
+    // A counted repetition keeps a live thread per count. So, the work done
+    // per frame grows with the bound, even though the automaton does not.
+    for bound in [10, 100, 1_000] {
+        let pattern = format!("[[:car:]]{{1,{bound}}}[[:pedestrian:]]");
+        let spre =
+            SpRE::with_options(&pattern, &Options::new().backend(Backend::Counting)).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("counting", bound), &frames, |b, frames| {
+            b.iter(|| spre.find_iter(black_box(frames)).count())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, online);
+criterion_main!(benches);
@@ -3,6 +3,7 @@
 
 use std::error::Error;
 use std::fmt;
+use std::path::PathBuf;
 
 use clap::ArgMatches;
 use strem::config::Configuration;
@@ -10,6 +11,9 @@ use strem::controller::Controller;
 use strem::datastream::importer::stremf::DataImporter;
 use strem::datastream::importer::DataImport;
 use strem::datastream::DataStream;
+use strem::matcher::automata::Backend;
+use strem::matcher::MatchKind;
+use strem::monitor::zones::Zones;
 
 use self::printer::Printer;
 
@@ -33,26 +37,64 @@ impl App {
         let config = self.configure()?;
 
         // Load data into memory.
-        let mut importer = DataImporter::new(config.datastream.unwrap().clone());
+        let path = config
+            .datastream
+            .ok_or(Box::new(AppError::from("missing datastream")))?;
+
+        let mut importer = DataImporter::new(path.clone());
         importer.load()?;
 
         let datastream = DataStream::new().importer(Box::new(importer));
 
         // Set up and run [`Controller`].
-        let controller = Controller::new(&config, Some(Printer::print));
+        let controller = Controller::new(&config, Some(Printer::print))
+            .events(Printer::notify)
+            .verdicts(Printer::verdict);
+
+        if controller.is_unbounded(&datastream)? {
+            eprintln!(
+                "strem: warning: unbounded pattern; frames are kept for as long as a match may continue"
+            );
+        }
+
         controller.run(datastream)?;
 
         Ok(())
     }
 
     /// Create a [`Configuration`] from the CLI arguments.
-    fn configure(&self) -> Result<Configuration, Box<dyn Error>> {
+    fn configure(&self) -> Result<Configuration<'_>, Box<dyn Error>> {
+        let zones = match self.matches.get_one::<PathBuf>("zones") {
+            Some(path) => Some(Zones::load(path)?),
+            None => None,
+        };
+
+        let kind = match self.matches.get_one::<String>("match-kind") {
+            Some(kind) => Some(kind.parse::<MatchKind>()?),
+            None => None,
+        };
+
+        let backend = match self.matches.get_one::<String>("backend") {
+            Some(backend) => Some(backend.parse::<Backend>()?),
+            None => None,
+        };
+
         #[cfg(feature = "export")]
         return Ok(Configuration {
             pattern: self.matches.get_one("PATTERN").unwrap(),
             datastream: self.matches.get_one("DATASTREAM"),
             online: self.matches.get_flag("online"),
+            events: self.matches.get_flag("events"),
+            mtl: self.matches.get_flag("mtl"),
+            violations: self.matches.get_flag("violations"),
             limit: self.matches.get_one("max-count").copied(),
+            top: self.matches.get_one("top").copied(),
+            threshold: self.matches.get_one("probability").copied(),
+            kind,
+            window: self.matches.get_one("window").copied(),
+            backend,
+            budget: self.matches.get_one("dfa-size-limit").copied(),
+            zones,
             export: self.matches.get_one("export"),
             channels: self
                 .matches
@@ -65,7 +107,17 @@ impl App {
             pattern: self.matches.get_one("PATTERN").unwrap(),
             datastream: self.matches.get_one("DATASTREAM"),
             online: self.matches.get_flag("online"),
+            events: self.matches.get_flag("events"),
+            mtl: self.matches.get_flag("mtl"),
+            violations: self.matches.get_flag("violations"),
             limit: self.matches.get_one("max-count").copied(),
+            top: self.matches.get_one("top").copied(),
+            threshold: self.matches.get_one("probability").copied(),
+            kind,
+            window: self.matches.get_one("window").copied(),
+            backend,
+            budget: self.matches.get_one("dfa-size-limit").copied(),
+            zones,
             channels: self
                 .matches
                 .get_many::<String>("channel")
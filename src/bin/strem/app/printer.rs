@@ -9,6 +9,9 @@ use std::path::PathBuf;
 
 use strem::config::Configuration;
 use strem::datastream::frame::Frame;
+use strem::matcher::events::Event;
+use strem::matcher::record::Record;
+use strem::monitor::temporal::Verdict;
 
 #[cfg(feature = "export")]
 pub mod imager;
@@ -16,20 +19,33 @@ pub mod imager;
 pub struct Printer {}
 
 impl Printer {
-    /// Print a [`Match`].
-    pub fn print(frames: &[Frame], config: &Configuration) -> Result<(), Box<dyn Error>> {
+    /// Print a [`Record`] of a match.
+    pub fn print(
+        record: &Record,
+        frames: &[Frame],
+        config: &Configuration,
+    ) -> Result<(), Box<dyn Error>> {
         let prefix = if let Some(path) = config.datastream {
             path.display().to_string()
         } else {
             String::from("")
         };
 
-        println!(
-            "{}: {:?}..{:?}",
-            prefix,
-            frames.first().unwrap().index,
-            frames.last().unwrap().index + 1
-        );
+        if frames.is_empty() {
+            return Err(Box::new(PrinterError::from("empty match")));
+        }
+
+        let mut line = format!("{}: {:?}..{:?}", prefix, record.start, record.end);
+
+        if let Some(probability) = record.probability {
+            line.push_str(&format!(": {:.3}", probability));
+        }
+
+        if config.top.is_some() {
+            line.push_str(&format!(": {:.3}", record.score));
+        }
+
+        println!("{}", line);
 
         #[cfg(feature = "export")]
         if let Some(outdir) = config.export {
@@ -50,6 +66,55 @@ impl Printer {
 
         Ok(())
     }
+
+    /// Print an [`Event`] of an online search.
+    ///
+    /// A prefix spans the frames seen so far from its start.
+    pub fn notify(
+        event: &Event,
+        frames: &[Frame],
+        config: &Configuration,
+    ) -> Result<(), Box<dyn Error>> {
+        let prefix = if let Some(path) = config.datastream {
+            path.display().to_string()
+        } else {
+            String::from("")
+        };
+
+        let (first, last) = match (frames.first(), frames.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Err(Box::new(PrinterError::from("empty event"))),
+        };
+
+        let name = match event {
+            Event::PrefixStarted(..) => "started",
+            Event::MatchExtended(..) => "extended",
+            Event::MatchClosed(..) => "closed",
+        };
+
+        println!(
+            "{}: {} {:?}..{:?}",
+            prefix,
+            name,
+            first.index,
+            last.index + 1
+        );
+
+        Ok(())
+    }
+
+    /// Print the [`Verdict`] of an MTL formula at a frame.
+    pub fn verdict(verdict: &Verdict, config: &Configuration) -> Result<(), Box<dyn Error>> {
+        let prefix = if let Some(path) = config.datastream {
+            path.display().to_string()
+        } else {
+            String::from("")
+        };
+
+        println!("{}: {:?}: {}", prefix, verdict.index, verdict.holds);
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -27,6 +27,10 @@ impl Imager {
         for sample in frame.samples.iter() {
             match sample {
                 Sample::ObjectDetection(d) => self.detection(d, indir, outdir)?,
+
+                // A sample of cuboids, signals, or scenes is not captured on
+                // an image; so, there is nothing to draw on.
+                Sample::CuboidDetection(..) | Sample::Signal(..) | Sample::Scene(..) => continue,
             }
         }
 
@@ -93,10 +97,12 @@ impl Imager {
             _ => Rgb([102, 255, 0]),             // green
         };
 
-        let xmin = annotation.bbox.min.x as i32;
-        let ymin = annotation.bbox.min.y as i32;
-        let xmax = annotation.bbox.max.x as i32;
-        let ymax = annotation.bbox.max.y as i32;
+        let bbox = annotation.geometry.bounds();
+
+        let xmin = bbox.min.x as i32;
+        let ymin = bbox.min.y as i32;
+        let xmax = bbox.max.x as i32;
+        let ymax = bbox.max.y as i32;
 
         const THICKNESS: u32 = 5;
 
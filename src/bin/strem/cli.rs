@@ -59,6 +59,28 @@ pub fn build() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Use the online algorithm"),
         )
+        .arg(
+            Arg::new("events")
+                .long("events")
+                .action(ArgAction::SetTrue)
+                .requires("online")
+                .conflicts_with("match-kind")
+                .help("Report when a match may start, is found, and is final"),
+        )
+        .arg(
+            Arg::new("mtl")
+                .long("mtl")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["events", "match-kind", "max-count"])
+                .help("Interpret the pattern as an MTL formula and report a verdict per frame"),
+        )
+        .arg(
+            Arg::new("violations")
+                .long("violations")
+                .action(ArgAction::SetTrue)
+                .requires("mtl")
+                .help("Report each violation of an invariant (G) at the frame it occurs"),
+        )
         .arg(
             Arg::new("max-count")
                 .short('m')
@@ -67,6 +89,75 @@ pub fn build() -> Command {
                 .action(ArgAction::Set)
                 .value_parser(clap::value_parser!(usize))
                 .help("Stop searching after `NUM` matches found"),
+        )
+        .arg(
+            Arg::new("top")
+                .short('t')
+                .long("top")
+                .value_name("NUM")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with_all(["max-count", "events", "mtl"])
+                .help("Report the `NUM` matches with the highest scores, along with their scores"),
+        )
+        .arg(
+            Arg::new("probability")
+                .short('p')
+                .long("probability")
+                .value_name("THRESHOLD")
+                .action(ArgAction::Set)
+                .value_parser(self::probability)
+                .conflicts_with_all(["online", "mtl"])
+                .help("Treat detection scores as probabilities and report the matches with at least probability `THRESHOLD`"),
+        )
+        .arg(
+            Arg::new("match-kind")
+                .short('k')
+                .long("match-kind")
+                .value_name("KIND")
+                .action(ArgAction::Set)
+                .value_parser([
+                    "leftmost-longest",
+                    "leftmost-shortest",
+                    "all-overlapping",
+                    "all-ends",
+                ])
+                .help("The kind of matches to report"),
+        )
+        .arg(
+            Arg::new("window")
+                .short('w')
+                .long("window")
+                .value_name("NUM")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(usize))
+                .help("Keep at most `NUM` frames in memory when online"),
+        )
+        .arg(
+            Arg::new("backend")
+                .short('b')
+                .long("backend")
+                .value_name("BACKEND")
+                .action(ArgAction::Set)
+                .value_parser(["dense", "lazy", "counting"])
+                .help("The kind of DFA to build (default: dense, unless too large)"),
+        )
+        .arg(
+            Arg::new("dfa-size-limit")
+                .long("dfa-size-limit")
+                .value_name("BYTES")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(usize))
+                .help("Build a lazy DFA if a dense DFA exceeds `BYTES`"),
+        )
+        .arg(
+            Arg::new("zones")
+                .short('z')
+                .long("zones")
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("The zone definitions referenced by the pattern"),
         );
 
     #[cfg(feature = "export")]
@@ -82,3 +173,11 @@ pub fn build() -> Command {
 
     cmd
 }
+
+/// Parse a probability within (0, 1].
+fn probability(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(p) if p > 0.0 && p <= 1.0 => Ok(p),
+        _ => Err(format!("`{}` is not a probability within (0, 1]", s)),
+    }
+}
@@ -2,10 +2,12 @@
 //!
 
 use std::error::Error;
+use std::fmt;
 
 use crate::symbolizer::ast::SymbolicAbstractSyntaxTree;
 use crate::symbolizer::Symbolizer;
 
+use self::ir::ast::TemporalFormula;
 use self::lexer::stream::CharStream;
 use self::lexer::Lexer;
 use self::listener::ErrorListener;
@@ -37,18 +39,69 @@ impl Compiler {
     ///
     /// To compile, a string is expected. Therefore, any file
     /// handling/interfacing must be done beforehand and converted appropriately.
+    /// A malformed pattern is returned as a [`CompilerError`], rather than
+    /// exiting the process.
     pub fn compile(&self, source: &str) -> Result<SymbolicAbstractSyntaxTree, Box<dyn Error>> {
-        let stream = CharStream::from(source);
+        let ast = ErrorListener::recover(|| {
+            let stream = CharStream::from(source);
 
-        let mut lexer = Lexer::new(stream).attach(ErrorListener::new());
-        let stream = lexer.lex();
+            let mut lexer = Lexer::new(stream).attach(ErrorListener::recoverable());
+            let stream = lexer.lex();
 
-        let mut parser = Parser::new(stream).attach(ErrorListener::new());
-        let ast = parser.parse();
+            let mut parser = Parser::new(stream).attach(ErrorListener::recoverable());
+            parser.parse()
+        })?;
 
         let mut symbolizer = Symbolizer::new(&self::ALPHABET);
         let ast = symbolizer.symbolize(ast)?;
 
         Ok(ast)
     }
+
+    /// Compile a Metric Temporal Logic (MTL) formula into a [`TemporalFormula`].
+    ///
+    /// Unlike a SpRE, the spatial formulas of an MTL formula are not symbolized,
+    /// as each is evaluated at each frame by the monitor, instead.
+    pub fn compile_temporal(&self, source: &str) -> Result<TemporalFormula, Box<dyn Error>> {
+        let formula = ErrorListener::recover(|| {
+            let stream = CharStream::from(source);
+
+            let mut lexer = Lexer::new(stream).attach(ErrorListener::recoverable());
+            let stream = lexer.lex();
+
+            let mut parser = Parser::new(stream).attach(ErrorListener::recoverable());
+            parser.parse_temporal()
+        })?;
+
+        Ok(formula)
+    }
+}
+
+/// A syntax error of a pattern (or formula) reported by the [`Lexer`] or the
+/// [`Parser`].
+#[derive(Debug, Clone)]
+pub struct CompilerError {
+    msg: String,
+}
+
+impl From<&str> for CompilerError {
+    fn from(msg: &str) -> Self {
+        CompilerError {
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl From<String> for CompilerError {
+    fn from(msg: String) -> Self {
+        CompilerError { msg }
+    }
 }
+
+impl fmt::Display for CompilerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "compiler: {}", self.msg)
+    }
+}
+
+impl Error for CompilerError {}
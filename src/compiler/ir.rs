@@ -4,7 +4,7 @@
 pub mod ast;
 
 /// Range operator kinds.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum RangeKind {
     Exactly(usize),
     AtLeast(usize),
@@ -12,12 +12,22 @@ pub enum RangeKind {
 }
 
 /// The set of Regular Expression operations allowed in a query.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum RegexOperatorKind {
     KleeneStar,
     Concatenation,
     Alternation,
     Range(RangeKind),
+
+    /// A sub-pattern where up to a number of frames may violate the spatial
+    /// formula expected of them (e.g., `(...)~2`).
+    Approximate(usize),
+
+    /// The frames that match both sub-patterns (e.g., `... && ...`).
+    Intersection,
+
+    /// The frames that do not match a sub-pattern (e.g., `~(...)`).
+    Complement,
 }
 
 /// The set of spatial operations allowed against a frame.
@@ -27,19 +37,20 @@ pub enum RegexOperatorKind {
 /// non-spatial expressions (e.g., alternation and disjunction). Therefore,
 /// these enumerations provide semantic meaning for symbolically
 /// equivalent operators.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum SpatialOperatorKind {
     FolOperator(FolOperatorKind),
     SolOperator(SolOperatorKind),
     S4uOperator(S4uOperatorKind),
     S4Operator(S4OperatorKind),
+    GeometricOperator(GeometricOperatorKind),
 }
 
 /// First-Order Logic operators.
 ///
 /// For more information on FOL, please see:
 /// [Stanford Encyclopedia of Philosophy: Classical Logic](https://plato.stanford.edu/entries/logic-classical/)
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum FolOperatorKind {
     Negation,
     Conjunction,
@@ -50,7 +61,7 @@ pub enum FolOperatorKind {
 ///
 /// For more information on SOL, please see:
 /// [Stanford Encyclopedia of Philosophy: Second-order and Higher-order logic](https://plato.stanford.edu/entries/logic-higher-order/)
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum SolOperatorKind {
     Exists,
 }
@@ -59,7 +70,7 @@ pub enum SolOperatorKind {
 ///
 /// For more information on S4, please see:
 /// [Combining Spatial and Temporal Logics: Expressiveness vs. Complexity](https://arxiv.org/abs/1)
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum S4uOperatorKind {
     NonEmpty,
 }
@@ -68,25 +79,243 @@ pub enum S4uOperatorKind {
 ///
 /// For more information on S4, please see:
 /// [Combining Spatial and Temporal Logics: Expressiveness vs. Complexity](https://arxiv.org/abs/1110.2726)
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum S4OperatorKind {
     Intersection,
     Union,
     Complement,
 }
 
+/// Geometric operators.
+///
+/// These operators compare geometric properties of regions (e.g., size,
+/// position, and distance) against a [`Comparison`].
+#[derive(Clone, Debug)]
+pub enum GeometricOperatorKind {
+    Measure(MeasureKind, Comparison),
+    Distance(DistanceKind, Comparison),
+}
+
+/// Measurable properties of a region.
+#[derive(Clone, Debug)]
+pub enum MeasureKind {
+    Width,
+    Height,
+    Area,
+    Aspect,
+    CenterX,
+    CenterY,
+}
+
+/// Distance metrics between regions.
+#[derive(Clone, Debug)]
+pub enum DistanceKind {
+    Edge,
+    Center,
+}
+
+/// Comparison operator kinds.
+#[derive(Clone, Debug)]
+pub enum ComparisonKind {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+/// The units a comparison value is expressed in.
+///
+/// A [`UnitKind::Normalized`] value is relative to the dimensions of the image
+/// (e.g., a width of 0.5 is half the width of the image). Otherwise, a
+/// [`UnitKind::Pixel`] value is taken as is (e.g., meters of a 3D detection or
+/// the raw value of a signal).
+#[derive(Clone, Debug)]
+pub enum UnitKind {
+    Pixel,
+    Normalized,
+}
+
+/// A comparison against a constant value (e.g., `> 100`).
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub kind: ComparisonKind,
+    pub value: f64,
+    pub unit: UnitKind,
+}
+
+impl Comparison {
+    /// Create a new [`Comparison`].
+    pub fn new(kind: ComparisonKind, value: f64, unit: UnitKind) -> Self {
+        Comparison { kind, value, unit }
+    }
+
+    /// Check whether the `value` provided satisfies the [`Comparison`].
+    pub fn test(&self, value: f64) -> bool {
+        match self.kind {
+            ComparisonKind::Less => value < self.value,
+            ComparisonKind::LessEqual => value <= self.value,
+            ComparisonKind::Greater => value > self.value,
+            ComparisonKind::GreaterEqual => value >= self.value,
+        }
+    }
+
+    /// The margin by which the `value` provided satisfies the [`Comparison`].
+    ///
+    /// This is positive if the [`Comparison`] is satisfied, and negative if it
+    /// is not (e.g., a `value` of 120 satisfies `> 100` by 20).
+    pub fn margin(&self, value: f64) -> f64 {
+        match self.kind {
+            ComparisonKind::Less | ComparisonKind::LessEqual => self.value - value,
+            ComparisonKind::Greater | ComparisonKind::GreaterEqual => value - self.value,
+        }
+    }
+
+    /// The degree to which the `value` provided satisfies the [`Comparison`].
+    ///
+    /// This is the margin relative to the magnitudes of the `value` and of the
+    /// bound (i.e., between -1 and 1), so that it is comparable to a score (e.g.,
+    /// a `value` of 120 satisfies `> 100` by 20 of 120). It is non-negative if,
+    /// and only if, the [`Comparison`] is satisfied; so, a `value` equal to the
+    /// bound of a strict comparison has a (slightly) negative degree.
+    pub fn degree(&self, value: f64) -> f64 {
+        let margin = self.margin(value);
+        let scale = value.abs().max(self.value.abs());
+
+        // An undefined degree (e.g., of an infinite value) is only its sign.
+        let degree = match scale > 0.0 {
+            true => margin / scale,
+            false => margin,
+        };
+        let degree = if degree.is_nan() { 0.0 } else { degree };
+
+        match self.test(value) {
+            true => degree.clamp(0.0, 1.0),
+            false => degree.clamp(-1.0, -f64::MIN_POSITIVE),
+        }
+    }
+}
+
+/// Temporal filters over the truth of a spatial formula across frames.
+///
+/// Unlike a spatial operator, a filter is not evaluated against a single frame.
+/// Instead, it smooths the sequence of truth values of its formula (e.g., to
+/// suppress detector flicker) before the frames are matched.
+#[derive(Clone, Debug)]
+pub enum FilterOperatorKind {
+    /// Satisfied if the formula holds in at least `k` of the last `n` frames
+    /// (i.e., `KOfN(k, n)`).
+    KOfN(usize, usize),
+
+    /// Satisfied if the formula holds, or held within the last `n` frames
+    /// (i.e., it is held for `n` frames after it last held).
+    Hold(usize),
+
+    /// Satisfied if the formula holds in at least a percentage of the frames
+    /// within a window, either of the last number of frames or of the last
+    /// number of seconds (i.e., `Frequency(percentage, window, unit)`).
+    Frequency(f64, f64, TimeUnitKind),
+}
+
+/// The units of the bounds of a [`TemporalInterval`].
+#[derive(Clone, Debug)]
+pub enum TimeUnitKind {
+    /// A number of frames (e.g., `[0,5]`).
+    Frames,
+
+    /// A number of seconds between the timestamps of frames (e.g., `[0,2s]`).
+    Seconds,
+}
+
+/// The bounds of a temporal operator, relative to the frame it is evaluated at.
+///
+/// If `max` is `None`, then the interval is unbounded (e.g., `[2,]`).
+#[derive(Clone, Debug)]
+pub struct TemporalInterval {
+    pub min: f64,
+    pub max: Option<f64>,
+    pub unit: TimeUnitKind,
+}
+
+impl TemporalInterval {
+    /// Create a new [`TemporalInterval`].
+    pub fn new(min: f64, max: Option<f64>, unit: TimeUnitKind) -> Self {
+        TemporalInterval { min, max, unit }
+    }
+
+    /// Create a new unbounded [`TemporalInterval`] (i.e., `[0,]`).
+    pub fn unbounded() -> Self {
+        TemporalInterval::new(0.0, None, TimeUnitKind::Frames)
+    }
+
+    /// Check whether the [`TemporalInterval`] is unbounded (i.e., `[0,]`).
+    pub fn is_unbounded(&self) -> bool {
+        self.min <= 0.0 && self.max.is_none()
+    }
+
+    /// Check whether a distance (in the unit of the interval) is within it.
+    pub fn contains(&self, distance: f64) -> bool {
+        self.min <= distance && self.max.is_none_or(|max| distance <= max)
+    }
+
+    /// Check whether a distance (in the unit of the interval) is past it.
+    pub fn exceeds(&self, distance: f64) -> bool {
+        self.max.is_some_and(|max| distance > max)
+    }
+}
+
+/// Metric Temporal Logic (MTL) operators, including past-time operators.
+///
+/// Unlike a [`RegexOperatorKind`], these operators are evaluated at each frame
+/// of a stream (i.e., a verdict per frame) over a formula whose operands are
+/// spatial formulas.
+#[derive(Clone, Debug)]
+pub enum TemporalOperatorKind {
+    Negation,
+    Conjunction,
+    Disjunction,
+    Implication,
+
+    /// The formula holds at the next frame (i.e., `X`).
+    Next,
+
+    /// The formula holds at some frame within the interval (i.e., `F`).
+    Eventually(TemporalInterval),
+
+    /// The formula holds at every frame within the interval (i.e., `G`).
+    Globally(TemporalInterval),
+
+    /// The right formula holds at some frame within the interval, and the left
+    /// holds until then (i.e., `U`).
+    Until(TemporalInterval),
+
+    /// The formula held at the previous frame (i.e., `Y`).
+    Previous,
+
+    /// The formula held at some frame within the interval (i.e., `O`).
+    Once(TemporalInterval),
+
+    /// The formula held at every frame within the interval (i.e., `H`).
+    Historically(TemporalInterval),
+
+    /// The right formula held at some frame within the interval, and the left
+    /// has held since (i.e., `S`).
+    Since(TemporalInterval),
+}
+
 /// Operations kinds supported.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Operator {
     RegexOperator(RegexOperatorKind),
     SpatialOperator(SpatialOperatorKind),
+    FilterOperator(FilterOperatorKind),
+    TemporalOperator(TemporalOperatorKind),
 }
 
 /// Generic representation of an AST.
 ///
 /// This AST is used as an Intermediate Representation (IR) of expressions that
 /// support unary and binary operator expressions.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Node<T> {
     Operand(T),
     UnaryExpr {
@@ -128,4 +357,18 @@ impl<T> Node<T> {
             right: Box::new(right.into()),
         }
     }
+
+    /// Collect the operands of the [`Node`], from left to right.
+    pub fn operands(&self) -> Vec<&T> {
+        match self {
+            Node::Operand(operand) => vec![operand],
+            Node::UnaryExpr { child, .. } => child.operands(),
+            Node::BinaryExpr { left, right, .. } => {
+                let mut operands = left.operands();
+                operands.extend(right.operands());
+
+                operands
+            }
+        }
+    }
 }
@@ -1,11 +1,35 @@
 //! Abstract Syntax Tree (AST) representation.
 //!
 
-use super::super::ir::Node;
+use super::super::ir::{Comparison, Node};
 
-pub type SpatialFormula = Node<String>;
+/// An atomic operand of a [`SpatialFormula`].
+#[derive(Clone, Debug)]
+pub enum Atom {
+    /// A class of object (e.g., `[:car:]`).
+    Class(String),
 
-#[derive(Debug)]
+    /// A named zone of the image (e.g., `@crosswalk`).
+    Zone(String),
+
+    /// A tag of the scene of the frame (e.g., `[:@rain:]`), optionally with a
+    /// comparison against its score (e.g., `[:@rain:] > 0.5`).
+    Tag(String, Option<Comparison>),
+
+    /// A comparison against a named signal of the frame (e.g., `<signal
+    /// speed> > 10`), optionally indexing a component of a vector signal.
+    Signal(String, Option<usize>, Comparison),
+
+    /// Any frame (i.e., `.`), which always holds.
+    Any,
+}
+
+pub type SpatialFormula = Node<Atom>;
+
+/// A Metric Temporal Logic (MTL) formula over spatial formulas.
+pub type TemporalFormula = Node<SpatialFormula>;
+
+#[derive(Clone, Debug)]
 pub struct AbstractSyntaxTree {
     pub root: Option<Node<SpatialFormula>>,
 }
@@ -94,14 +94,18 @@ impl Lexer {
             '[' => Ok(self.tokenize(LeftBracket)),
             ']' => Ok(self.tokenize(RightBracket)),
             '<' => Ok(self.functionify(LeftChevron)),
-            '>' => Ok(self.tokenize(RightChevron)),
+            '>' => Ok(self.equalify(RightChevron, RightChevronEqual)),
             ',' => Ok(self.tokenize(Comma)),
             ':' => Ok(self.tokenize(Colon)),
+            '@' => Ok(self.tokenize(At)),
             '*' => Ok(self.tokenize(Star)),
             '%' => Ok(self.tokenize(Percent)),
+            '~' => Ok(self.tokenize(Tilde)),
+            '-' => Ok(self.doublify('>', Minus, Arrow)),
             '!' => Ok(self.tokenize(Not)),
-            '&' => Ok(self.tokenize(And)),
+            '&' => Ok(self.doublify('&', And, DoubleAnd)),
             '|' => Ok(self.tokenize(Or)),
+            '.' => Ok(self.tokenize(Dot)),
             '\n' => Ok(self.newline()),
             ' ' | '\r' | '\t' => Ok(self.skip(0)),
             '0'..='9' => Ok(self.numberify()),
@@ -133,13 +137,43 @@ impl Lexer {
     /// Advance the [`current`](Lexer::current), capturing the function.
     ///
     /// This method is used for identifying functions that are bounded with a
-    /// left and right chevron (e.g., `<FUNCTION-NAME>`).
+    /// left and right chevron (e.g., `<FUNCTION-NAME>`). A function that takes
+    /// arguments within the chevrons (e.g., `<signal speed>` or `<hold 3>`)
+    /// leaves them, and the closing chevron, to be tokenized separately.
     fn functionify(&mut self, kind: TokenKind) -> Option<Token> {
         if let Some('a'..='z' | 'A'..='Z' | '_') = self.peek(0) {
             let name = self.identifierify().unwrap().lexeme;
+            let function = self.functionit(name).unwrap();
+
+            if !matches!(function, Signal | KOfN | Hold | Frequency) {
+                self.advance();
+            }
+
+            self.tokenize(function)
+        } else {
+            self.equalify(kind, LeftChevronEqual)
+        }
+    }
+
+    /// Advance the [`current`](Lexer::current), capturing a trailing equal sign.
+    ///
+    /// This method is used for identifying comparison operators that may be
+    /// followed by an equal sign (e.g., `>` and `>=`).
+    fn equalify(&mut self, kind: TokenKind, equal: TokenKind) -> Option<Token> {
+        if let Some('=') = self.peek(0) {
             self.advance();
+            self.tokenize(equal)
+        } else {
+            self.tokenize(kind)
+        }
+    }
 
-            self.tokenize(self.functionit(name).unwrap())
+    /// Build a token of a single character (i.e., `kind`), or of two characters
+    /// (i.e., `double`), if followed by the `next` character (e.g., `&&`).
+    fn doublify(&mut self, next: char, kind: TokenKind, double: TokenKind) -> Option<Token> {
+        if Some(next) == self.peek(0) {
+            self.advance();
+            self.tokenize(double)
         } else {
             self.tokenize(kind)
         }
@@ -151,6 +185,18 @@ impl Lexer {
     fn functionit(&self, name: String) -> Option<TokenKind> {
         match &name[1..] {
             "nonempty" => Some(NonEmpty),
+            "width" => Some(Width),
+            "height" => Some(Height),
+            "area" => Some(Area),
+            "aspect" => Some(Aspect),
+            "cx" => Some(CenterX),
+            "cy" => Some(CenterY),
+            "dist" => Some(Distance),
+            "cdist" => Some(CenterDistance),
+            "signal" => Some(Signal),
+            "kofn" => Some(KOfN),
+            "hold" => Some(Hold),
+            "freq" => Some(Frequency),
             _ => match &self.listener {
                 Some(listener) => {
                     listener.exit(format!("lexer: `{}` function not supported.", name), 1);
@@ -316,4 +362,79 @@ mod tests {
             lexer.next().ok().unwrap().unwrap()
         );
     }
+
+    #[test]
+    fn lex_signals() {
+        let mut lexer = Lexer::new(CharStream::from("<signal speed> > -1"));
+        let kinds: Vec<TokenKind> = lexer.lex().buffer.into_iter().map(|t| t.kind).collect();
+
+        assert_eq!(
+            vec![
+                TokenKind::Signal,
+                TokenKind::Identifier,
+                TokenKind::RightChevron,
+                TokenKind::RightChevron,
+                TokenKind::Minus,
+                TokenKind::Integer,
+                TokenKind::EndOfFile,
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn lex_filters() {
+        let mut lexer = Lexer::new(CharStream::from("<freq 80% 2.5s>"));
+        let kinds: Vec<TokenKind> = lexer.lex().buffer.into_iter().map(|t| t.kind).collect();
+
+        assert_eq!(
+            vec![
+                TokenKind::Frequency,
+                TokenKind::Integer,
+                TokenKind::Percent,
+                TokenKind::Real,
+                TokenKind::Identifier,
+                TokenKind::RightChevron,
+                TokenKind::EndOfFile,
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn lex_comparisons() {
+        let mut lexer = Lexer::new(CharStream::from("< <= > >="));
+        let kinds: Vec<TokenKind> = lexer.lex().buffer.into_iter().map(|t| t.kind).collect();
+
+        assert_eq!(
+            vec![
+                TokenKind::LeftChevron,
+                TokenKind::LeftChevronEqual,
+                TokenKind::RightChevron,
+                TokenKind::RightChevronEqual,
+                TokenKind::EndOfFile,
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn lex_booleans() {
+        let mut lexer = Lexer::new(CharStream::from("& && ~(.) ->"));
+        let kinds: Vec<TokenKind> = lexer.lex().buffer.into_iter().map(|t| t.kind).collect();
+
+        assert_eq!(
+            vec![
+                TokenKind::And,
+                TokenKind::DoubleAnd,
+                TokenKind::Tilde,
+                TokenKind::LeftParen,
+                TokenKind::Dot,
+                TokenKind::RightParen,
+                TokenKind::Arrow,
+                TokenKind::EndOfFile,
+            ],
+            kinds
+        );
+    }
 }
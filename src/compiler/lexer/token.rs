@@ -11,18 +11,38 @@ pub enum TokenKind {
     RightBracket,
     LeftChevron,
     RightChevron,
+    LeftChevronEqual,
+    RightChevronEqual,
     Comma,
     Colon,
+    At,
     Star,
     Percent,
+    Tilde,
+    Minus,
+    Arrow,
     Not,
     And,
+    DoubleAnd,
     Or,
+    Dot,
     EndOfFile,
     Integer,
     Real,
     Identifier,
     NonEmpty,
+    Width,
+    Height,
+    Area,
+    Aspect,
+    CenterX,
+    CenterY,
+    Distance,
+    CenterDistance,
+    Signal,
+    KOfN,
+    Hold,
+    Frequency,
 }
 
 /// Locational information used in a [`Token`].
@@ -3,8 +3,11 @@
 //! This error listener is contextualized for the [compiler](../index.html). Therefore, its
 //! usage outside the scope of this is not tested.
 
+use std::panic;
 use std::process;
 
+use super::CompilerError;
+
 /// Interface to handle errors.
 ///
 /// This is a general error listener that can be attached to any process that
@@ -12,11 +15,34 @@ use std::process;
 /// and better support, a particular error listener should be implemented and
 /// attached to the process, accordingly.
 #[derive(Default)]
-pub struct ErrorListener {}
+pub struct ErrorListener {
+    /// Whether a fatal error unwinds to the caller, instead of exiting.
+    recoverable: bool,
+}
 
 impl ErrorListener {
     pub fn new() -> Self {
-        ErrorListener {}
+        ErrorListener { recoverable: false }
+    }
+
+    /// Create a new [`ErrorListener`] whose fatal errors unwind (as a
+    /// [`CompilerError`]) instead of exiting the process, so that they may be
+    /// recovered (see [`ErrorListener::recover`]).
+    pub fn recoverable() -> Self {
+        ErrorListener { recoverable: true }
+    }
+
+    /// Run a process with recoverable listeners attached, returning its fatal
+    /// error (if any) instead.
+    ///
+    /// Any other panic of the process is not recovered.
+    pub fn recover<T>(process: impl FnOnce() -> T) -> Result<T, CompilerError> {
+        panic::catch_unwind(panic::AssertUnwindSafe(process)).map_err(|payload| match payload
+            .downcast::<CompilerError>(
+        ) {
+            Ok(e) => *e,
+            Err(payload) => panic::resume_unwind(payload),
+        })
     }
 
     /// Print an error to stderr.
@@ -28,8 +54,13 @@ impl ErrorListener {
     ///
     /// The error printed is simply a message that is passed to this function
     /// along with the specified error code. Therefore, there is not much
-    /// restriction to this call.
+    /// restriction to this call. If the [`ErrorListener`] is recoverable, then
+    /// the error unwinds instead (without the panic hook).
     pub fn exit(&self, e: String, code: i32) {
+        if self.recoverable {
+            panic::resume_unwind(Box::new(CompilerError::from(e)));
+        }
+
         eprintln!("listener: fatal: {}", e);
         process::exit(code);
     }
@@ -3,10 +3,12 @@
 //! Currently, the parser is manually implemented from a Context-Free Grammar
 //! (CFG) definition. For grammar details, see relevant function documentation.
 
-use super::ir::ast::{AbstractSyntaxTree, SpatialFormula};
+use super::ir::ast::{AbstractSyntaxTree, Atom, SpatialFormula, TemporalFormula};
 use super::ir::{
-    FolOperatorKind, Node, Operator, RangeKind, RegexOperatorKind, S4OperatorKind, S4uOperatorKind,
-    SpatialOperatorKind,
+    Comparison, ComparisonKind, DistanceKind, FilterOperatorKind, FolOperatorKind,
+    GeometricOperatorKind, MeasureKind, Node, Operator, RangeKind, RegexOperatorKind,
+    S4OperatorKind, S4uOperatorKind, SpatialOperatorKind, TemporalInterval, TemporalOperatorKind,
+    TimeUnitKind, UnitKind,
 };
 use super::lexer::stream::TokenStream;
 use super::lexer::token::{Token, TokenKind, TokenKind::*};
@@ -131,13 +133,20 @@ impl Parser {
     ///
     /// ```text
     /// phi ::= '(' phi ')' | phi '*' | phi phi | phi '|' phi | phi range
-    ///       | '[' pi ']'
+    ///       | phi '~' Integer | phi '&&' phi | '~' '(' phi ')' | '.'
+    ///       | '[' pi ']' | '[' filter pi ']'
     /// ```
     ///
+    /// A `phi '~' Integer` matches `phi` where up to `Integer` frames violate
+    /// their spatial formula (i.e., approximately).
+    ///
     /// Note: The following symbol(s) have a different semantic meaning derived
     /// at parse time:
     ///
     /// `|`: Alternation
+    /// `&&`: Intersection
+    /// `~`: Complement (if followed by `(`)
+    /// `.`: Any frame
     fn parse_spre(&mut self) -> Option<Node<SpatialFormula>> {
         let mut node = None;
 
@@ -148,12 +157,31 @@ impl Parser {
                     node = self.parse_spre();
                     self.expect(RightParen);
                 }
+                Tilde => {
+                    self.expect(Tilde);
+                    self.expect(LeftParen);
+                    let child = self.parse_spre();
+                    self.expect(RightParen);
+
+                    node = Some(Node::unary(
+                        Operator::RegexOperator(RegexOperatorKind::Complement),
+                        child.unwrap(),
+                    ));
+                }
+                Dot => {
+                    self.expect(Dot);
+                    node = Some(Node::from(Node::from(Atom::Any)));
+                }
                 LeftBracket => {
                     self.expect(LeftBracket);
-                    let tree = self.parse_s4u();
+                    let filter = self.parse_filter();
+                    let tree = self.parse_s4u().unwrap();
                     self.expect(RightBracket);
 
-                    node = Some(Node::from(tree.unwrap()));
+                    node = Some(Node::from(match filter {
+                        Some(kind) => Node::unary(Operator::FilterOperator(kind), tree),
+                        None => tree,
+                    }));
                 }
                 _ => self.error(),
             }
@@ -171,8 +199,11 @@ impl Parser {
                         ));
                     }
 
-                    // concatenation
-                    LeftParen | LeftBracket => {
+                    // concatenation (a `~` followed by `(` is a complement)
+                    LeftParen | LeftBracket | Dot | Tilde
+                        if token.kind != Tilde
+                            || self.peek(2).is_some_and(|token| token.kind == LeftParen) =>
+                    {
                         let right = self.parse_spre();
                         node = Some(Node::binary(
                             Operator::RegexOperator(RegexOperatorKind::Concatenation),
@@ -193,6 +224,18 @@ impl Parser {
                         ))
                     }
 
+                    // intersection
+                    DoubleAnd => {
+                        self.expect(DoubleAnd);
+
+                        let right = self.parse_spre();
+                        node = Some(Node::binary(
+                            Operator::RegexOperator(RegexOperatorKind::Intersection),
+                            node.unwrap(),
+                            right.unwrap(),
+                        ))
+                    }
+
                     // range
                     LeftBrace => {
                         let range = self.parse_range();
@@ -202,6 +245,17 @@ impl Parser {
                         ));
                     }
 
+                    // approximation
+                    Tilde => {
+                        self.expect(Tilde);
+
+                        let budget = self.expect(Integer).lexeme.parse().unwrap();
+                        node = Some(Node::unary(
+                            Operator::RegexOperator(RegexOperatorKind::Approximate(budget)),
+                            node.unwrap(),
+                        ));
+                    }
+
                     _ => break,
                 }
             } else {
@@ -212,15 +266,348 @@ impl Parser {
         node
     }
 
+    /// Parse the [`TokenStream`] according to the MTL grammar.
+    ///
+    /// This method parses the initialized [`TokenStream`] as a Metric Temporal
+    /// Logic (MTL) formula whose operands are spatial formulas, instead of a
+    /// SpRE (see [`Parser::parse_mtl`]).
+    pub fn parse_temporal(&mut self) -> TemporalFormula {
+        let root = self.parse_mtl();
+        self.expect(EndOfFile);
+
+        root.unwrap()
+    }
+
+    /// Parse a Metric Temporal Logic (MTL) expression.
+    ///
+    /// This parse function captures the following grammar (from the lowest to
+    /// the highest precedence):
+    ///
+    /// ```text
+    /// psi ::= '(' psi ')' | psi '->' psi | psi '|' psi | psi '&' psi
+    ///       | psi 'U' interval? psi | psi 'S' interval? psi | '!' psi
+    ///       | 'X' psi | 'Y' psi | 'F' interval? psi | 'G' interval? psi
+    ///       | 'O' interval? psi | 'H' interval? psi
+    ///       | '[' pi ']' | '[' filter pi ']' | Identifier
+    /// ```
+    ///
+    /// A bare `Identifier` is shorthand for the class `'[' '[' ':' Identifier
+    /// ':' ']' ']'` (e.g., `pedestrian`). The `->`, `U`, and `S` operators
+    /// associate to the right.
+    ///
+    /// Note: The following symbol(s) have a different semantic meaning derived
+    /// at parse time:
+    ///
+    /// `!`: Negation
+    /// `&`: Conjunction
+    /// `|`: Disjunction
+    fn parse_mtl(&mut self) -> Option<TemporalFormula> {
+        let node = self.parse_mtl_disjunction();
+
+        if let Some(Token { kind: Arrow, .. }) = self.peek(1) {
+            self.expect(Arrow);
+
+            let right = self.parse_mtl();
+            return Some(Node::binary(
+                Operator::TemporalOperator(TemporalOperatorKind::Implication),
+                node.unwrap(),
+                right.unwrap(),
+            ));
+        }
+
+        node
+    }
+
+    /// Parse an MTL disjunction (see [`Parser::parse_mtl`]).
+    fn parse_mtl_disjunction(&mut self) -> Option<TemporalFormula> {
+        let mut node = self.parse_mtl_conjunction();
+
+        while let Some(Token { kind: Or, .. }) = self.peek(1) {
+            self.expect(Or);
+
+            let right = self.parse_mtl_conjunction();
+            node = Some(Node::binary(
+                Operator::TemporalOperator(TemporalOperatorKind::Disjunction),
+                node.unwrap(),
+                right.unwrap(),
+            ));
+        }
+
+        node
+    }
+
+    /// Parse an MTL conjunction (see [`Parser::parse_mtl`]).
+    fn parse_mtl_conjunction(&mut self) -> Option<TemporalFormula> {
+        let mut node = self.parse_mtl_until();
+
+        while let Some(Token { kind: And, .. }) = self.peek(1) {
+            self.expect(And);
+
+            let right = self.parse_mtl_until();
+            node = Some(Node::binary(
+                Operator::TemporalOperator(TemporalOperatorKind::Conjunction),
+                node.unwrap(),
+                right.unwrap(),
+            ));
+        }
+
+        node
+    }
+
+    /// Parse an MTL until or since (see [`Parser::parse_mtl`]).
+    fn parse_mtl_until(&mut self) -> Option<TemporalFormula> {
+        let node = self.parse_mtl_unary();
+
+        let keyword = match self.peek(1) {
+            Some(Token {
+                kind: Identifier,
+                lexeme,
+                ..
+            }) if lexeme == "U" || lexeme == "S" => lexeme.clone(),
+            _ => return node,
+        };
+
+        self.expect(Identifier);
+        let interval = self.parse_interval();
+
+        let kind = match keyword.as_str() {
+            "U" => TemporalOperatorKind::Until(interval),
+            _ => TemporalOperatorKind::Since(interval),
+        };
+
+        let right = self.parse_mtl_until();
+        Some(Node::binary(
+            Operator::TemporalOperator(kind),
+            node.unwrap(),
+            right.unwrap(),
+        ))
+    }
+
+    /// Parse a unary MTL expression or operand (see [`Parser::parse_mtl`]).
+    fn parse_mtl_unary(&mut self) -> Option<TemporalFormula> {
+        let token = match self.peek(1) {
+            Some(token) => token.clone(),
+            None => {
+                self.error();
+                return None;
+            }
+        };
+
+        match token.kind {
+            LeftParen => {
+                self.expect(LeftParen);
+                let node = self.parse_mtl();
+                self.expect(RightParen);
+
+                node
+            }
+            Not => {
+                self.expect(Not);
+
+                let child = self.parse_mtl_unary();
+                Some(Node::unary(
+                    Operator::TemporalOperator(TemporalOperatorKind::Negation),
+                    child.unwrap(),
+                ))
+            }
+            LeftBracket => {
+                self.expect(LeftBracket);
+                let filter = self.parse_filter();
+                let tree = self.parse_s4u().unwrap();
+                self.expect(RightBracket);
+
+                Some(Node::from(match filter {
+                    Some(kind) => Node::unary(Operator::FilterOperator(kind), tree),
+                    None => tree,
+                }))
+            }
+            Identifier => {
+                self.expect(Identifier);
+
+                let kind = match token.lexeme.as_str() {
+                    "X" => TemporalOperatorKind::Next,
+                    "Y" => TemporalOperatorKind::Previous,
+                    "F" => TemporalOperatorKind::Eventually(self.parse_interval()),
+                    "G" => TemporalOperatorKind::Globally(self.parse_interval()),
+                    "O" => TemporalOperatorKind::Once(self.parse_interval()),
+                    "H" => TemporalOperatorKind::Historically(self.parse_interval()),
+                    _ => return Some(Node::from(Node::from(Atom::Class(token.lexeme)))),
+                };
+
+                let child = self.parse_mtl_unary();
+                Some(Node::unary(
+                    Operator::TemporalOperator(kind),
+                    child.unwrap(),
+                ))
+            }
+            _ => {
+                self.error();
+                None
+            }
+        }
+    }
+
+    /// Parse the interval of an MTL operator, if any.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// interval ::= '[' bound ',' ']' | '[' bound ',' bound ']'
+    /// bound    ::= Integer | Real | Integer 's' | Real 's'
+    /// ```
+    ///
+    /// A bound followed by `s` is in seconds, instead of frames, in which case
+    /// both bounds must be (e.g., `[1s,2s]`, but not `[1,2s]`), unless the
+    /// lower bound is zero. If the interval is omitted, then it is unbounded
+    /// (i.e., `[0,]`).
+    fn parse_interval(&mut self) -> TemporalInterval {
+        // An interval is distinguished from an operand by its number.
+        match (self.peek(1), self.peek(2)) {
+            (
+                Some(Token {
+                    kind: LeftBracket, ..
+                }),
+                Some(Token {
+                    kind: Integer | Real,
+                    ..
+                }),
+            ) => (),
+            _ => return TemporalInterval::unbounded(),
+        }
+
+        self.expect(LeftBracket);
+        let (min, mut seconds) = self.parse_bound();
+        self.expect(Comma);
+
+        let max = match self.peek(1) {
+            Some(Token {
+                kind: RightBracket, ..
+            }) => None,
+            _ => {
+                let (max, unit) = self.parse_bound();
+
+                // Frames and seconds are not comparable; so, both bounds must
+                // be in the same unit. A lower bound of zero is the same in
+                // either, so it may be left without one (e.g., `[0,2s]`).
+                match (seconds, unit) {
+                    (false, true) if min == 0.0 => seconds = true,
+                    (lower, upper) if lower != upper => self.error(),
+                    _ => (),
+                }
+
+                if max < min {
+                    self.error();
+                }
+
+                Some(max)
+            }
+        };
+
+        self.expect(RightBracket);
+
+        let unit = match seconds {
+            true => TimeUnitKind::Seconds,
+            false => TimeUnitKind::Frames,
+        };
+
+        TemporalInterval::new(min, max, unit)
+    }
+
+    /// Parse a bound of an MTL interval, and whether it is in seconds.
+    fn parse_bound(&mut self) -> (f64, bool) {
+        let value = match self.peek(1) {
+            Some(Token { kind: Real, .. }) => self.expect(Real),
+            _ => self.expect(Integer),
+        };
+
+        let seconds = matches!(
+            self.peek(1),
+            Some(Token { kind: Identifier, lexeme, .. }) if lexeme == "s"
+        );
+
+        if seconds {
+            self.expect(Identifier);
+        }
+
+        (value.lexeme.parse().unwrap(), seconds)
+    }
+
+    /// Parse a temporal filter of a spatial formula, if any.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// filter ::= '<kofn' Integer Integer '>' | '<hold' Integer '>'
+    ///          | '<freq' Number '%' bound '>'
+    /// ```
+    ///
+    /// A filter applies to the entire spatial formula that follows it within
+    /// the `[]` enclosures.
+    fn parse_filter(&mut self) -> Option<FilterOperatorKind> {
+        let kind = match self.peek(1).map(|token| &token.kind) {
+            Some(KOfN) => {
+                self.expect(KOfN);
+                let k = self.expect(Integer).lexeme.parse().unwrap();
+                let n = self.expect(Integer).lexeme.parse().unwrap();
+
+                // The formula cannot hold in more frames than the window.
+                if k > n || n == 0 {
+                    self.error();
+                }
+
+                FilterOperatorKind::KOfN(k, n)
+            }
+            Some(Hold) => {
+                self.expect(Hold);
+                FilterOperatorKind::Hold(self.expect(Integer).lexeme.parse().unwrap())
+            }
+            Some(Frequency) => {
+                self.expect(Frequency);
+
+                let percentage = match self.peek(1) {
+                    Some(Token { kind: Real, .. }) => self.expect(Real),
+                    _ => self.expect(Integer),
+                };
+                let percentage: f64 = percentage.lexeme.parse().unwrap();
+                self.expect(Percent);
+
+                let (window, seconds) = self.parse_bound();
+
+                // A window of frames must hold a whole number of frames.
+                if percentage <= 0.0
+                    || percentage > 100.0
+                    || window <= 0.0
+                    || (!seconds && window.fract() != 0.0)
+                {
+                    self.error();
+                }
+
+                let unit = match seconds {
+                    true => TimeUnitKind::Seconds,
+                    false => TimeUnitKind::Frames,
+                };
+
+                FilterOperatorKind::Frequency(percentage, window, unit)
+            }
+            _ => return None,
+        };
+
+        self.expect(RightChevron);
+
+        Some(kind)
+    }
+
     /// Parse an S4u-based expression.
     ///
     /// This parse function captures the following grammar:
     ///
     /// ```text
     /// pi ::= '(' pi ')' | pi '&' pi | pi '|' pi | NonEmpty class
-    ///      | NonEmpty '(' tau ')' | class
+    ///      | NonEmpty '(' tau ')' | measure | distance | signal | tag | class
     /// ```
     ///
+    /// A `measure` at this level is shorthand for `NonEmpty '(' measure ')'`.
+    ///
     /// Note: The following symbol(s) have a different semantic meaning derived
     /// at parse time:
     ///
@@ -253,34 +640,17 @@ impl Parser {
                 NonEmpty => {
                     self.expect(NonEmpty);
 
-                    // The behavior of the NonEmpty operator is non-greedy.
-                    // Therefore, it should consume only the next token and
-                    // decide what to do from there. The two cases are as
-                    // follows:
-                    //
-                    //   1. A class is seen: Consume the class and return.
-                    //   2. A parenthesis is seen: Consume everything between the
-                    //      parenthesis (i.e., an S4 expression).
-                    let child = if let Some(token) = self.peek(1) {
-                        match token.kind {
-                            TokenKind::LeftBracket => self.parse_class(),
-                            TokenKind::LeftParen => {
-                                self.expect(LeftParen);
-                                let child = self.parse_s4();
-                                self.expect(RightParen);
-
-                                child
-                            }
-                            _ => {
-                                self.error();
-                                None
-                            }
-                        }
-                    } else {
-                        self.error();
-                        None
-                    };
+                    let child = self.parse_region();
+                    node = Some(Node::unary(
+                        Operator::SpatialOperator(SpatialOperatorKind::S4uOperator(
+                            S4uOperatorKind::NonEmpty,
+                        )),
+                        child.unwrap(),
+                    ));
+                }
 
+                Width | Height | Area | Aspect | CenterX | CenterY => {
+                    let child = self.parse_measure();
                     node = Some(Node::unary(
                         Operator::SpatialOperator(SpatialOperatorKind::S4uOperator(
                             S4uOperatorKind::NonEmpty,
@@ -289,6 +659,19 @@ impl Parser {
                     ));
                 }
 
+                Distance | CenterDistance => {
+                    node = self.parse_distance();
+                }
+
+                Signal => {
+                    node = self.parse_signal();
+                }
+
+                // tag
+                LeftBracket if matches!(self.peek(3), Some(Token { kind: At, .. })) => {
+                    node = self.parse_tag();
+                }
+
                 // class
                 LeftBracket => {
                     node = self.parse_class();
@@ -345,7 +728,8 @@ impl Parser {
     /// This parse function captures the following grammar:
     ///
     /// ```text
-    /// tau ::= '(' tau ')' | tau '&' tau | tau '|' tau | '!' tau | class
+    /// tau ::= '(' tau ')' | tau '&' tau | tau '|' tau | '!' tau | measure
+    ///       | zone | class
     /// ```
     ///
     /// Note: The following symbol(s) have a different semantic meaning derived
@@ -378,6 +762,16 @@ impl Parser {
                     ));
                 }
 
+                // measure
+                Width | Height | Area | Aspect | CenterX | CenterY => {
+                    node = self.parse_measure();
+                }
+
+                // zone
+                At => {
+                    node = self.parse_zone();
+                }
+
                 // class
                 LeftBracket => {
                     node = self.parse_class();
@@ -428,6 +822,209 @@ impl Parser {
         node
     }
 
+    /// Parse the region operand of a spatial function.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// region ::= class | '(' tau ')'
+    /// ```
+    ///
+    /// The behavior of spatial functions (e.g., NonEmpty) is non-greedy.
+    /// Therefore, it should consume only the next token and decide what to do
+    /// from there. The two cases are as follows:
+    ///
+    ///   1. A class is seen: Consume the class and return.
+    ///   2. A parenthesis is seen: Consume everything between the parenthesis
+    ///      (i.e., an S4 expression).
+    fn parse_region(&mut self) -> Option<SpatialFormula> {
+        if let Some(token) = self.peek(1) {
+            match token.kind {
+                LeftBracket => return self.parse_class(),
+                LeftParen => {
+                    self.expect(LeftParen);
+                    let child = self.parse_s4();
+                    self.expect(RightParen);
+
+                    return child;
+                }
+                _ => self.error(),
+            }
+        } else {
+            self.error();
+        }
+
+        None
+    }
+
+    /// Parse a geometric measure.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// measure ::= ( Width | Height | Area | Aspect | CenterX | CenterY )
+    ///             region comparison
+    /// ```
+    ///
+    /// An aspect is a ratio of the dimensions of a region, which is the same
+    /// relative to the image. Therefore, its comparison may not be normalized
+    /// (i.e., followed by a `%`).
+    fn parse_measure(&mut self) -> Option<SpatialFormula> {
+        let kind = match self.peek(1).map(|token| token.kind.clone()) {
+            Some(Width) => MeasureKind::Width,
+            Some(Height) => MeasureKind::Height,
+            Some(Area) => MeasureKind::Area,
+            Some(Aspect) => MeasureKind::Aspect,
+            Some(CenterX) => MeasureKind::CenterX,
+            Some(CenterY) => MeasureKind::CenterY,
+            _ => {
+                self.error();
+                return None;
+            }
+        };
+
+        self.current += 1;
+
+        let child = self.parse_region();
+        let comparison = self.parse_comparison().unwrap();
+
+        if let (MeasureKind::Aspect, UnitKind::Normalized) = (&kind, &comparison.unit) {
+            self.error();
+        }
+
+        Some(Node::unary(
+            Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                GeometricOperatorKind::Measure(kind, comparison),
+            )),
+            child.unwrap(),
+        ))
+    }
+
+    /// Parse a distance.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// distance ::= ( Distance | CenterDistance ) '(' tau ',' tau ')' comparison
+    /// ```
+    fn parse_distance(&mut self) -> Option<SpatialFormula> {
+        let kind = match self.peek(1).map(|token| token.kind.clone()) {
+            Some(Distance) => DistanceKind::Edge,
+            Some(CenterDistance) => DistanceKind::Center,
+            _ => {
+                self.error();
+                return None;
+            }
+        };
+
+        self.current += 1;
+
+        self.expect(LeftParen);
+        let left = self.parse_s4();
+        self.expect(Comma);
+        let right = self.parse_s4();
+        self.expect(RightParen);
+
+        let comparison = self.parse_comparison();
+
+        Some(Node::binary(
+            Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                GeometricOperatorKind::Distance(kind, comparison.unwrap()),
+            )),
+            left.unwrap(),
+            right.unwrap(),
+        ))
+    }
+
+    /// Parse a comparison.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// comparison ::= ( '<' | '<=' | '>' | '>=' ) number
+    /// number     ::= [ '-' ] ( Integer | Real ) [ '%' ]
+    /// ```
+    ///
+    /// A number followed by a `%` is normalized against the dimensions of the
+    /// image (e.g., `50%` is half the image).
+    fn parse_comparison(&mut self) -> Option<Comparison> {
+        let kind = match self.peek(1).map(|token| token.kind.clone()) {
+            Some(LeftChevron) => ComparisonKind::Less,
+            Some(LeftChevronEqual) => ComparisonKind::LessEqual,
+            Some(RightChevron) => ComparisonKind::Greater,
+            Some(RightChevronEqual) => ComparisonKind::GreaterEqual,
+            _ => {
+                self.error();
+                return None;
+            }
+        };
+
+        self.current += 1;
+
+        let sign = if let Some(Token { kind: Minus, .. }) = self.peek(1) {
+            self.expect(Minus);
+            -1.0
+        } else {
+            1.0
+        };
+
+        let value: f64 = match self.peek(1).map(|token| token.kind.clone()) {
+            Some(Integer) => self.expect(Integer).lexeme.parse().unwrap(),
+            Some(Real) => self.expect(Real).lexeme.parse().unwrap(),
+            _ => {
+                self.error();
+                return None;
+            }
+        };
+
+        let value = sign * value;
+
+        if let Some(Token { kind: Percent, .. }) = self.peek(1) {
+            self.expect(Percent);
+            return Some(Comparison::new(kind, value / 100.0, UnitKind::Normalized));
+        }
+
+        Some(Comparison::new(kind, value, UnitKind::Pixel))
+    }
+
+    /// Parse a signal.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// signal ::= Signal Identifier [ '[' Integer ']' ] '>' comparison
+    /// ```
+    ///
+    /// A signal is not relative to an image. Therefore, its comparison may not
+    /// be normalized (i.e., followed by a `%`).
+    fn parse_signal(&mut self) -> Option<SpatialFormula> {
+        self.expect(Signal);
+        let name = self.expect(Identifier).lexeme;
+
+        let index = if let Some(Token {
+            kind: LeftBracket, ..
+        }) = self.peek(1)
+        {
+            self.expect(LeftBracket);
+            let index = self.expect(Integer).lexeme.parse().unwrap();
+            self.expect(RightBracket);
+
+            Some(index)
+        } else {
+            None
+        };
+
+        self.expect(RightChevron);
+
+        let comparison = self.parse_comparison().unwrap();
+
+        if let UnitKind::Normalized = comparison.unit {
+            self.error();
+        }
+
+        Some(Node::from(Atom::Signal(name, index, comparison)))
+    }
+
     /// Parse a class.
     ///
     /// This parse function captures the following grammar:
@@ -453,7 +1050,56 @@ impl Parser {
         self.expect(Colon);
         self.expect(RightBracket);
 
-        Some(Node::from(name))
+        Some(Node::from(Atom::Class(name)))
+    }
+
+    /// Parse a tag.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// tag ::= '[' ':' '@' Identifier ':' ']' [ comparison ]
+    /// ```
+    ///
+    /// As with a signal, the score of a tag is not relative to an image.
+    /// Therefore, its comparison may not be normalized (i.e., followed by a
+    /// `%`).
+    fn parse_tag(&mut self) -> Option<SpatialFormula> {
+        self.expect(LeftBracket);
+        self.expect(Colon);
+        self.expect(At);
+        let name = self.expect(Identifier).lexeme;
+        self.expect(Colon);
+        self.expect(RightBracket);
+
+        let comparison = match self.peek(1).map(|token| token.kind.clone()) {
+            Some(LeftChevron | LeftChevronEqual | RightChevron | RightChevronEqual) => {
+                let comparison = self.parse_comparison().unwrap();
+
+                if let UnitKind::Normalized = comparison.unit {
+                    self.error();
+                }
+
+                Some(comparison)
+            }
+            _ => None,
+        };
+
+        Some(Node::from(Atom::Tag(name, comparison)))
+    }
+
+    /// Parse a zone.
+    ///
+    /// This parse function captures the following grammar:
+    ///
+    /// ```text
+    /// zone ::= '@' Identifier
+    /// ```
+    fn parse_zone(&mut self) -> Option<SpatialFormula> {
+        self.expect(At);
+        let name = self.expect(Identifier).lexeme;
+
+        Some(Node::from(Atom::Zone(name)))
     }
 
     /// Parse a range.
@@ -497,3 +1143,52 @@ impl Parser {
         range
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ir::ast::TemporalFormula;
+    use crate::compiler::ir::{Node, Operator, TemporalOperatorKind, TimeUnitKind};
+    use crate::compiler::lexer::stream::CharStream;
+    use crate::compiler::lexer::Lexer;
+
+    use super::Parser;
+
+    fn parse(source: &str) -> TemporalFormula {
+        let stream = Lexer::new(CharStream::from(source)).lex();
+        Parser::new(stream).parse_temporal()
+    }
+
+    #[test]
+    fn interval() {
+        let unit = |source: &str| match parse(source) {
+            Node::UnaryExpr {
+                op: Operator::TemporalOperator(TemporalOperatorKind::Once(interval)),
+                ..
+            } => Some(interval.unit),
+            _ => None,
+        };
+
+        assert!(matches!(unit("O[1,2] a"), Some(TimeUnitKind::Frames)));
+        assert!(matches!(unit("O[1s,2s] a"), Some(TimeUnitKind::Seconds)));
+        assert!(matches!(unit("O[1s,] a"), Some(TimeUnitKind::Seconds)));
+        assert!(matches!(unit("O[0,2s] a"), Some(TimeUnitKind::Seconds)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn interval_mixed_upper() {
+        parse("O[1,2s] a");
+    }
+
+    #[test]
+    #[should_panic]
+    fn interval_mixed_lower() {
+        parse("O[1s,2] a");
+    }
+
+    #[test]
+    #[should_panic]
+    fn aspect_percent() {
+        parse("[<aspect>[:car:] > 50%]");
+    }
+}
@@ -3,6 +3,10 @@
 
 use std::path::PathBuf;
 
+use crate::matcher::automata::Backend;
+use crate::matcher::MatchKind;
+use crate::monitor::zones::Zones;
+
 /// Configuration information for Application.
 ///
 /// This information does not capture the subcommands used---just flags, options,
@@ -18,12 +22,49 @@ pub struct Configuration<'a> {
     /// Use the online algorithm.
     pub online: bool,
 
+    /// Report the progress of each longest match as events when online.
+    pub events: bool,
+
+    /// Interpret the pattern as an MTL formula, and report a verdict per frame.
+    pub mtl: bool,
+
+    /// Report the verdict of `psi` at each frame for an MTL formula `G psi`,
+    /// such that each violation is reported at the frame it occurs.
+    pub violations: bool,
+
     /// A collection of channels to import.
     pub channels: Option<Vec<String>>,
 
     /// Maximum number of matches to search for.
     pub limit: Option<usize>,
 
+    /// Report only this number of matches with the highest scores (i.e., the
+    /// most robust), once the search is complete.
+    pub top: Option<usize>,
+
+    /// Match probabilistically, reporting only the matches with at least this
+    /// probability. If this is `None`, then every formula either holds or not.
+    pub threshold: Option<f64>,
+
+    /// The kind of matches to report. If this is `None`, then the default of
+    /// the matching algorithm is used.
+    pub kind: Option<MatchKind>,
+
+    /// Maximum number of frames kept in memory when online. If this is `None`,
+    /// then it is derived from the longest possible match of the SpRE.
+    pub window: Option<usize>,
+
+    /// The kind of DFA to build. If this is `None`, then a dense DFA is built
+    /// unless it exceeds the `budget`.
+    pub backend: Option<Backend>,
+
+    /// Maximum size (in bytes) of a dense DFA. If this is `None`, then the
+    /// default budget is used.
+    pub budget: Option<usize>,
+
+    /// Named zones that may be referenced by the SpRE.
+    pub zones: Option<Zones>,
+
     /// Draw frames.
     #[cfg(feature = "export")]
     pub export: Option<&'a PathBuf>,
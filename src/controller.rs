@@ -3,17 +3,27 @@
 //! This module is responsible for managing and controlling the behavior of the
 //! matching framework.
 
+use std::collections::VecDeque;
 use std::error::Error;
 
+use crate::compiler::ir::ast::SpatialFormula;
 use crate::compiler::Compiler;
 use crate::config::Configuration;
 use crate::datastream::frame::Frame;
 use crate::datastream::DataStream;
+use crate::matcher::automata::Options;
+use crate::matcher::events::{Event, Events};
 use crate::matcher::offline;
 use crate::matcher::online;
-use crate::matcher::Matching;
+use crate::matcher::probabilistic;
+use crate::matcher::record::Record;
+use crate::matcher::{MatchKind, Matching};
+use crate::monitor::temporal::{TemporalMonitor, Verdict};
+use crate::monitor::Monitor;
 
-type PrintCallback = fn(&[Frame], &Configuration) -> Result<(), Box<dyn Error>>;
+type PrintCallback = fn(&Record, &[Frame], &Configuration) -> Result<(), Box<dyn Error>>;
+type EventCallback = fn(&Event, &[Frame], &Configuration) -> Result<(), Box<dyn Error>>;
+type VerdictCallback = fn(&Verdict, &Configuration) -> Result<(), Box<dyn Error>>;
 
 /// The main driver to perform matching.
 ///
@@ -24,12 +34,40 @@ pub struct Controller<'a> {
 
     /// A callback to use (e.g., printing results).
     callback: Option<PrintCallback>,
+
+    /// A callback to use for the [`Event`](s) of an online search.
+    notify: Option<EventCallback>,
+
+    /// A callback to use for the [`Verdict`](s) of an MTL formula.
+    verdict: Option<VerdictCallback>,
 }
 
 impl<'a> Controller<'a> {
     /// Create new [`Controller`] with associated [`Configuration`].
     pub fn new(config: &'a Configuration, callback: Option<PrintCallback>) -> Self {
-        Self { config, callback }
+        Self {
+            config,
+            callback,
+            notify: None,
+            verdict: None,
+        }
+    }
+
+    /// Set the callback to use for [`Event`](s).
+    ///
+    /// This is only used when events are requested by the [`Configuration`],
+    /// in which case it replaces the callback used for each match.
+    pub fn events(mut self, callback: EventCallback) -> Self {
+        self.notify = Some(callback);
+        self
+    }
+
+    /// Set the callback to use for [`Verdict`](s).
+    ///
+    /// This is only used when the pattern is an MTL formula.
+    pub fn verdicts(mut self, callback: VerdictCallback) -> Self {
+        self.verdict = Some(callback);
+        self
     }
 
     /// Entrypoint to execute the [`Controller`].
@@ -38,6 +76,14 @@ impl<'a> Controller<'a> {
     /// [`Controller`] to be reused for differing streams without creating a new
     /// one for each run, accordingly.
     pub fn run(&self, datastream: DataStream) -> Result<(), Box<dyn Error>> {
+        if self.config.mtl {
+            return self.temporal(datastream);
+        }
+
+        if let Some(threshold) = self.config.threshold {
+            return self.probabilistic(datastream, threshold);
+        }
+
         if self.config.online {
             return self.online(datastream);
         }
@@ -45,6 +91,26 @@ impl<'a> Controller<'a> {
         self.offline(datastream)
     }
 
+    /// Check whether the [`Controller`] keeps frames of a [`DataStream`] for as
+    /// long as a match may continue.
+    ///
+    /// This is the case for an online search of a pattern without a longest
+    /// match (e.g., `[[:car:]]*`) and without a `window` (or `capacity`). So,
+    /// the memory used may grow with the length of the stream, which a caller
+    /// may warn about before it is run.
+    pub fn is_unbounded(&self, datastream: &DataStream) -> Result<bool, Box<dyn Error>> {
+        if self.config.mtl || self.config.threshold.is_some() || !self.config.online {
+            return Ok(false);
+        }
+
+        if self.config.window.or(datastream.capacity).is_some() {
+            return Ok(false);
+        }
+
+        let ast = Compiler::new().compile(self.config.pattern)?;
+        Ok(ast.length().max.is_none())
+    }
+
     /// Run the offline matching algorithm.
     pub fn offline(&self, mut datastream: DataStream) -> Result<(), Box<dyn Error>> {
         // Compile the SpRE into an S-AST ("Symbolic AST").
@@ -55,7 +121,12 @@ impl<'a> Controller<'a> {
         let ast = compiler.compile(self.config.pattern)?;
 
         // Build [`offline::Matcher`].
-        let matcher = offline::Matcher::from(&ast);
+        let monitor = self.monitor(ast.fmap().into_iter().map(|x| &x.formula))?;
+        let mut matcher = offline::Matcher::new(&ast, &self.options())?.monitor(monitor);
+
+        if let Some(kind) = self.config.kind {
+            matcher = matcher.kind(kind);
+        }
 
         // Load all [`Frame`](s) into the [`DataStream`].
         //
@@ -67,6 +138,17 @@ impl<'a> Controller<'a> {
             datastream.append(frame);
         }
 
+        let frames = datastream.frames.make_contiguous();
+
+        // Evaluate the symbols satisfied by each [`Frame`].
+        //
+        // Each [`Frame`] is evaluated exactly once, as the matcher is run from
+        // several offsets of the same [`DataStream`].
+        let symbols = frames
+            .iter()
+            .map(|frame| matcher.evaluate(frame))
+            .collect::<Vec<_>>();
+
         // A counter for the number of [`Match`].
         //
         // Ideally, this variable should be stored at a higher level as it is
@@ -74,37 +156,37 @@ impl<'a> Controller<'a> {
         // in the [`Configuration`] struct, it is declared here.
         let mut mcount = 0;
 
-        let mut offset = 0;
-        while offset < datastream.frames.len() {
-            if let Some(m) = matcher.leftmost(&datastream.frames[offset..])? {
-                // Increment `count` and check for limit.
-                //
-                // This is done before display the [`Match`] as a `limit` of 0
-                // may be requested.
-                mcount += 1;
+        // The highest scoring matches, if requested.
+        let mut ranking = self.config.top.map(Ranking::new);
 
-                if let Some(limit) = self.config.limit {
-                    if mcount > limit {
-                        break;
-                    }
-                }
+        for m in matcher.find_iter(&symbols)? {
+            let m = m?;
 
-                // Handle [`Match`].
-                if let Some(callback) = self.callback {
-                    callback(
-                        &datastream.frames[(offset + m.start)..(offset + m.end)],
-                        self.config,
-                    )?;
-                }
+            // Increment `count` and check for limit.
+            //
+            // This is done before display the [`Match`] as a `limit` of 0
+            // may be requested.
+            mcount += 1;
 
-                offset += m.end;
-                continue;
+            if let Some(limit) = self.config.limit {
+                if mcount > limit {
+                    break;
+                }
             }
 
-            offset += 1;
+            // Handle [`Match`].
+            if let Some(callback) = self.callback {
+                let (frames, symbols) = (&frames[m.start..m.end], &symbols[m.start..m.end]);
+                let record = matcher.record(frames, symbols)?;
+
+                match &mut ranking {
+                    Some(ranking) => ranking.insert(record, frames),
+                    None => callback(&record, frames, self.config)?,
+                }
+            }
         }
 
-        Ok(())
+        self.rank(ranking)
     }
 
     /// Run the online matching algorithm.
@@ -117,7 +199,23 @@ impl<'a> Controller<'a> {
         let ast = compiler.compile(self.config.pattern)?;
 
         // Build [`online::Matcher`].
-        let matcher = online::Matcher::from(&ast);
+        let monitor = self.monitor(ast.fmap().into_iter().map(|x| &x.formula))?;
+        let mut matcher = online::Matcher::new(&ast, &self.options())?.monitor(monitor);
+
+        if let Some(kind) = self.config.kind {
+            matcher = matcher.kind(kind);
+        }
+
+        // The [`Event`](s) reported so far, if requested.
+        //
+        // These track the progress of each longest match. Otherwise, each
+        // match is only reported once it is final.
+        let mut events = None;
+
+        if self.config.events {
+            matcher = matcher.kind(MatchKind::LeftmostLongest);
+            events = Some(Events::new());
+        }
 
         // A counter for the number of [`Match`].
         //
@@ -126,49 +224,302 @@ impl<'a> Controller<'a> {
         // in the [`Configuration`] struct, it is declared here.
         let mut mcount = 0;
 
+        // The highest scoring matches, if requested.
+        //
+        // A match may be outranked by any later match. So, these are only
+        // reported at the end of the stream.
+        let mut ranking = self.config.top.map(Ranking::new);
+
+        // Size the window of [`Frame`](s) kept in memory.
+        //
+        // A [`Match`](crate::matcher::Match) never spans more [`Frame`](s)
+        // than the longest possible match of the SpRE. So, unless a `window` is
+        // requested, this is used as the `capacity` of the [`DataStream`].
+        datastream.capacity = self
+            .config
+            .window
+            .or(datastream.capacity)
+            .or(ast.length().max);
+
+        // The live threads of the search over the [`DataStream`].
+        //
+        // The `capacity` of the [`DataStream`] limits the number of [`Frame`](s)
+        // a [`Match`](crate::matcher::Match) may span.
+        let mut threads = matcher.threads();
+
+        // Otherwise, frames are kept for as long as a match may continue (see
+        // [`Controller::is_unbounded`]).
+        if let Some(capacity) = datastream.capacity {
+            threads = threads.window(capacity);
+        }
+
         // Load all [`Frame`](s) into the [`DataStream`].
         //
         // For online, we want to search over the data stream incrementally, so
-        // the algorithm is run for each new [`Frame`] imported. This differs to
-        // offline where all [`Frame`](s) must be loadecd before running the
+        // the algorithm is advanced by each new [`Frame`] imported. This differs
+        // to offline where all [`Frame`](s) must be loadecd before running the
         // algorithm.
-        while let Some(frame) = datastream.request(&self.config.channels)? {
-            if let Some(capacity) = datastream.capacity {
-                if datastream.frames.len() >= capacity {
-                    // Remove the least recent [`Frame`] from the [`DataStream`].
-                    //
-                    // This procedure can be thought of as a LRU cache.
-                    //
-                    // OPTIMIZATION: The use of `remove` shifts all elements to
-                    // the right one index to the left. Therefore, it may be
-                    // worthwhile to find a better operation to remove the LRU
-                    // element (e.g., use a reversed vector with `pop`).
-                    datastream.frames.remove(0);
+        // The symbols satisfied by each [`Frame`] kept in the [`DataStream`].
+        //
+        // This is kept aligned with the frames of the [`DataStream`], so that
+        // the [`Record`] of a match can be resolved.
+        let mut symbols = VecDeque::new();
+
+        'stream: loop {
+            let frame = datastream.request(&self.config.channels)?;
+            let finished = frame.is_none();
+
+            // Advance by the next [`Frame`], or finish at the end of the stream
+            // to report any remaining matches.
+            let mats = match frame {
+                Some(frame) => {
+                    symbols.push_back(matcher.evaluate(&frame));
+                    datastream.append(frame);
+
+                    matcher.advance(&mut threads, &symbols[symbols.len() - 1])?
                 }
-            }
+                None => threads.finish(),
+            };
 
-            datastream.append(frame);
+            // The absolute position of the first [`Frame`] kept.
+            let offset = threads.position() - datastream.frames.len();
+
+            // Each match is closed as soon as it is final, unless its progress
+            // is tracked by the [`Event`](s).
+            let reported = match &mut events {
+                Some(events) => events.update(&threads, mats),
+                None => mats.into_iter().map(Event::MatchClosed).collect(),
+            };
 
-            if let Some(m) = matcher.leftmost(&datastream.frames[..])? {
+            for event in reported {
                 // Increment `count` and check for limit.
                 //
                 // This is done before display the [`Match`] as a `limit` of 0
                 // may be requested.
-                mcount += 1;
+                if let Event::MatchClosed(..) = event {
+                    mcount += 1;
 
-                if let Some(limit) = self.config.limit {
-                    if mcount > limit {
-                        break;
+                    // The matches ranked so far are still reported.
+                    if let Some(limit) = self.config.limit {
+                        if mcount > limit {
+                            break 'stream;
+                        }
                     }
                 }
 
-                // Handle [`Match`].
-                if let Some(callback) = self.callback {
-                    callback(&datastream.frames[m.start..m.end], self.config)?;
+                let m = event.as_match();
+                let range = m.start - offset..m.end - offset;
+
+                let frames = &datastream.frames.make_contiguous()[range.clone()];
+
+                // Handle [`Event`] (or [`Match`]).
+                if self.config.events {
+                    if let Some(notify) = self.notify {
+                        notify(&event, frames, self.config)?;
+                    }
+                } else if let Some(callback) = self.callback {
+                    let record = matcher.record(frames, &symbols.make_contiguous()[range])?;
+
+                    match &mut ranking {
+                        Some(ranking) => ranking.insert(record, frames),
+                        None => callback(&record, frames, self.config)?,
+                    }
                 }
             }
+
+            if finished {
+                break;
+            }
+
+            // Remove the [`Frame`](s) that are never part of a future match.
+            //
+            // This keeps the memory used bounded by the longest possible match
+            // (or the `capacity`), rather than the length of the stream.
+            let earliest = threads.earliest() - offset;
+
+            datastream.frames.drain(..earliest);
+            symbols.drain(..earliest);
+        }
+
+        self.rank(ranking)
+    }
+
+    /// Run the probabilistic matching algorithm.
+    ///
+    /// This is run offline, where each [`Frame`] is evaluated into the
+    /// probability of each formula, instead (see [`probabilistic::Matcher`]).
+    /// Only the matches with at least the probability of the `threshold` are
+    /// reported.
+    pub fn probabilistic(
+        &self,
+        mut datastream: DataStream,
+        threshold: f64,
+    ) -> Result<(), Box<dyn Error>> {
+        let compiler = Compiler::new();
+        let ast = compiler.compile(self.config.pattern)?;
+
+        let monitor = self.monitor(ast.fmap().into_iter().map(|x| &x.formula))?;
+        let mut matcher = probabilistic::Matcher::new(&ast, &self.options())?
+            .monitor(monitor)
+            .threshold(threshold);
+
+        if let Some(kind) = self.config.kind {
+            matcher = matcher.kind(kind);
+        }
+
+        while let Some(frame) = datastream.request(&self.config.channels)? {
+            datastream.append(frame);
+        }
+
+        let frames = datastream.frames.make_contiguous();
+
+        let likelihoods = frames
+            .iter()
+            .map(|frame| matcher.evaluate(frame))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut ranking = self.config.top.map(Ranking::new);
+
+        for (mcount, (m, probability)) in matcher.find(&likelihoods)?.into_iter().enumerate() {
+            if self.config.limit.is_some_and(|limit| mcount >= limit) {
+                break;
+            }
+
+            if let Some(callback) = self.callback {
+                let (frames, likelihoods) = (&frames[m.start..m.end], &likelihoods[m.start..m.end]);
+                let record = matcher.record(frames, likelihoods, probability)?;
+
+                match &mut ranking {
+                    Some(ranking) => ranking.insert(record, frames),
+                    None => callback(&record, frames, self.config)?,
+                }
+            }
+        }
+
+        self.rank(ranking)
+    }
+
+    /// Run the online monitor of an MTL formula.
+    ///
+    /// Unlike matching, a verdict is reported for each [`Frame`] of the
+    /// [`DataStream`], which is decided as soon as the [`Frame`](s) it depends
+    /// on are seen. So, the [`DataStream`] is always consumed online.
+    pub fn temporal(&self, mut datastream: DataStream) -> Result<(), Box<dyn Error>> {
+        let compiler = Compiler::new();
+        let formula = compiler.compile_temporal(self.config.pattern)?;
+
+        let monitor = self.monitor(formula.operands())?;
+        let mut monitor = TemporalMonitor::new(&formula).monitor(monitor);
+
+        if self.config.violations {
+            monitor = monitor.violations();
+        }
+
+        loop {
+            let verdicts = match datastream.request(&self.config.channels)? {
+                Some(frame) => monitor.advance(&frame),
+                None => break,
+            };
+
+            self.report(&verdicts)?;
+        }
+
+        self.report(&monitor.finish())
+    }
+
+    /// Handle the [`Record`](s) of the highest scoring matches, if ranked.
+    fn rank(&self, ranking: Option<Ranking>) -> Result<(), Box<dyn Error>> {
+        if let (Some(ranking), Some(callback)) = (ranking, self.callback) {
+            for (record, frames) in ranking.records {
+                callback(&record, &frames, self.config)?;
+            }
         }
 
         Ok(())
     }
+
+    /// Handle the [`Verdict`](s) of an MTL formula.
+    fn report(&self, verdicts: &[Verdict]) -> Result<(), Box<dyn Error>> {
+        if let Some(callback) = self.verdict {
+            for verdict in verdicts {
+                callback(verdict, self.config)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the [`Monitor`] used to evaluate spatial formulas.
+    ///
+    /// This attaches any relevant information from the [`Configuration`] that
+    /// spatial formulas may reference (e.g., zones). Each of the spatial
+    /// formulas provided is validated against this information, so that a
+    /// reference to an unknown zone is reported rather than never satisfied.
+    fn monitor<'f>(
+        &self,
+        formulas: impl IntoIterator<Item = &'f SpatialFormula>,
+    ) -> Result<Monitor, Box<dyn Error>> {
+        let mut monitor = Monitor::new();
+
+        if let Some(zones) = &self.config.zones {
+            monitor = monitor.zones(zones.clone());
+        }
+
+        for formula in formulas {
+            monitor.validate(formula)?;
+        }
+
+        Ok(monitor)
+    }
+
+    /// Construct the [`Options`] used to build the DFA(s) of a matcher.
+    ///
+    /// This attaches the backend (and size limit of a dense DFA) from the
+    /// [`Configuration`], if any.
+    fn options(&self) -> Options {
+        let mut options = Options::new();
+
+        if let Some(backend) = self.config.backend {
+            options = options.backend(backend);
+        }
+
+        if let Some(budget) = self.config.budget {
+            options = options.budget(budget);
+        }
+
+        options
+    }
+}
+
+/// The highest scoring matches of a search.
+///
+/// Each [`Record`] is kept along with its [`Frame`](s), as these may no
+/// longer be kept by the [`DataStream`] once the search is complete.
+struct Ranking {
+    size: usize,
+
+    /// The matches kept, by descending score (and then in the order found).
+    records: Vec<(Record, Vec<Frame>)>,
+}
+
+impl Ranking {
+    /// Create a new [`Ranking`] of a number of matches.
+    fn new(size: usize) -> Self {
+        Ranking {
+            size,
+            records: Vec::new(),
+        }
+    }
+
+    /// Insert a match, unless it is outranked by every match kept.
+    fn insert(&mut self, record: Record, frames: &[Frame]) {
+        let position = self
+            .records
+            .partition_point(|(r, _)| r.score >= record.score);
+
+        if position < self.size {
+            self.records.insert(position, (record, frames.to_vec()));
+            self.records.truncate(self.size);
+        }
+    }
 }
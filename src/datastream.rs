@@ -2,6 +2,7 @@
 //!
 //! This is the format from which all importers must import to.
 
+use std::collections::VecDeque;
 use std::error::Error;
 use std::fmt;
 use std::path::Path;
@@ -20,7 +21,7 @@ pub mod importer;
 /// reading/writing of the stream regardless of offline/online application.
 #[derive(Default)]
 pub struct DataStream {
-    pub frames: Vec<Frame>,
+    pub frames: VecDeque<Frame>,
 
     /// The [`DataImport`] to retrieve [`Frame`] from.
     pub importer: Option<Box<dyn DataImport>>,
@@ -39,7 +40,7 @@ impl DataStream {
     /// be further populated with frames.
     pub fn new() -> Self {
         DataStream {
-            frames: Vec::new(),
+            frames: VecDeque::new(),
             importer: None,
             exporter: None,
             capacity: None,
@@ -96,9 +97,9 @@ impl DataStream {
     /// Export the [`DataStream`] to a file.
     ///
     /// This uses the provided `self::exporter`, accordingly.
-    pub fn export(&self, outfile: &Path) -> Result<(), Box<dyn Error>> {
+    pub fn export(&mut self, outfile: &Path) -> Result<(), Box<dyn Error>> {
         if let Some(exporter) = &self.exporter {
-            return exporter.export(&self.frames, outfile);
+            return exporter.export(self.frames.make_contiguous(), outfile);
         }
 
         Err(Box::new(DataStreamError::from("missing exporter")))
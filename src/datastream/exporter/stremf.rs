@@ -4,12 +4,14 @@ use std::fs::OpenOptions;
 use std::io::BufWriter;
 use std::path::Path;
 
-use crate::datastream::frame::sample::detections::ImageSource;
+use crate::datastream::frame::sample::cuboids::CuboidAnnotation;
+use crate::datastream::frame::sample::detections::{Annotation, Geometry, ImageSource};
+use crate::datastream::frame::sample::signals::Signal;
 use crate::datastream::frame::sample::Sample;
 use crate::datastream::frame::Frame;
 use crate::datastream::importer::stremf::{
-    StremAnnotation, StremBoundingBox, StremDimension, StremFormat, StremFrame, StremImage,
-    StremSample,
+    StremAnnotation, StremBoundingBox, StremCuboid, StremDimension, StremFormat, StremFrame,
+    StremImage, StremMask, StremPoint, StremRotatedBox, StremSample, StremSignal, StremTag,
 };
 
 use super::DataExport;
@@ -27,11 +29,89 @@ impl DataExporter {
     }
 }
 
+impl DataExporter {
+    /// Convert an [`Annotation`] into a STREM-formatted annotation.
+    ///
+    /// Only the field corresponding to the kind of [`Geometry`] is populated.
+    fn annotation(annotation: &Annotation) -> StremAnnotation {
+        let mut a = StremAnnotation {
+            class: annotation.label.clone(),
+            score: annotation.score,
+            bbox: None,
+            rbox: None,
+            polygon: None,
+            mask: None,
+            cuboid: None,
+        };
+
+        match &annotation.geometry {
+            Geometry::BoundingBox(bbox) => {
+                a.bbox = Some(StremBoundingBox {
+                    x: bbox.min.x,
+                    y: bbox.min.y,
+                    w: bbox.max.x - bbox.min.x,
+                    h: bbox.max.y - bbox.min.y,
+                });
+            }
+            Geometry::RotatedBox(rbox) => {
+                a.rbox = Some(StremRotatedBox {
+                    cx: rbox.center.x,
+                    cy: rbox.center.y,
+                    w: rbox.width,
+                    h: rbox.height,
+                    angle: rbox.angle,
+                });
+            }
+            Geometry::Polygon(polygon) => {
+                a.polygon = Some(
+                    polygon
+                        .vertices
+                        .iter()
+                        .map(|p| StremPoint { x: p.x, y: p.y })
+                        .collect(),
+                );
+            }
+            Geometry::Mask(mask) => {
+                a.mask = Some(StremMask {
+                    size: [mask.height, mask.width],
+                    counts: mask.counts.clone(),
+                });
+            }
+        }
+
+        a
+    }
+
+    /// Convert a [`CuboidAnnotation`] into a STREM-formatted annotation.
+    fn cuboid(annotation: &CuboidAnnotation) -> StremAnnotation {
+        let cuboid = &annotation.cuboid;
+
+        StremAnnotation {
+            class: annotation.label.clone(),
+            score: annotation.score,
+            bbox: None,
+            rbox: None,
+            polygon: None,
+            mask: None,
+            cuboid: Some(StremCuboid {
+                x: cuboid.center.x,
+                y: cuboid.center.y,
+                z: cuboid.center.z,
+                l: cuboid.length,
+                w: cuboid.width,
+                h: cuboid.height,
+                yaw: cuboid.yaw,
+            }),
+        }
+    }
+}
+
 impl DataExport for DataExporter {
     fn export(&self, frames: &[Frame], path: &Path) -> Result<(), Box<dyn Error>> {
         let infile = OpenOptions::new()
             .write(true)
             .create(true)
+            .truncate(true)
             .open(path)
             .or(Err(Box::new(DataExporterError::from(format!(
                 "unable to write to `{}`",
@@ -61,24 +141,76 @@ impl DataExport for DataExporter {
                         let mut a = Vec::new();
                         for annotations in record.annotations.values() {
                             for annotation in annotations {
-                                a.push(StremAnnotation {
-                                    class: annotation.label.clone(),
-                                    score: annotation.score,
-                                    bbox: StremBoundingBox {
-                                        x: annotation.bbox.min.x,
-                                        y: annotation.bbox.min.y,
-                                        w: annotation.bbox.max.x - annotation.bbox.min.x,
-                                        h: annotation.bbox.max.y - annotation.bbox.min.y,
-                                    },
-                                });
+                                a.push(Self::annotation(annotation));
                             }
                         }
 
                         s.push(StremSample {
                             channel: record.channel.clone(),
                             timestamp: record.timestamp,
-                            image: i.unwrap(),
+                            image: i,
                             annotations: a,
+                            signals: None,
+                            tags: None,
+                        });
+                    }
+                    Sample::CuboidDetection(record) => {
+                        let mut a = Vec::new();
+                        for annotations in record.annotations.values() {
+                            for annotation in annotations {
+                                a.push(Self::cuboid(annotation));
+                            }
+                        }
+
+                        s.push(StremSample {
+                            channel: record.channel.clone(),
+                            timestamp: record.timestamp,
+                            image: None,
+                            annotations: a,
+                            signals: None,
+                            tags: None,
+                        });
+                    }
+                    Sample::Signal(record) => {
+                        let signals = record
+                            .signals
+                            .iter()
+                            .map(|(name, signal)| {
+                                let signal = match signal {
+                                    Signal::Scalar(value) => StremSignal::Scalar(*value),
+                                    Signal::Vector(values) => StremSignal::Vector(values.clone()),
+                                };
+
+                                (name.clone(), signal)
+                            })
+                            .collect();
+
+                        s.push(StremSample {
+                            channel: record.channel.clone(),
+                            timestamp: record.timestamp,
+                            image: None,
+                            annotations: Vec::new(),
+                            signals: Some(signals),
+                            tags: None,
+                        });
+                    }
+                    Sample::Scene(record) => {
+                        let tags = record
+                            .tags
+                            .iter()
+                            .map(|(label, score)| StremTag {
+                                label: label.clone(),
+                                score: *score,
+                            })
+                            .collect();
+
+                        s.push(StremSample {
+                            channel: record.channel.clone(),
+                            timestamp: record.timestamp,
+                            image: None,
+                            annotations: Vec::new(),
+                            signals: None,
+                            tags: Some(tags),
                         });
                     }
                 }
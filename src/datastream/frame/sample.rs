@@ -1,6 +1,12 @@
-use self::detections::DetectionRecord;
+use self::cuboids::CuboidRecord;
+use self::detections::{DetectionRecord, Image};
+use self::scenes::SceneRecord;
+use self::signals::SignalRecord;
 
+pub mod cuboids;
 pub mod detections;
+pub mod scenes;
+pub mod signals;
 
 /// A kind of data captured by a channel of the perception sytem.
 ///
@@ -12,4 +18,33 @@ pub mod detections;
 pub enum Sample {
     /// A sample of object detection(s).
     ObjectDetection(DetectionRecord),
+
+    /// A sample of 3D object detection(s) (e.g., from LiDAR).
+    CuboidDetection(CuboidRecord),
+
+    /// A sample of numeric signal(s) (e.g., ego speed).
+    Signal(SignalRecord),
+
+    /// A sample of scene classification(s) (e.g., weather).
+    Scene(SceneRecord),
+}
+
+impl Sample {
+    /// The name of the channel that captured the [`Sample`].
+    pub fn channel(&self) -> &str {
+        match self {
+            Sample::ObjectDetection(record) => &record.channel,
+            Sample::CuboidDetection(record) => &record.channel,
+            Sample::Signal(record) => &record.channel,
+            Sample::Scene(record) => &record.channel,
+        }
+    }
+
+    /// The [`Image`] the [`Sample`] was captured on, if any.
+    pub fn image(&self) -> Option<&Image> {
+        match self {
+            Sample::ObjectDetection(record) => record.image.as_ref(),
+            Sample::CuboidDetection(..) | Sample::Signal(..) | Sample::Scene(..) => None,
+        }
+    }
 }
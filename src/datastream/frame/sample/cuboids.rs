@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use super::detections::{Point, Polygon, RotatedBox};
+
+/// A sample record of 3D object detections produced for a single frame.
+///
+/// This is typically produced from a LiDAR (or fused) channel where each
+/// detection is a cuboid expressed in the ego frame of the vehicle. That is,
+/// the x-axis points forward, the y-axis points left, and the z-axis points up
+/// (all in meters).
+#[derive(Clone, Debug)]
+pub struct CuboidRecord {
+    pub channel: String,
+    pub timestamp: f64,
+
+    /// A mapping between labels and annotations (i.e., cuboids).
+    pub annotations: HashMap<String, Vec<CuboidAnnotation>>,
+}
+
+impl CuboidRecord {
+    /// Create a new [`CuboidRecord`].
+    pub fn new(channel: String, timestamp: f64) -> Self {
+        CuboidRecord {
+            channel,
+            timestamp,
+            annotations: HashMap::new(),
+        }
+    }
+}
+
+/// An annotation of a label with a 3D region.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CuboidAnnotation {
+    pub label: String,
+    pub score: f64,
+    pub cuboid: Cuboid,
+}
+
+impl CuboidAnnotation {
+    /// Create a new [`CuboidAnnotation`] with associated data.
+    pub fn new(label: String, score: f64, cuboid: Cuboid) -> Self {
+        CuboidAnnotation {
+            label,
+            score,
+            cuboid,
+        }
+    }
+}
+
+/// A 3D bounding box rotated about its vertical axis.
+///
+/// The `length` extends along the heading of the box, the `width` extends
+/// across it, and the `height` extends along the z-axis. The `yaw` is in
+/// radians and rotates the heading from the x-axis towards the y-axis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cuboid {
+    pub center: Point3,
+    pub length: f64,
+    pub width: f64,
+    pub height: f64,
+    pub yaw: f64,
+}
+
+impl Cuboid {
+    /// Create a new [`Cuboid`].
+    pub fn new(center: Point3, length: f64, width: f64, height: f64, yaw: f64) -> Self {
+        Cuboid {
+            center,
+            length,
+            width,
+            height,
+            yaw,
+        }
+    }
+
+    /// The footprint of the [`Cuboid`] from a bird's-eye view (BEV).
+    pub fn footprint(&self) -> Polygon {
+        RotatedBox::new(
+            Point::new(self.center.x, self.center.y),
+            self.length,
+            self.width,
+            self.yaw,
+        )
+        .polygon()
+    }
+
+    /// Check whether two cuboids overlap.
+    ///
+    /// Two cuboids overlap if their footprints overlap and their vertical
+    /// extents overlap.
+    pub fn intersects(&self, other: &Cuboid) -> bool {
+        let (a, b) = (self.center.z, other.center.z);
+        let (h, k) = (self.height / 2.0, other.height / 2.0);
+
+        a - h < b + k && b - k < a + h && self.footprint().intersects(&other.footprint())
+    }
+}
+
+/// A point in 3D space.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Point3 {
+    /// Create a new [`Point3`] with (x, y, z) coordinates.
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cuboid, Point3};
+
+    #[test]
+    fn cuboid_intersects() {
+        let vehicle = Cuboid::new(Point3::new(8.0, 0.0, 0.8), 4.5, 1.9, 1.6, 0.3);
+
+        // overlapping footprints at the same elevation
+        let other = Cuboid::new(Point3::new(10.0, 1.0, 0.8), 4.5, 1.9, 1.6, 0.0);
+        assert!(vehicle.intersects(&other));
+
+        // overlapping footprints above the vehicle
+        let other = Cuboid::new(Point3::new(8.0, 0.0, 5.0), 0.6, 0.6, 1.8, 0.0);
+        assert!(!vehicle.intersects(&other));
+        assert_eq!(0.0, vehicle.footprint().distance(&other.footprint()));
+
+        // beside the vehicle
+        let other = Cuboid::new(Point3::new(0.0, 5.0, 0.8), 2.0, 2.0, 1.6, 0.0);
+        assert!(!vehicle.intersects(&other));
+        assert!(vehicle.footprint().distance(&other.footprint()) > 0.0);
+    }
+}
@@ -31,17 +31,148 @@ impl DetectionRecord {
 ///
 /// This fundamentally includes the label, the region, and the confidence
 /// ("score") of the resulting detection.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Annotation {
     pub label: String,
     pub score: f64,
-    pub bbox: BoundingBox,
+    pub geometry: Geometry,
 }
 
 impl Annotation {
     /// Create a new [`Annotation`] with associated data.
-    pub fn new(label: String, score: f64, bbox: BoundingBox) -> Self {
-        Annotation { label, score, bbox }
+    pub fn new(label: String, score: f64, geometry: Geometry) -> Self {
+        Annotation {
+            label,
+            score,
+            geometry,
+        }
+    }
+}
+
+/// The geometry of an annotated region.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Geometry {
+    /// An axis-aligned bounding box.
+    BoundingBox(BoundingBox),
+
+    /// A bounding box rotated about its center.
+    RotatedBox(RotatedBox),
+
+    /// An instance polygon.
+    Polygon(Polygon),
+
+    /// An instance segmentation mask.
+    Mask(Mask),
+}
+
+impl Geometry {
+    /// The smallest [`BoundingBox`] that contains the [`Geometry`].
+    pub fn bounds(&self) -> BoundingBox {
+        match self {
+            Geometry::BoundingBox(bbox) => bbox.clone(),
+            Geometry::RotatedBox(rbox) => rbox.polygon().bounds(),
+            Geometry::Polygon(polygon) => polygon.bounds(),
+            Geometry::Mask(mask) => mask.bounds(),
+        }
+    }
+
+    /// The area covered by the [`Geometry`].
+    ///
+    /// The area of a [`Mask`] is its number of set pixels.
+    pub fn area(&self) -> f64 {
+        match self {
+            Geometry::BoundingBox(bbox) => bbox.area(),
+            Geometry::RotatedBox(rbox) => rbox.polygon().area(),
+            Geometry::Polygon(polygon) => polygon.area(),
+            Geometry::Mask(mask) => mask.area() as f64,
+        }
+    }
+
+    /// The [`Polygon`] that outlines the [`Geometry`].
+    ///
+    /// A [`Mask`] has no outline; so, [`None`] is returned.
+    pub fn polygon(&self) -> Option<Polygon> {
+        match self {
+            Geometry::BoundingBox(bbox) => Some(Polygon::from(bbox)),
+            Geometry::RotatedBox(rbox) => Some(rbox.polygon()),
+            Geometry::Polygon(polygon) => Some(polygon.clone()),
+            Geometry::Mask(..) => None,
+        }
+    }
+
+    /// Check whether two geometries overlap.
+    ///
+    /// Convex shapes (i.e., boxes) are clipped against, masks are compared
+    /// pixel-wise, and any other pair of polygons is checked for crossing
+    /// edges or contained vertices.
+    pub fn intersects(&self, other: &Geometry) -> bool {
+        match (self, other) {
+            (Geometry::BoundingBox(a), Geometry::BoundingBox(b)) => a.intersection(b).is_some(),
+            (Geometry::Mask(mask), other) | (other, Geometry::Mask(mask)) => mask
+                .decode()
+                .into_iter()
+                .zip(other.rasterize(mask.width, mask.height))
+                .any(|(a, b)| a && b),
+            (a, b) => {
+                let (p, q) = (a.polygon().unwrap(), b.polygon().unwrap());
+
+                if a.is_convex() {
+                    q.clip(&p).area() > 0.0
+                } else if b.is_convex() {
+                    p.clip(&q).area() > 0.0
+                } else {
+                    p.intersects(&q)
+                }
+            }
+        }
+    }
+
+    /// Rasterize the [`Geometry`] onto a grid of pixels.
+    ///
+    /// The grid is traversed in column-major order (see [`Mask`]). A pixel is
+    /// set if its center lies within the [`Geometry`].
+    pub fn rasterize(&self, width: usize, height: usize) -> Vec<bool> {
+        let mut bits = vec![false; width * height];
+
+        match self {
+            Geometry::Mask(mask) => {
+                let source = mask.decode();
+
+                for x in 0..width.min(mask.width) {
+                    for y in 0..height.min(mask.height) {
+                        bits[x * height + y] = source[x * mask.height + y];
+                    }
+                }
+            }
+            _ => {
+                let polygon = self.polygon().unwrap();
+                let bounds = polygon.bounds();
+
+                let (x0, x1) = (
+                    bounds.min.x.floor().max(0.0) as usize,
+                    bounds.max.x.ceil() as usize,
+                );
+                let (y0, y1) = (
+                    bounds.min.y.floor().max(0.0) as usize,
+                    bounds.max.y.ceil() as usize,
+                );
+
+                for x in x0..x1.min(width) {
+                    for y in y0..y1.min(height) {
+                        if polygon.contains(&Point::new(x as f64 + 0.5, y as f64 + 0.5)) {
+                            bits[x * height + y] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        bits
+    }
+
+    /// Check whether the [`Geometry`] is always convex.
+    fn is_convex(&self) -> bool {
+        matches!(self, Geometry::BoundingBox(..) | Geometry::RotatedBox(..))
     }
 }
 
@@ -49,7 +180,7 @@ impl Annotation {
 ///
 /// The selected representation of the AABB uses the major and minor coordinates
 /// (i.e., the corners) to represent the rectangle.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct BoundingBox {
     pub min: Point,
     pub max: Point,
@@ -60,10 +191,344 @@ impl BoundingBox {
     pub fn new(min: Point, max: Point) -> Self {
         BoundingBox { min, max }
     }
+
+    /// The width of the [`BoundingBox`].
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    /// The height of the [`BoundingBox`].
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    /// The area of the [`BoundingBox`].
+    pub fn area(&self) -> f64 {
+        self.width() * self.height()
+    }
+
+    /// The center of the [`BoundingBox`].
+    pub fn center(&self) -> Point {
+        Point::new(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+        )
+    }
+
+    /// Compute the intersection of two bounding boxes.
+    ///
+    /// If no intersection exists, then [`None`] is returned which is
+    /// semantically equivalent to the empty set.
+    pub fn intersection(&self, other: &BoundingBox) -> Option<BoundingBox> {
+        let (a, b) = (self, other);
+
+        // check if overlap exists
+        if a.min.x < b.max.x && b.min.x < a.max.x && a.min.y < b.max.y && b.min.y < a.max.y {
+            let min = Point::new(
+                std::cmp::max(a.min.x as i64, b.min.x as i64) as f64,
+                std::cmp::max(a.min.y as i64, b.min.y as i64) as f64,
+            );
+
+            let max = Point::new(
+                std::cmp::min(a.max.x as i64, b.max.x as i64) as f64,
+                std::cmp::min(a.max.y as i64, b.max.y as i64) as f64,
+            );
+
+            return Some(BoundingBox::new(min, max));
+        }
+
+        None
+    }
+}
+
+/// A bounding box rotated about its center.
+///
+/// The `angle` is in radians and rotates the box from the x-axis towards the
+/// y-axis of the image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RotatedBox {
+    pub center: Point,
+    pub width: f64,
+    pub height: f64,
+    pub angle: f64,
+}
+
+impl RotatedBox {
+    /// Create a new [`RotatedBox`].
+    pub fn new(center: Point, width: f64, height: f64, angle: f64) -> Self {
+        RotatedBox {
+            center,
+            width,
+            height,
+            angle,
+        }
+    }
+
+    /// The corners of the [`RotatedBox`] as a [`Polygon`].
+    pub fn polygon(&self) -> Polygon {
+        let (sin, cos) = self.angle.sin_cos();
+        let (w, h) = (self.width / 2.0, self.height / 2.0);
+
+        Polygon::new(
+            [(-w, -h), (w, -h), (w, h), (-w, h)]
+                .into_iter()
+                .map(|(dx, dy)| {
+                    Point::new(
+                        self.center.x + dx * cos - dy * sin,
+                        self.center.y + dx * sin + dy * cos,
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A simple polygon.
+///
+/// The vertices are ordered, and the polygon is implicitly closed (i.e., the
+/// last vertex connects back to the first).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon {
+    pub vertices: Vec<Point>,
+}
+
+impl Polygon {
+    /// Create a new [`Polygon`] from its ordered vertices.
+    pub fn new(vertices: Vec<Point>) -> Self {
+        Polygon { vertices }
+    }
+
+    /// The smallest [`BoundingBox`] that contains the [`Polygon`].
+    pub fn bounds(&self) -> BoundingBox {
+        let mut min = Point::new(f64::INFINITY, f64::INFINITY);
+        let mut max = Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY);
+
+        for vertex in self.vertices.iter() {
+            min.x = min.x.min(vertex.x);
+            min.y = min.y.min(vertex.y);
+            max.x = max.x.max(vertex.x);
+            max.y = max.y.max(vertex.y);
+        }
+
+        BoundingBox::new(min, max)
+    }
+
+    /// The area enclosed by the [`Polygon`].
+    pub fn area(&self) -> f64 {
+        self.signed_area().abs()
+    }
+
+    /// The signed area enclosed by the [`Polygon`] (i.e., the shoelace formula).
+    ///
+    /// The sign reflects the winding order of the vertices.
+    fn signed_area(&self) -> f64 {
+        self.edges()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<f64>()
+            / 2.0
+    }
+
+    /// Clip the [`Polygon`] against a convex polygon.
+    ///
+    /// This uses the Sutherland-Hodgman algorithm. The result is the region
+    /// of the [`Polygon`] that lies within the `convex` polygon.
+    pub fn clip(&self, convex: &Polygon) -> Polygon {
+        let sign = convex.signed_area().signum();
+
+        if sign == 0.0 {
+            return Polygon::new(Vec::new());
+        }
+
+        let mut output = self.vertices.clone();
+
+        for (a, b) in convex.edges() {
+            let input = std::mem::take(&mut output);
+            let inside = |p: &Point| Point::orientation(a, b, p) * sign >= 0.0;
+
+            for (i, current) in input.iter().enumerate() {
+                let previous = &input[(i + input.len() - 1) % input.len()];
+
+                match (inside(previous), inside(current)) {
+                    (true, true) => output.push(current.clone()),
+                    (true, false) => output.push(Point::intersection((previous, current), (a, b))),
+                    (false, true) => {
+                        output.push(Point::intersection((previous, current), (a, b)));
+                        output.push(current.clone());
+                    }
+                    (false, false) => continue,
+                }
+            }
+        }
+
+        Polygon::new(output)
+    }
+
+    /// The edges of the [`Polygon`] as pairs of vertices.
+    fn edges(&self) -> impl Iterator<Item = (&Point, &Point)> {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    /// Check whether a [`Point`] lies inside the [`Polygon`].
+    ///
+    /// This uses the even-odd (i.e., ray casting) rule.
+    pub fn contains(&self, point: &Point) -> bool {
+        let mut inside = false;
+
+        for (a, b) in self.edges() {
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+            {
+                inside = !inside;
+            }
+        }
+
+        inside
+    }
+
+    /// Check whether two polygons overlap.
+    ///
+    /// Two polygons overlap if any of their edges cross or if one polygon has a
+    /// vertex inside of the other.
+    pub fn intersects(&self, other: &Polygon) -> bool {
+        let (a, b) = (self.bounds(), other.bounds());
+
+        if !(a.min.x < b.max.x && b.min.x < a.max.x && a.min.y < b.max.y && b.min.y < a.max.y) {
+            return false;
+        }
+
+        for (p, q) in self.edges() {
+            for (r, s) in other.edges() {
+                if Point::crosses((p, q), (r, s)) {
+                    return true;
+                }
+            }
+        }
+
+        self.vertices.iter().any(|v| other.contains(v))
+            || other.vertices.iter().any(|v| self.contains(v))
+    }
+
+    /// The shortest distance between the boundaries of two polygons.
+    ///
+    /// If the polygons overlap, the distance is zero.
+    pub fn distance(&self, other: &Polygon) -> f64 {
+        if self.intersects(other) {
+            return 0.0;
+        }
+
+        let closest = |a: &Polygon, b: &Polygon| {
+            a.vertices
+                .iter()
+                .flat_map(|v| b.edges().map(move |edge| v.distance(edge)))
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        closest(self, other).min(closest(other, self))
+    }
+}
+
+impl From<&BoundingBox> for Polygon {
+    fn from(bbox: &BoundingBox) -> Self {
+        Polygon::new(vec![
+            Point::new(bbox.min.x, bbox.min.y),
+            Point::new(bbox.max.x, bbox.min.y),
+            Point::new(bbox.max.x, bbox.max.y),
+            Point::new(bbox.min.x, bbox.max.y),
+        ])
+    }
+}
+
+/// A binary segmentation mask encoded with Run-Length Encoding (RLE).
+///
+/// The encoding follows the uncompressed COCO convention: the pixels are
+/// traversed in column-major order, and the `counts` alternate between runs of
+/// unset and set pixels, starting with unset pixels. The mask is aligned with
+/// the origin of the image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mask {
+    pub width: usize,
+    pub height: usize,
+    pub counts: Vec<usize>,
+}
+
+impl Mask {
+    /// Create a new [`Mask`] from its run-length `counts`.
+    pub fn new(width: usize, height: usize, counts: Vec<usize>) -> Self {
+        Mask {
+            width,
+            height,
+            counts,
+        }
+    }
+
+    /// Encode a grid of pixels into a [`Mask`].
+    ///
+    /// The `bits` are expected in column-major order.
+    pub fn encode(width: usize, height: usize, bits: &[bool]) -> Self {
+        let mut counts = Vec::new();
+        let (mut current, mut run) = (false, 0);
+
+        for bit in bits.iter() {
+            if *bit != current {
+                counts.push(run);
+                current = *bit;
+                run = 0;
+            }
+
+            run += 1;
+        }
+
+        counts.push(run);
+
+        Mask::new(width, height, counts)
+    }
+
+    /// Decode the [`Mask`] into a grid of pixels in column-major order.
+    pub fn decode(&self) -> Vec<bool> {
+        let size = self.width * self.height;
+        let mut bits = Vec::with_capacity(size);
+
+        for (i, count) in self.counts.iter().enumerate() {
+            bits.extend(std::iter::repeat_n(i % 2 == 1, *count));
+        }
+
+        bits.resize(size, false);
+        bits
+    }
+
+    /// The number of set pixels of the [`Mask`].
+    pub fn area(&self) -> usize {
+        self.counts.iter().skip(1).step_by(2).sum()
+    }
+
+    /// The smallest [`BoundingBox`] that contains the set pixels.
+    pub fn bounds(&self) -> BoundingBox {
+        let mut min = Point::new(f64::INFINITY, f64::INFINITY);
+        let mut max = Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY);
+
+        for (i, bit) in self.decode().into_iter().enumerate() {
+            if bit {
+                let (x, y) = ((i / self.height) as f64, (i % self.height) as f64);
+
+                min.x = min.x.min(x);
+                min.y = min.y.min(y);
+                max.x = max.x.max(x + 1.0);
+                max.y = max.y.max(y + 1.0);
+            }
+        }
+
+        if min.x > max.x {
+            return BoundingBox::new(Point::new(0.0, 0.0), Point::new(0.0, 0.0));
+        }
+
+        BoundingBox::new(min, max)
+    }
 }
 
 /// A Z axis-aligned point (i.e., 2D).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Point {
     pub x: f64,
     pub y: f64,
@@ -74,6 +539,46 @@ impl Point {
     pub fn new(x: f64, y: f64) -> Self {
         Point { x, y }
     }
+
+    /// The orientation of the ordered triplet (`a`, `b`, `c`).
+    ///
+    /// A positive value is counter-clockwise, a negative value is clockwise,
+    /// and zero is collinear.
+    fn orientation(a: &Point, b: &Point, c: &Point) -> f64 {
+        (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+    }
+
+    /// The point where a line segment (`p`, `q`) meets the line through (`a`, `b`).
+    fn intersection((p, q): (&Point, &Point), (a, b): (&Point, &Point)) -> Point {
+        let (o1, o2) = (Self::orientation(a, b, p), Self::orientation(a, b, q));
+        let t = o1 / (o1 - o2);
+
+        Point::new(p.x + t * (q.x - p.x), p.y + t * (q.y - p.y))
+    }
+
+    /// The shortest distance from the [`Point`] to a line segment (`a`, `b`).
+    fn distance(&self, (a, b): (&Point, &Point)) -> f64 {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let length = dx * dx + dy * dy;
+
+        let t = if length > 0.0 {
+            (((self.x - a.x) * dx + (self.y - a.y) * dy) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        (self.x - (a.x + t * dx)).hypot(self.y - (a.y + t * dy))
+    }
+
+    /// Check whether two line segments properly cross each other.
+    ///
+    /// Segments that only touch (e.g., at an endpoint) do not cross.
+    fn crosses((p, q): (&Point, &Point), (r, s): (&Point, &Point)) -> bool {
+        let (o1, o2) = (Self::orientation(p, q, r), Self::orientation(p, q, s));
+        let (o3, o4) = (Self::orientation(r, s, p), Self::orientation(r, s, q));
+
+        o1 * o2 < 0.0 && o3 * o4 < 0.0
+    }
 }
 
 /// An interface to handle image metadata.
@@ -105,3 +610,61 @@ impl Image {
 pub enum ImageSource {
     File(PathBuf),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundingBox, Geometry, Mask, Point, Polygon, RotatedBox};
+
+    #[test]
+    fn polygon_intersects() {
+        let triangle = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(0.0, 10.0),
+        ]);
+
+        // overlapping edges
+        let bbox = BoundingBox::new(Point::new(4.0, 4.0), Point::new(8.0, 8.0));
+        assert!(triangle.intersects(&Polygon::from(&bbox)));
+
+        // contained entirely
+        let bbox = BoundingBox::new(Point::new(1.0, 1.0), Point::new(2.0, 2.0));
+        assert!(triangle.intersects(&Polygon::from(&bbox)));
+
+        // beyond the hypotenuse
+        let bbox = BoundingBox::new(Point::new(6.0, 6.0), Point::new(8.0, 8.0));
+        assert!(!triangle.intersects(&Polygon::from(&bbox)));
+    }
+
+    #[test]
+    fn geometry_intersects() {
+        let triangle = Geometry::Polygon(Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(0.0, 10.0),
+        ]));
+
+        // clipped against a rotated box
+        let rbox = RotatedBox::new(Point::new(6.0, 6.0), 2.0, 2.0, std::f64::consts::FRAC_PI_4);
+        assert!(!triangle.intersects(&Geometry::RotatedBox(rbox)));
+
+        let rbox = RotatedBox::new(Point::new(5.0, 5.0), 2.0, 2.0, std::f64::consts::FRAC_PI_4);
+        assert!(triangle.intersects(&Geometry::RotatedBox(rbox)));
+
+        // compared pixel-wise against a 4x4 mask with a 2x2 block at (2, 2)
+        let mut bits = vec![false; 16];
+        for (x, y) in [(2, 2), (2, 3), (3, 2), (3, 3)] {
+            bits[x * 4 + y] = true;
+        }
+
+        let mask = Mask::encode(4, 4, &bits);
+        assert_eq!(bits, mask.decode());
+        assert_eq!(4, mask.area());
+
+        let bbox = BoundingBox::new(Point::new(0.0, 0.0), Point::new(2.0, 2.0));
+        assert!(!Geometry::Mask(mask.clone()).intersects(&Geometry::BoundingBox(bbox)));
+
+        let bbox = BoundingBox::new(Point::new(1.0, 1.0), Point::new(3.0, 3.0));
+        assert!(Geometry::Mask(mask).intersects(&Geometry::BoundingBox(bbox)));
+    }
+}
@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+/// A sample record of scene classifications produced for a single frame.
+///
+/// This is typically produced from classifiers of the scene as a whole (e.g.,
+/// weather, time of day, road type, etc.).
+#[derive(Clone, Debug)]
+pub struct SceneRecord {
+    pub channel: String,
+    pub timestamp: f64,
+
+    /// A mapping between the labels (i.e., tags) of the scene and their scores.
+    pub tags: HashMap<String, f64>,
+}
+
+impl SceneRecord {
+    /// Create a new [`SceneRecord`].
+    pub fn new(channel: String, timestamp: f64) -> Self {
+        SceneRecord {
+            channel,
+            timestamp,
+            tags: HashMap::new(),
+        }
+    }
+}
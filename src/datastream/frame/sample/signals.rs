@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+/// A sample record of numeric signals produced for a single frame.
+///
+/// This is typically produced from the state of the ego vehicle or its CAN bus
+/// (e.g., speed, steering angle, brake pressure, etc.).
+#[derive(Clone, Debug)]
+pub struct SignalRecord {
+    pub channel: String,
+    pub timestamp: f64,
+
+    /// A mapping between the names of signals and their values.
+    pub signals: HashMap<String, Signal>,
+}
+
+impl SignalRecord {
+    /// Create a new [`SignalRecord`].
+    pub fn new(channel: String, timestamp: f64) -> Self {
+        SignalRecord {
+            channel,
+            timestamp,
+            signals: HashMap::new(),
+        }
+    }
+}
+
+/// The value of a numeric signal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Signal {
+    /// A single value (e.g., speed).
+    Scalar(f64),
+
+    /// A series of values (e.g., acceleration along each axis).
+    Vector(Vec<f64>),
+}
+
+impl Signal {
+    /// Retrieve the value of the [`Signal`].
+    ///
+    /// If an `index` is provided, the component of a [`Signal::Vector`] is
+    /// retrieved; otherwise, its magnitude is. A [`Signal::Scalar`] has no
+    /// components; so, [`None`] is returned when indexed.
+    pub fn value(&self, index: Option<usize>) -> Option<f64> {
+        match (self, index) {
+            (Signal::Scalar(value), None) => Some(*value),
+            (Signal::Scalar(..), Some(..)) => None,
+            (Signal::Vector(values), None) => {
+                Some(values.iter().map(|v| v * v).sum::<f64>().sqrt())
+            }
+            (Signal::Vector(values), Some(i)) => values.get(i).copied(),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::io::BufReader;
@@ -5,9 +6,13 @@ use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use crate::datastream::frame::sample::cuboids::{Cuboid, CuboidAnnotation, CuboidRecord, Point3};
 use crate::datastream::frame::sample::detections::{
-    Annotation, BoundingBox, DetectionRecord, Image, ImageSource, Point,
+    Annotation, BoundingBox, DetectionRecord, Geometry, Image, ImageSource, Mask, Point, Polygon,
+    RotatedBox,
 };
+use crate::datastream::frame::sample::scenes::SceneRecord;
+use crate::datastream::frame::sample::signals::{Signal, SignalRecord};
 use crate::datastream::frame::sample::Sample;
 use crate::datastream::frame::Frame;
 
@@ -32,6 +37,151 @@ impl DataImporter {
     }
 }
 
+impl DataImporter {
+    /// Convert the region of a STREM-formatted annotation into a [`Geometry`].
+    ///
+    /// Exactly one kind of region must be provided by the annotation.
+    fn geometry(a: &StremAnnotation) -> Result<Geometry, Box<dyn Error>> {
+        if a.cuboid.is_some() {
+            return Err(Box::new(DataImporterError::from(format!(
+                "annotation `{}` must not define a `cuboid` in a sample with an `image`",
+                a.class
+            ))));
+        }
+
+        match (&a.bbox, &a.rbox, &a.polygon, &a.mask) {
+            (Some(bbox), None, None, None) => Ok(Geometry::BoundingBox(BoundingBox::new(
+                Point::new(bbox.x, bbox.y),
+                Point::new(bbox.x + bbox.w, bbox.y + bbox.h),
+            ))),
+            (None, Some(rbox), None, None) => Ok(Geometry::RotatedBox(RotatedBox::new(
+                Point::new(rbox.cx, rbox.cy),
+                rbox.w,
+                rbox.h,
+                rbox.angle,
+            ))),
+            (None, None, Some(polygon), None) => Ok(Geometry::Polygon(Polygon::new(
+                polygon.iter().map(|p| Point::new(p.x, p.y)).collect(),
+            ))),
+            (None, None, None, Some(mask)) => Ok(Geometry::Mask(Mask::new(
+                mask.size[1],
+                mask.size[0],
+                mask.counts.clone(),
+            ))),
+            _ => Err(Box::new(DataImporterError::from(format!(
+                "annotation `{}` must define exactly one of `bbox`, `rbox`, `polygon`, or `mask`",
+                a.class
+            )))),
+        }
+    }
+
+    /// Convert the region of a STREM-formatted annotation into a [`Cuboid`].
+    ///
+    /// Only a `cuboid` may be provided by the annotation.
+    fn cuboid(a: &StremAnnotation) -> Result<Cuboid, Box<dyn Error>> {
+        match (&a.cuboid, &a.bbox, &a.rbox, &a.polygon, &a.mask) {
+            (Some(c), None, None, None, None) => Ok(Cuboid::new(
+                Point3::new(c.x, c.y, c.z),
+                c.l,
+                c.w,
+                c.h,
+                c.yaw,
+            )),
+            _ => Err(Box::new(DataImporterError::from(format!(
+                "annotation `{}` must define only a `cuboid` in a sample without an `image`",
+                a.class
+            )))),
+        }
+    }
+
+    /// Convert a STREM-formatted sample into a [`Sample`].
+    ///
+    /// A sample with signals is a sample of numeric signals, a sample with tags
+    /// is a sample of scene classifications, and a sample with an image is a
+    /// sample of 2D object detections; otherwise, it is a sample of 3D object
+    /// detections (i.e., cuboids).
+    fn sample(s: &StremSample) -> Result<Sample, Box<dyn Error>> {
+        if let Some(tags) = &s.tags {
+            if s.image.is_some() || !s.annotations.is_empty() || s.signals.is_some() {
+                return Err(Box::new(DataImporterError::from(format!(
+                    "sample of `{}` with `tags` must not define an `image`, `annotations`, or `signals`",
+                    s.channel
+                ))));
+            }
+
+            let mut record = SceneRecord::new(s.channel.clone(), s.timestamp);
+
+            for tag in tags.iter() {
+                record.tags.insert(tag.label.clone(), tag.score);
+            }
+
+            return Ok(Sample::Scene(record));
+        }
+
+        if let Some(signals) = &s.signals {
+            if s.image.is_some() || !s.annotations.is_empty() {
+                return Err(Box::new(DataImporterError::from(format!(
+                    "sample of `{}` with `signals` must not define an `image` or `annotations`",
+                    s.channel
+                ))));
+            }
+
+            let mut record = SignalRecord::new(s.channel.clone(), s.timestamp);
+
+            for (name, signal) in signals.iter() {
+                let signal = match signal {
+                    StremSignal::Scalar(value) => Signal::Scalar(*value),
+                    StremSignal::Vector(values) => Signal::Vector(values.clone()),
+                };
+
+                record.signals.insert(name.clone(), signal);
+            }
+
+            return Ok(Sample::Signal(record));
+        }
+
+        if let Some(image) = &s.image {
+            let mut record = DetectionRecord::new(
+                s.channel.clone(),
+                s.timestamp,
+                Some(Image::new(
+                    ImageSource::File(PathBuf::from(&image.path)),
+                    image.dimensions.width,
+                    image.dimensions.height,
+                )),
+            );
+
+            // Add annotations to the [`DetectionRecord`].
+            for a in s.annotations.iter() {
+                let geometry = Self::geometry(a)?;
+
+                record
+                    .annotations
+                    .entry(a.class.clone())
+                    .or_default()
+                    .push(Annotation::new(a.class.clone(), a.score, geometry));
+            }
+
+            return Ok(Sample::ObjectDetection(record));
+        }
+
+        let mut record = CuboidRecord::new(s.channel.clone(), s.timestamp);
+
+        // Add annotations to the [`CuboidRecord`].
+        for a in s.annotations.iter() {
+            let cuboid = Self::cuboid(a)?;
+
+            record
+                .annotations
+                .entry(a.class.clone())
+                .or_default()
+                .push(CuboidAnnotation::new(a.class.clone(), a.score, cuboid));
+        }
+
+        Ok(Sample::CuboidDetection(record))
+    }
+}
+
 impl DataImport for DataImporter {
     /// From the provided source, load the data.
     ///
@@ -83,31 +233,7 @@ impl DataImport for DataImporter {
                         }
                     }
 
-                    let mut record = DetectionRecord::new(
-                        s.channel.clone(),
-                        s.timestamp,
-                        Some(Image::new(
-                            ImageSource::File(PathBuf::from(&s.image.path)),
-                            s.image.dimensions.width,
-                            s.image.dimensions.height,
-                        )),
-                    );
-
-                    // Add annotations to the [`DetectionRecord`].
-                    for a in s.annotations.iter() {
-                        let bbox = BoundingBox::new(
-                            Point::new(a.bbox.x, a.bbox.y),
-                            Point::new(a.bbox.x + a.bbox.w, a.bbox.y + a.bbox.h),
-                        );
-
-                        record
-                            .annotations
-                            .entry(a.class.clone())
-                            .or_default()
-                            .push(Annotation::new(a.class.clone(), a.score, bbox));
-                    }
-
-                    frame.samples.push(Sample::ObjectDetection(record));
+                    frame.samples.push(Self::sample(s)?);
                 }
 
                 return Ok(Some(frame));
@@ -135,8 +261,18 @@ pub struct StremFrame {
 pub struct StremSample {
     pub channel: String,
     pub timestamp: f64,
-    pub image: StremImage,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<StremImage>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub annotations: Vec<StremAnnotation>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signals: Option<HashMap<String, StremSignal>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<StremTag>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -155,7 +291,21 @@ pub struct StremDimension {
 pub struct StremAnnotation {
     pub class: String,
     pub score: f64,
-    pub bbox: StremBoundingBox,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<StremBoundingBox>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rbox: Option<StremRotatedBox>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polygon: Option<Vec<StremPoint>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<StremMask>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cuboid: Option<StremCuboid>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -166,6 +316,59 @@ pub struct StremBoundingBox {
     pub h: f64,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StremRotatedBox {
+    pub cx: f64,
+    pub cy: f64,
+    pub w: f64,
+    pub h: f64,
+    pub angle: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StremPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// An uncompressed COCO-style Run-Length Encoding (RLE).
+///
+/// The `size` is the [height, width] of the mask.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StremMask {
+    pub size: [usize; 2],
+    pub counts: Vec<usize>,
+}
+
+/// A cuboid in the ego frame (i.e., x forward, y left, z up).
+///
+/// The (`x`, `y`, `z`) is the center of the cuboid, and the `yaw` (in radians)
+/// rotates its length from the x-axis towards the y-axis.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StremCuboid {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub l: f64,
+    pub w: f64,
+    pub h: f64,
+    pub yaw: f64,
+}
+
+/// The value of a numeric signal (i.e., a number or an array of numbers).
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum StremSignal {
+    Scalar(f64),
+    Vector(Vec<f64>),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StremTag {
+    pub label: String,
+    pub score: f64,
+}
+
 #[derive(Debug, Clone)]
 struct DataImporterError {
     msg: String,
@@ -10,4 +10,8 @@ pub mod controller;
 pub mod datastream;
 pub mod matcher;
 pub mod monitor;
+pub mod query;
+pub mod spre;
 pub mod symbolizer;
+
+pub use spre::SpRE;
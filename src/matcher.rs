@@ -2,22 +2,82 @@
 //!
 
 use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 
 use crate::compiler::ir::{Node, Operator, RangeKind, RegexOperatorKind};
 use crate::datastream::frame::Frame;
 use crate::symbolizer::ast::{SymbolicAbstractSyntaxTree, SymbolicFormula};
 
+use self::record::Record;
+use self::symbols::SymbolSet;
+
 pub mod automata;
+pub mod events;
+pub mod filters;
 pub mod offline;
 pub mod online;
+pub mod probabilistic;
+pub mod record;
+pub mod symbols;
+pub mod threads;
 
 /// A trait for which all matchers must implement.
 ///
 /// This is defined to provide a ubiquitous interface for all matchers to adhere
 /// to for simplicity of switching (e.g., facade pattern).
 pub trait Matching {
-    /// Find a possible leftmost [`Match`] from the set of [`Frame`].
-    fn leftmost(&self, frames: &[Frame]) -> Result<Option<Match>, Box<dyn Error>>;
+    /// The [`MatchKind`] reported by [`Matching::find`].
+    fn kind(&self) -> MatchKind;
+
+    /// Evaluate the [`SymbolSet`] satisfied by a [`Frame`].
+    ///
+    /// This should be computed once per [`Frame`] (in order) and kept alongside
+    /// it for as long as the [`Frame`] is searched over.
+    fn evaluate(&self, frame: &Frame) -> SymbolSet;
+
+    /// Find a possible leftmost [`Match`] from the symbols of a set of [`Frame`].
+    fn leftmost(&self, symbols: &[SymbolSet]) -> Result<Option<Match>, Box<dyn Error>>;
+
+    /// Find every [`Match`] of the [`MatchKind`] from the symbols of a set of
+    /// [`Frame`].
+    fn find(&self, symbols: &[SymbolSet]) -> Result<Vec<Match>, Box<dyn Error>>;
+
+    /// Resolve the [`Frame`](s) of a [`Match`] (and their symbols) into a
+    /// [`Record`].
+    fn record(&self, frames: &[Frame], symbols: &[SymbolSet]) -> Result<Record, Box<dyn Error>>;
+}
+
+/// The kinds of [`Match`] to report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchKind {
+    /// The leftmost start with the longest end, without overlapping.
+    LeftmostLongest,
+
+    /// The leftmost start with the shortest end, without overlapping (e.g.,
+    /// similar to a lazy `*?`).
+    LeftmostShortest,
+
+    /// Every (start, end) pair that matches.
+    AllOverlapping,
+
+    /// Every end that matches, with the leftmost start (i.e., the longest
+    /// match ending there).
+    AllEnds,
+}
+
+impl FromStr for MatchKind {
+    type Err = MatcherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "leftmost-longest" => Ok(MatchKind::LeftmostLongest),
+            "leftmost-shortest" => Ok(MatchKind::LeftmostShortest),
+            "all-overlapping" => Ok(MatchKind::AllOverlapping),
+            "all-ends" => Ok(MatchKind::AllEnds),
+            _ => Err(MatcherError::from(format!("unknown match kind `{}`", s))),
+        }
+    }
 }
 
 /// A range of valid indices.
@@ -25,7 +85,7 @@ pub trait Matching {
 /// It should be noted that `start` is inclusive (closed) while `end` is
 /// exclusive (open); so a [`Match`] takes the form: [start, end). This is also
 /// referred to as a half-open interval.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Match {
     pub start: usize,
     pub end: usize,
@@ -41,10 +101,23 @@ impl Match {
 /// Construct a Regular Expression (RE) pattern from a [`SymbolicAbstractSyntaxTree`].
 ///
 /// This traverses the outer components of a SpRE related solely to the RE-based
-/// patterns and symbols.
+/// patterns and symbols. A symbol that is matched approximately may also be
+/// substituted by its [`symbols::mismatch`]; the number of substitutions is
+/// bounded by the automaton, instead (see [`automata::approximate`]).
+///
+/// An intersection or complement has no equivalent RE. So, these are replaced
+/// by their left sub-pattern and by any sequence of frames, respectively. The
+/// RE then matches (at least) every match of the pattern, and the pattern
+/// itself is matched by a [`BooleanAutomaton`](automata::boolean::BooleanAutomaton).
 pub fn regexify(ast: &SymbolicAbstractSyntaxTree) -> String {
     if let Some(root) = &ast.root {
-        return self::regexit(root);
+        let approximate = ast
+            .approximations()
+            .into_iter()
+            .flat_map(|approximation| approximation.symbols)
+            .collect::<Vec<_>>();
+
+        return self::regexit(root, &approximate);
     }
 
     String::new()
@@ -54,11 +127,18 @@ pub fn regexify(ast: &SymbolicAbstractSyntaxTree) -> String {
 ///
 /// This is the helper function that walks the root [`Node`] of a
 /// [`SymbolicAbstractSyntaxTree`] to build the appropriate pattern.
-fn regexit(node: &Node<SymbolicFormula>) -> String {
+fn regexit(node: &Node<SymbolicFormula>, approximate: &[char]) -> String {
     match node {
-        Node::Operand(formula) => String::from(formula.symbol),
+        Node::Operand(formula) => match approximate.contains(&formula.symbol) {
+            true => format!(
+                "({}|\\x{:02x})",
+                formula.symbol,
+                symbols::mismatch(formula.symbol)
+            ),
+            false => String::from(formula.symbol),
+        },
         Node::UnaryExpr { op, child } => {
-            let child = self::regexit(child);
+            let child = self::regexit(child, approximate);
 
             match op {
                 Operator::RegexOperator(kind) => match kind {
@@ -68,19 +148,22 @@ fn regexit(node: &Node<SymbolicFormula>) -> String {
                         RangeKind::AtLeast(min) => format!("({}{{{},}})", child, min),
                         RangeKind::Between(min, max) => format!("({}{{{},{}}})", child, min, max),
                     },
+                    RegexOperatorKind::Approximate(..) => child,
+                    RegexOperatorKind::Complement => String::from("([\\x00-\\x7f]*)"),
                     _ => String::new(),
                 },
                 _ => String::new(),
             }
         }
         Node::BinaryExpr { op, left, right } => {
-            let left = self::regexit(left);
-            let right = self::regexit(right);
+            let left = self::regexit(left, approximate);
+            let right = self::regexit(right, approximate);
 
             match op {
                 Operator::RegexOperator(kind) => match kind {
                     RegexOperatorKind::Concatenation => format!("({}{})", left, right),
                     RegexOperatorKind::Alternation => format!("({}|{})", left, right),
+                    RegexOperatorKind::Intersection => left,
                     _ => String::new(),
                 },
                 _ => String::new(),
@@ -88,3 +171,30 @@ fn regexit(node: &Node<SymbolicFormula>) -> String {
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct MatcherError {
+    msg: String,
+}
+
+impl From<&str> for MatcherError {
+    fn from(msg: &str) -> Self {
+        MatcherError {
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl From<String> for MatcherError {
+    fn from(msg: String) -> Self {
+        MatcherError { msg }
+    }
+}
+
+impl fmt::Display for MatcherError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "matcher: {}", self.msg)
+    }
+}
+
+impl Error for MatcherError {}
@@ -1,11 +1,347 @@
-use regex_automata::dfa::{dense, Automaton};
+use std::error::Error;
+use std::str::FromStr;
+
+use regex_automata::dfa::{dense, Automaton, StartError};
 use regex_automata::util::primitives::StateID;
+use regex_automata::util::start::Config;
+use regex_automata::Anchored;
+
+use crate::symbolizer::ast::SymbolicAbstractSyntaxTree as AST;
+
+use self::approximate::ApproximateAutomaton;
+use self::boolean::BooleanAutomaton;
+use self::counting::CountingAutomaton;
+use self::lazy::LazyAutomaton;
+
+use super::symbols::SymbolSet;
+use super::MatcherError;
 
+pub mod approximate;
+pub mod boolean;
+pub mod counting;
 pub mod dfa;
+pub mod lazy;
+
+/// The default limit (in bytes) on the size of a dense DFA.
+///
+/// This is well above the size of the DFA of typical patterns (i.e., a few
+/// kilobytes), such that only patterns that would take long to determinize
+/// and minimize fall back to a [`LazyAutomaton`].
+pub const BUDGET: usize = 1 << 20;
+
+/// The underlying representation of a DFA.
+///
+/// The dense DFA of `regex-automata` is the default choice, as every transition
+/// is built up front. However, its size may grow exponentially with the pattern
+/// (e.g., with large bounded repetitions), in which case the [`LazyAutomaton`]
+/// builds only the states that are reached, instead. Similarly, the
+/// [`CountingAutomaton`] keeps a counter for large bounded repetitions rather
+/// than expanding them. Any of these may be wrapped by an
+/// [`ApproximateAutomaton`] to bound the mismatches of a pattern, or composed
+/// by a [`BooleanAutomaton`] to intersect and complement sub-patterns.
+#[derive(Debug)]
+pub enum AutomatonType {
+    Dense(Box<dense::DFA<Vec<u32>>>),
+    Lazy(LazyAutomaton),
+    Counting(CountingAutomaton),
+    Approximate(Box<ApproximateAutomaton>),
+    Boolean(Box<BooleanAutomaton>),
+}
+
+/// The states reached by a single transition of an [`AutomatonType`].
+///
+/// Only a [`CountingAutomaton`] (even if wrapped) may reach several states.
+pub enum Successors {
+    One(std::iter::Once<StateID>),
+    Many(std::vec::IntoIter<StateID>),
+}
+
+impl Iterator for Successors {
+    type Item = StateID;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Successors::One(it) => it.next(),
+            Successors::Many(it) => it.next(),
+        }
+    }
+}
+
+impl AutomatonType {
+    /// Retrieve the start state for the configuration provided.
+    pub fn start_state(&self, config: &Config) -> Result<StateID, StartError> {
+        match self {
+            AutomatonType::Dense(dfa) => dfa.start_state(config),
+            AutomatonType::Lazy(dfa) => Ok(dfa.start_state(config.get_anchored())),
+            AutomatonType::Counting(nfa) => Ok(nfa.start_state(config.get_anchored())),
+            AutomatonType::Approximate(nfa) => nfa.start_state(config),
+            AutomatonType::Boolean(dfa) => Ok(dfa.start_state()),
+        }
+    }
+
+    /// Take the transition(s) on a byte from a state.
+    #[inline]
+    pub fn next_states(&self, sid: StateID, byte: u8) -> Successors {
+        match self {
+            AutomatonType::Dense(dfa) => {
+                Successors::One(std::iter::once(dfa.next_state(sid, byte)))
+            }
+            AutomatonType::Lazy(dfa) => Successors::One(std::iter::once(dfa.next_state(sid, byte))),
+            AutomatonType::Counting(nfa) => {
+                Successors::Many(nfa.next_states(sid, byte).into_iter())
+            }
+            AutomatonType::Approximate(nfa) => {
+                Successors::Many(nfa.next_states(sid, byte).into_iter())
+            }
+            AutomatonType::Boolean(dfa) => {
+                Successors::One(std::iter::once(dfa.next_byte_state(sid, byte)))
+            }
+        }
+    }
+
+    /// Take the transition on the symbols satisfied by an entire frame from a
+    /// state, if the automaton steps over frames rather than bytes.
+    ///
+    /// Only a [`BooleanAutomaton`] steps over frames. Otherwise, [`None`] is
+    /// returned, and the transitions on each symbol should be taken instead.
+    #[inline]
+    pub fn next_frame_state(&self, sid: StateID, symbols: &SymbolSet) -> Option<StateID> {
+        match self {
+            AutomatonType::Boolean(dfa) => Some(dfa.next_state(sid, symbols)),
+            _ => None,
+        }
+    }
+
+    /// Take the transition on the End of Input (EOI) from a state.
+    #[inline]
+    pub fn next_eoi_state(&self, sid: StateID) -> StateID {
+        match self {
+            AutomatonType::Dense(dfa) => dfa.next_eoi_state(sid),
+            AutomatonType::Lazy(dfa) => dfa.next_eoi_state(sid),
+            AutomatonType::Counting(nfa) => nfa.next_eoi_state(sid),
+            AutomatonType::Approximate(nfa) => nfa.next_eoi_state(sid),
+            AutomatonType::Boolean(dfa) => dfa.next_eoi_state(sid),
+        }
+    }
+
+    /// Check whether a state is a match state.
+    #[inline]
+    pub fn is_match_state(&self, sid: StateID) -> bool {
+        match self {
+            AutomatonType::Dense(dfa) => dfa.is_match_state(sid),
+            AutomatonType::Lazy(dfa) => dfa.is_match_state(sid),
+            AutomatonType::Counting(nfa) => nfa.is_match_state(sid),
+            AutomatonType::Approximate(nfa) => nfa.is_match_state(sid),
+            AutomatonType::Boolean(dfa) => dfa.is_match_state(sid),
+        }
+    }
+
+    /// Check whether a state is the dead state.
+    #[inline]
+    pub fn is_dead_state(&self, sid: StateID) -> bool {
+        match self {
+            AutomatonType::Dense(dfa) => dfa.is_dead_state(sid),
+            AutomatonType::Lazy(dfa) => dfa.is_dead_state(sid),
+            AutomatonType::Counting(nfa) => nfa.is_dead_state(sid),
+            AutomatonType::Approximate(nfa) => nfa.is_dead_state(sid),
+            AutomatonType::Boolean(dfa) => dfa.is_dead_state(sid),
+        }
+    }
+
+    /// Check whether a state is a start state.
+    #[inline]
+    pub fn is_start_state(&self, sid: StateID) -> bool {
+        match self {
+            AutomatonType::Dense(dfa) => dfa.is_start_state(sid),
+            AutomatonType::Lazy(dfa) => dfa.is_start_state(sid),
+            AutomatonType::Counting(nfa) => nfa.is_start_state(sid),
+            AutomatonType::Approximate(nfa) => nfa.is_start_state(sid),
+            AutomatonType::Boolean(dfa) => dfa.is_start_state(sid),
+        }
+    }
+
+    /// Merge the states within a set of live states that are equivalent.
+    ///
+    /// Only a [`CountingAutomaton`] merges the runs of its counters (see
+    /// [`CountingAutomaton::merge`]). The result is sorted without duplicates.
+    pub fn merge(&self, states: &mut Vec<StateID>) {
+        match self {
+            AutomatonType::Counting(nfa) => nfa.merge(states),
+            _ => {
+                states.sort_unstable();
+                states.dedup();
+            }
+        }
+    }
+
+    /// The number of frames mismatched to reach a state.
+    ///
+    /// This is always zero, unless mismatches are bounded (i.e., by an
+    /// [`ApproximateAutomaton`]).
+    #[inline]
+    pub fn errors(&self, sid: StateID) -> usize {
+        match self {
+            AutomatonType::Approximate(nfa) => nfa.errors(sid),
+            _ => 0,
+        }
+    }
+}
+
+/// The kinds of [`AutomatonType`] to build.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// A dense DFA that is built up front.
+    Dense,
+
+    /// A DFA that is built lazily (see [`LazyAutomaton`]).
+    Lazy,
+
+    /// An automaton with counters for bounded repetitions (see
+    /// [`CountingAutomaton`]).
+    Counting,
+}
+
+impl FromStr for Backend {
+    type Err = MatcherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dense" => Ok(Backend::Dense),
+            "lazy" => Ok(Backend::Lazy),
+            "counting" => Ok(Backend::Counting),
+            _ => Err(MatcherError::from(format!("unknown backend `{}`", s))),
+        }
+    }
+}
+
+/// The options used to build an [`AutomatonType`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// The [`Backend`] to build. If this is `None`, then a dense DFA is built
+    /// unless it exceeds the `budget`, in which case either a counting
+    /// automaton or lazy DFA is built.
+    pub backend: Option<Backend>,
+
+    /// The limit (in bytes) on the size of a dense DFA, which also limits the
+    /// transitions cached by a lazy DFA.
+    pub budget: usize,
+}
+
+impl Options {
+    /// Create a new default set of [`Options`].
+    pub fn new() -> Self {
+        Options {
+            backend: None,
+            budget: BUDGET,
+        }
+    }
 
-/// The underlying representation of the `regex-automata` DFA. From their own
-/// implementation, this is the default choice.
-pub type AutomatonType = dense::DFA<Vec<u32>>;
+    /// Set the [`Backend`] to build.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Set the limit (in bytes) on the size of a dense DFA.
+    pub fn budget(mut self, budget: usize) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Build an [`AutomatonType`] of a [`AST`] with these [`Options`].
+    ///
+    /// The `dense` function builds a dense DFA of a pattern limited to a size
+    /// (if any). The other backends are built from the [`AST`] directly, in
+    /// the direction (i.e., `reverse`) provided.
+    ///
+    /// When no [`Backend`] is set, any failure to build the dense DFA within
+    /// the `budget` falls back to another backend. The `regex-automata` library
+    /// limits both the size of the DFA and the memory used to determinize it;
+    /// so, a pattern too large for the `budget` fails early, rather than
+    /// after it is entirely determinized. A counting automaton is preferred if
+    /// any repetition of the pattern is counted, as a lazy DFA still expands
+    /// it; otherwise, a lazy DFA is built.
+    ///
+    /// If any sub-pattern is matched approximately, then the forward automaton
+    /// is wrapped by an [`ApproximateAutomaton`]. The reverse automaton is
+    /// only used to locate where matches may start; so, it is left unbounded.
+    ///
+    /// Similarly, if any sub-pattern is intersected or complemented, then both
+    /// automata are a [`BooleanAutomaton`] of the sub-patterns built by the
+    /// [`Backend`] (in their direction). The reverse automaton is unanchored;
+    /// so, the `dense` function must also support anchored searches (i.e.,
+    /// for its sub-patterns).
+    pub fn build(
+        &self,
+        ast: &AST,
+        reverse: bool,
+        dense: impl Fn(&str, Option<usize>) -> Result<dense::DFA<Vec<u32>>, Box<dyn Error>>,
+    ) -> Result<AutomatonType, Box<dyn Error>> {
+        let approximations = ast.approximations();
+
+        if ast.is_boolean() {
+            if !approximations.is_empty() {
+                return Err(Box::new(MatcherError::from(
+                    "approximate matching is not supported with `&&` or `~`",
+                )));
+            }
+
+            let anchored = match reverse {
+                true => Anchored::No,
+                false => Anchored::Yes,
+            };
+
+            let dfa = BooleanAutomaton::build(ast, reverse, anchored, |leaf| {
+                self.automaton(leaf, reverse, &dense)
+            })?;
+
+            return Ok(AutomatonType::Boolean(Box::new(dfa)));
+        }
+
+        let automaton = self.automaton(ast, reverse, &dense)?;
+
+        if reverse || approximations.is_empty() {
+            return Ok(automaton);
+        }
+
+        Ok(AutomatonType::Approximate(Box::new(
+            ApproximateAutomaton::new(automaton, &approximations),
+        )))
+    }
+
+    /// Build the [`AutomatonType`] of the [`Backend`] of a [`AST`] (see
+    /// [`Options::build`]).
+    fn automaton(
+        &self,
+        ast: &AST,
+        reverse: bool,
+        dense: &impl Fn(&str, Option<usize>) -> Result<dense::DFA<Vec<u32>>, Box<dyn Error>>,
+    ) -> Result<AutomatonType, Box<dyn Error>> {
+        let pattern = super::regexify(ast);
+        let lazy = || LazyAutomaton::build(&pattern, reverse).map(|dfa| dfa.capacity(self.budget));
+
+        Ok(match self.backend {
+            Some(Backend::Dense) => AutomatonType::Dense(Box::new(dense(&pattern, None)?)),
+            Some(Backend::Lazy) => AutomatonType::Lazy(lazy()?),
+            Some(Backend::Counting) => {
+                AutomatonType::Counting(CountingAutomaton::build(ast, reverse)?)
+            }
+            None => match dense(&pattern, Some(self.budget)) {
+                Ok(dfa) => AutomatonType::Dense(Box::new(dfa)),
+                Err(..) => match CountingAutomaton::build(ast, reverse) {
+                    Ok(nfa) if nfa.is_counting() => AutomatonType::Counting(nfa),
+                    _ => AutomatonType::Lazy(lazy()?),
+                },
+            },
+        })
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options::new()
+    }
+}
 
 #[derive(Hash, PartialEq, Eq, Debug)]
 pub enum State {
@@ -0,0 +1,222 @@
+//! An automaton that bounds the mismatches of approximate sub-patterns.
+//!
+//! A sub-pattern `(...)~k` matches where up to `k` frames violate the spatial
+//! formula expected of them (e.g., a pedestrian missed by the detector for a
+//! couple of frames). Each symbol of such a sub-pattern may be substituted by
+//! its mismatch (see [`symbols::mismatch`]), which a frame satisfies only when
+//! it does not satisfy the symbol itself. The automaton of every backend is
+//! built from this pattern, which allows any number of substitutions; so, this
+//! automaton wraps it and counts the mismatches taken towards each budget.
+//!
+//! Mismatches are counted along a path from the anchored start. Therefore, a
+//! budget holds for an entire match, even if the sub-pattern is repeated. An
+//! unanchored search merges the paths of every start into the same states, so
+//! it is never bounded (see [`Options::build`]).
+//!
+//! [`symbols::mismatch`]: crate::matcher::symbols::mismatch
+//! [`Options::build`]: super::Options::build
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use regex_automata::dfa::StartError;
+use regex_automata::util::primitives::StateID;
+use regex_automata::util::start::Config;
+
+use crate::matcher::symbols;
+use crate::symbolizer::ast::Approximation;
+
+use super::AutomatonType;
+
+/// The [`StateID`] of the dead state.
+const DEAD: StateID = StateID::ZERO;
+
+/// A state of the [`ApproximateAutomaton`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ApproximateState {
+    /// The state of the wrapped automaton.
+    sid: StateID,
+
+    /// The mismatches taken towards each budget.
+    errors: Vec<usize>,
+
+    /// The number of frames mismatched.
+    total: usize,
+}
+
+/// The states built so far.
+#[derive(Debug, Default)]
+struct Cache {
+    states: Vec<ApproximateState>,
+    ids: HashMap<ApproximateState, StateID>,
+}
+
+/// An automaton that bounds the mismatches taken by another automaton.
+///
+/// As with the [`LazyAutomaton`](super::lazy::LazyAutomaton), states are
+/// built as they are reached and never evicted. The states are shared behind a
+/// lock; so, the automaton may be searched from several threads at once.
+#[derive(Debug)]
+pub struct ApproximateAutomaton {
+    automaton: AutomatonType,
+    budgets: Vec<usize>,
+
+    /// The budgets (by index) that the mismatch of each byte counts towards.
+    groups: Vec<Vec<usize>>,
+
+    cache: RwLock<Cache>,
+}
+
+impl ApproximateAutomaton {
+    /// Create a new [`ApproximateAutomaton`] bounding the mismatches of the
+    /// [`Approximation`](s) taken by an [`AutomatonType`].
+    pub fn new(automaton: AutomatonType, approximations: &[Approximation]) -> Self {
+        let mut groups = vec![Vec::new(); 256];
+
+        for (i, approximation) in approximations.iter().enumerate() {
+            for symbol in approximation.symbols.iter() {
+                groups[symbols::mismatch(*symbol) as usize].push(i);
+            }
+        }
+
+        let cache = Cache {
+            // The dead state is always the first state. It is never interned,
+            // as its state of the wrapped automaton is irrelevant.
+            states: vec![ApproximateState {
+                sid: StateID::ZERO,
+                errors: Vec::new(),
+                total: 0,
+            }],
+            ids: HashMap::new(),
+        };
+
+        ApproximateAutomaton {
+            automaton,
+            budgets: approximations.iter().map(|x| x.budget).collect(),
+            groups,
+            cache: RwLock::new(cache),
+        }
+    }
+
+    /// Retrieve the start state for the configuration provided.
+    pub fn start_state(&self, config: &Config) -> Result<StateID, StartError> {
+        let sid = self.automaton.start_state(config)?;
+
+        Ok(self.intern(ApproximateState {
+            sid,
+            errors: vec![0; self.budgets.len()],
+            total: 0,
+        }))
+    }
+
+    /// Take the transitions on a byte from a state.
+    ///
+    /// As matches are delayed by a single transition, a state that exceeds a
+    /// budget is kept alive only if it reports a match (of the frames before
+    /// the mismatch). It is dead on the next transition.
+    pub fn next_states(&self, sid: StateID, byte: u8) -> Vec<StateID> {
+        let state = self.state(sid);
+
+        if sid == DEAD || self.exceeds(&state.errors) {
+            return vec![DEAD];
+        }
+
+        let mut errors = state.errors;
+        let mut total = state.total;
+
+        let groups = &self.groups[usize::from(byte)];
+
+        if !groups.is_empty() {
+            total += 1;
+
+            for i in groups.iter() {
+                errors[*i] += 1;
+            }
+        }
+
+        let exceeds = self.exceeds(&errors);
+
+        self.automaton
+            .next_states(state.sid, byte)
+            .map(|next| {
+                if self.automaton.is_dead_state(next)
+                    || (exceeds && !self.automaton.is_match_state(next))
+                {
+                    return DEAD;
+                }
+
+                self.intern(ApproximateState {
+                    sid: next,
+                    errors: errors.clone(),
+                    total,
+                })
+            })
+            .collect()
+    }
+
+    /// Take the transition on the EOI from a state.
+    pub fn next_eoi_state(&self, sid: StateID) -> StateID {
+        let state = self.state(sid);
+
+        if sid == DEAD || self.exceeds(&state.errors) {
+            return DEAD;
+        }
+
+        self.intern(ApproximateState {
+            sid: self.automaton.next_eoi_state(state.sid),
+            ..state
+        })
+    }
+
+    /// Check whether a state is a match state.
+    pub fn is_match_state(&self, sid: StateID) -> bool {
+        sid != DEAD && self.automaton.is_match_state(self.state(sid).sid)
+    }
+
+    /// Check whether a state is the dead state.
+    pub fn is_dead_state(&self, sid: StateID) -> bool {
+        sid == DEAD
+    }
+
+    /// Check whether a state is a start state.
+    pub fn is_start_state(&self, sid: StateID) -> bool {
+        let state = self.state(sid);
+        sid != DEAD && state.total == 0 && self.automaton.is_start_state(state.sid)
+    }
+
+    /// The number of frames mismatched to reach a state.
+    pub fn errors(&self, sid: StateID) -> usize {
+        self.state(sid).total
+    }
+
+    /// Check whether any budget is exceeded.
+    fn exceeds(&self, errors: &[usize]) -> bool {
+        errors
+            .iter()
+            .zip(self.budgets.iter())
+            .any(|(errors, budget)| errors > budget)
+    }
+
+    /// Retrieve a state by its [`StateID`].
+    fn state(&self, sid: StateID) -> ApproximateState {
+        self.cache.read().unwrap().states[sid.as_usize()].clone()
+    }
+
+    /// Find the [`StateID`] of a state, adding it if it is new.
+    fn intern(&self, state: ApproximateState) -> StateID {
+        let mut cache = self.cache.write().unwrap();
+
+        if let Some(sid) = cache.ids.get(&state) {
+            return *sid;
+        }
+
+        // The number of states is bounded by the memory available long before
+        // the limit of a [`StateID`] is reached.
+        let sid = StateID::must(cache.states.len());
+
+        cache.states.push(state.clone());
+        cache.ids.insert(state, sid);
+
+        sid
+    }
+}
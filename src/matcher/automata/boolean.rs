@@ -0,0 +1,690 @@
+//! An automaton that intersects and complements sub-patterns.
+//!
+//! Regular languages are closed under intersection and complement, but an RE
+//! has an operator for neither. So, a pattern with `&&` or `~(...)` is split
+//! into its largest sub-patterns that are REs, each of which is built by a
+//! backend as before (see [`Options::build`]), and this automaton composes
+//! them (e.g., by their product for `&&`).
+//!
+//! Unlike the other automata, this automaton steps over the symbols of an
+//! entire frame at once. A frame may satisfy several symbols, and a sub-pattern
+//! is matched if any of them leads to a match. So, the complement must be taken
+//! over the states reached by all of them, and not by each symbol separately.
+//! Therefore, the configuration of a sub-pattern is the set of its live states,
+//! which is deterministic with respect to the frames consumed.
+//!
+//! The reverse of a pattern is composed alike from the reverse of its
+//! sub-patterns (e.g., the reverse of a complement is the complement of the
+//! reverse). So, the reverse search (which is unanchored) is built from the
+//! same composition, rather than from an RE that over-approximates it.
+//!
+//! [`Options::build`]: super::Options::build
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::sync::RwLock;
+
+use regex_automata::util::primitives::StateID;
+use regex_automata::util::start::Config;
+use regex_automata::Anchored;
+
+use crate::compiler::ir::ast::Atom;
+use crate::compiler::ir::{Node, Operator, RangeKind, RegexOperatorKind};
+use crate::matcher::symbols::{SymbolSet, BLANK};
+use crate::matcher::MatcherError;
+use crate::symbolizer::ast::{SymbolicAbstractSyntaxTree as AST, SymbolicFormula};
+
+use super::AutomatonType;
+
+/// The [`StateID`] of the dead state.
+const DEAD: StateID = StateID::ZERO;
+
+/// The [`StateID`] of the state reached on the EOI from a state that matches.
+const EOI: StateID = StateID::new_unchecked(1);
+
+/// The [`StateID`] of the start state.
+const START: StateID = StateID::new_unchecked(2);
+
+/// The limit on the configurations explored to check whether a leaf matches
+/// every further sequence of frames (see [`Term::is_universal`]).
+const EXPLORE: usize = 1 << 10;
+
+/// A sub-pattern of a [`BooleanAutomaton`].
+#[derive(Debug)]
+enum Term {
+    /// The empty sub-pattern (e.g., the last copies of a bounded repetition).
+    Empty,
+
+    /// Any sequence of frames (i.e., before the start of an unanchored search).
+    Any,
+
+    /// A sub-pattern that is an RE, along with the start state of its
+    /// automaton and the frames that stand for every frame (see
+    /// [`Term::is_universal`]).
+    Leaf(Box<AutomatonType>, StateID, Vec<SymbolSet>),
+
+    Intersection(Box<Term>, Box<Term>),
+    Complement(Box<Term>),
+    Concatenation(Box<Term>, Box<Term>),
+    Alternation(Box<Term>, Box<Term>),
+    KleeneStar(Box<Term>),
+}
+
+/// The configuration of a [`Term`] after the frames consumed so far.
+///
+/// The configuration of a complement is that of its sub-pattern.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Configuration {
+    /// Whether no frame was consumed, yet.
+    Empty(bool),
+
+    /// Any sequence of frames, which is always matched.
+    Any,
+
+    /// The live states of the automaton of a leaf (in ascending order).
+    Leaf(Vec<StateID>),
+
+    /// The configurations of both sub-patterns (of an intersection or an
+    /// alternation).
+    Pair(Box<Configuration>, Box<Configuration>),
+
+    /// The configuration of the left sub-pattern, and of each match of the
+    /// right sub-pattern in progress (i.e., started after a match of the left).
+    Concatenation(Box<Configuration>, Vec<Configuration>),
+
+    /// The configuration of each repetition in progress, and whether a
+    /// repetition was just completed.
+    KleeneStar(Vec<Configuration>, bool),
+}
+
+impl Term {
+    /// Build the [`Term`] of a node of the pattern.
+    ///
+    /// Each largest sub-pattern without an intersection or complement is built
+    /// into an [`AutomatonType`] by the `leaf` function. If `reverse` is set,
+    /// then each leaf is expected to match the reverse of its sub-pattern, and
+    /// the sub-patterns of a concatenation are swapped.
+    fn build(
+        node: &Node<SymbolicFormula>,
+        leaf: &impl Fn(&AST) -> Result<AutomatonType, Box<dyn Error>>,
+        reverse: bool,
+        frames: &[SymbolSet],
+    ) -> Result<Term, Box<dyn Error>> {
+        if !AST::booleanit(node) {
+            let automaton = leaf(&AST::new(Some(node.clone())))?;
+            let start = automaton.start_state(&Config::new().anchored(Anchored::Yes))?;
+
+            return Ok(Term::Leaf(Box::new(automaton), start, frames.to_vec()));
+        }
+
+        let build = |node| Term::build(node, leaf, reverse, frames).map(Box::new);
+
+        match node {
+            Node::UnaryExpr {
+                op: Operator::RegexOperator(kind),
+                child,
+            } => match kind {
+                RegexOperatorKind::Complement => Ok(Term::Complement(build(child)?)),
+                RegexOperatorKind::KleeneStar => Ok(Term::KleeneStar(build(child)?)),
+                RegexOperatorKind::Range(kind) => {
+                    let (min, max) = match kind {
+                        RangeKind::Exactly(size) => (*size, Some(*size)),
+                        RangeKind::AtLeast(min) => (*min, None),
+                        RangeKind::Between(min, max) => (*min, Some(*max)),
+                    };
+
+                    Term::range(child, min, max, leaf, reverse, frames)
+                }
+                _ => Err(Box::new(MatcherError::from(
+                    "approximate matching is not supported with `&&` or `~`",
+                ))),
+            },
+            Node::BinaryExpr {
+                op: Operator::RegexOperator(kind),
+                left,
+                right,
+            } => match kind {
+                RegexOperatorKind::Intersection => {
+                    Ok(Term::Intersection(build(left)?, build(right)?))
+                }
+                RegexOperatorKind::Concatenation => match reverse {
+                    false => Ok(Term::Concatenation(build(left)?, build(right)?)),
+                    true => Ok(Term::Concatenation(build(right)?, build(left)?)),
+                },
+                RegexOperatorKind::Alternation => {
+                    Ok(Term::Alternation(build(left)?, build(right)?))
+                }
+                _ => Err(Box::new(MatcherError::from("unknown temporal operator"))),
+            },
+            _ => Err(Box::new(MatcherError::from("unknown temporal operator"))),
+        }
+    }
+
+    /// Build the [`Term`] of a bounded or unbounded repetition.
+    ///
+    /// The repeated sub-pattern is expanded into a copy per repetition (e.g.,
+    /// `x{2,4}` into `x x (x (x)?)?`), as with the dense DFA. As the copies
+    /// are alike, the expansion is the same in reverse.
+    fn range(
+        node: &Node<SymbolicFormula>,
+        min: usize,
+        max: Option<usize>,
+        leaf: &impl Fn(&AST) -> Result<AutomatonType, Box<dyn Error>>,
+        reverse: bool,
+        frames: &[SymbolSet],
+    ) -> Result<Term, Box<dyn Error>> {
+        let mut term = match max {
+            Some(max) => {
+                let mut term = Term::Empty;
+
+                for _ in min..max {
+                    let copy = Term::build(node, leaf, reverse, frames)?;
+                    term = Term::Alternation(
+                        Box::new(Term::Concatenation(Box::new(copy), Box::new(term))),
+                        Box::new(Term::Empty),
+                    );
+                }
+
+                term
+            }
+            None => Term::KleeneStar(Box::new(Term::build(node, leaf, reverse, frames)?)),
+        };
+
+        for _ in 0..min {
+            let copy = Term::build(node, leaf, reverse, frames)?;
+            term = Term::Concatenation(Box::new(copy), Box::new(term));
+        }
+
+        Ok(term)
+    }
+
+    /// The [`Configuration`] before any frame is consumed.
+    fn start(&self) -> Configuration {
+        match self {
+            Term::Empty => Configuration::Empty(true),
+            Term::Any => Configuration::Any,
+            Term::Leaf(_, start, _) => Configuration::Leaf(vec![*start]),
+            Term::Intersection(left, right) | Term::Alternation(left, right) => {
+                Configuration::Pair(Box::new(left.start()), Box::new(right.start()))
+            }
+            Term::Complement(child) => child.start(),
+            Term::Concatenation(left, right) => {
+                let start = left.start();
+
+                let rights = match left.accepts(&start) {
+                    true => right.normalize(vec![right.start()]),
+                    false => Vec::new(),
+                };
+
+                Configuration::Concatenation(Box::new(start), rights)
+            }
+            Term::KleeneStar(child) => Configuration::KleeneStar(vec![child.start()], true),
+        }
+    }
+
+    /// Take the transition on the symbols satisfied by the next frame.
+    fn step(&self, config: &Configuration, symbols: &SymbolSet) -> Configuration {
+        match (self, config) {
+            (Term::Empty, _) => Configuration::Empty(false),
+            (Term::Any, _) => Configuration::Any,
+            (Term::Leaf(automaton, ..), Configuration::Leaf(sids)) => {
+                let blank = symbols.is_empty().then_some(BLANK);
+
+                let mut nexts = sids
+                    .iter()
+                    .flat_map(|sid| {
+                        symbols
+                            .bytes()
+                            .chain(blank)
+                            .flat_map(|byte| automaton.next_states(*sid, byte))
+                    })
+                    .filter(|sid| !automaton.is_dead_state(*sid))
+                    .collect::<Vec<_>>();
+
+                automaton.merge(&mut nexts);
+
+                Configuration::Leaf(nexts)
+            }
+            (
+                Term::Intersection(left, right) | Term::Alternation(left, right),
+                Configuration::Pair(l, r),
+            ) => Configuration::Pair(
+                Box::new(left.step(l, symbols)),
+                Box::new(right.step(r, symbols)),
+            ),
+            (Term::Complement(child), config) => child.step(config, symbols),
+            (Term::Concatenation(left, right), Configuration::Concatenation(l, rs)) => {
+                let l = left.step(l, symbols);
+
+                let mut rs = rs
+                    .iter()
+                    .map(|r| right.step(r, symbols))
+                    .collect::<Vec<_>>();
+
+                // A match of the right sub-pattern may start after every match
+                // of the left one.
+                if left.accepts(&l) {
+                    rs.push(right.start());
+                }
+
+                Configuration::Concatenation(Box::new(l), right.normalize(rs))
+            }
+            (Term::KleeneStar(child), Configuration::KleeneStar(cs, _)) => {
+                let mut cs = cs
+                    .iter()
+                    .map(|c| child.step(c, symbols))
+                    .collect::<Vec<_>>();
+
+                let completed = cs.iter().any(|c| child.accepts(c));
+
+                if completed {
+                    cs.push(child.start());
+                }
+
+                Configuration::KleeneStar(child.normalize(cs), completed)
+            }
+            _ => unreachable!("boolean: configuration of another sub-pattern"),
+        }
+    }
+
+    /// Check whether the frames consumed so far match.
+    fn accepts(&self, config: &Configuration) -> bool {
+        match (self, config) {
+            (Term::Empty, Configuration::Empty(empty)) => *empty,
+            (Term::Any, _) => true,
+            (Term::Leaf(automaton, ..), Configuration::Leaf(sids)) => sids
+                .iter()
+                .any(|sid| automaton.is_match_state(automaton.next_eoi_state(*sid))),
+            (Term::Intersection(left, right), Configuration::Pair(l, r)) => {
+                left.accepts(l) && right.accepts(r)
+            }
+            (Term::Alternation(left, right), Configuration::Pair(l, r)) => {
+                left.accepts(l) || right.accepts(r)
+            }
+            (Term::Complement(child), config) => !child.accepts(config),
+            (Term::Concatenation(_, right), Configuration::Concatenation(_, rs)) => {
+                rs.iter().any(|r| right.accepts(r))
+            }
+            (Term::KleeneStar(..), Configuration::KleeneStar(_, completed)) => *completed,
+            _ => unreachable!("boolean: configuration of another sub-pattern"),
+        }
+    }
+
+    /// Check whether no further frames may ever match.
+    ///
+    /// A complement is dead when its sub-pattern matches every further
+    /// sequence of frames (see [`Term::is_universal`]).
+    fn is_dead(&self, config: &Configuration) -> bool {
+        match (self, config) {
+            (Term::Empty, Configuration::Empty(empty)) => !*empty,
+            (Term::Any, _) => false,
+            (Term::Leaf(..), Configuration::Leaf(sids)) => sids.is_empty(),
+            (Term::Intersection(left, right), Configuration::Pair(l, r)) => {
+                left.is_dead(l) || right.is_dead(r)
+            }
+            (Term::Alternation(left, right), Configuration::Pair(l, r)) => {
+                left.is_dead(l) && right.is_dead(r)
+            }
+            (Term::Complement(child), config) => child.is_universal(config),
+            (Term::Concatenation(left, _), Configuration::Concatenation(l, rs)) => {
+                left.is_dead(l) && rs.is_empty()
+            }
+            (Term::KleeneStar(..), Configuration::KleeneStar(cs, _)) => cs.is_empty(),
+            _ => unreachable!("boolean: configuration of another sub-pattern"),
+        }
+    }
+
+    /// Check whether every further sequence of frames (even none) matches.
+    ///
+    /// For a leaf, each configuration reachable is explored, where it steps
+    /// over a frame per symbol (along with the symbols of `.`, which every
+    /// frame satisfies) rather than over every set of symbols. A frame with
+    /// more symbols reaches more live states, which match whenever any of
+    /// them does; so, this is exact, unless more than [`EXPLORE`] are reached.
+    /// Otherwise, the sub-patterns are checked alone (e.g., an alternation is
+    /// universal if either of its sub-patterns is). So, a sub-pattern may be
+    /// universal even if this does not find it, but never the other way.
+    fn is_universal(&self, config: &Configuration) -> bool {
+        match (self, config) {
+            (Term::Empty, _) => false,
+            (Term::Any, _) => true,
+            (Term::Leaf(.., frames), Configuration::Leaf(..)) => {
+                let mut seen = HashSet::from([config.clone()]);
+                let mut stack = vec![config.clone()];
+
+                while let Some(config) = stack.pop() {
+                    if !self.accepts(&config) || seen.len() > EXPLORE {
+                        return false;
+                    }
+
+                    for frame in frames.iter() {
+                        let next = self.step(&config, frame);
+
+                        if !seen.contains(&next) {
+                            seen.insert(next.clone());
+                            stack.push(next);
+                        }
+                    }
+                }
+
+                true
+            }
+            (Term::Intersection(left, right), Configuration::Pair(l, r)) => {
+                left.is_universal(l) && right.is_universal(r)
+            }
+            (Term::Alternation(left, right), Configuration::Pair(l, r)) => {
+                left.is_universal(l) || right.is_universal(r)
+            }
+            (Term::Complement(child), config) => child.is_dead(config),
+            (Term::Concatenation(_, right), Configuration::Concatenation(_, rs)) => {
+                rs.iter().any(|r| right.is_universal(r))
+            }
+            (Term::KleeneStar(child), Configuration::KleeneStar(cs, completed)) => {
+                *completed && cs.iter().any(|c| child.is_universal(c))
+            }
+            _ => unreachable!("boolean: configuration of another sub-pattern"),
+        }
+    }
+
+    /// Sort a set of configurations of this [`Term`], removing any duplicate
+    /// or dead ones.
+    fn normalize(&self, mut configs: Vec<Configuration>) -> Vec<Configuration> {
+        configs.retain(|config| !self.is_dead(config));
+        configs.sort_unstable();
+        configs.dedup();
+
+        configs
+    }
+}
+
+/// A state of the [`BooleanAutomaton`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct BooleanState {
+    config: Configuration,
+
+    /// Whether the frames consumed before the last one match.
+    matched: bool,
+}
+
+/// The states and transitions built so far.
+#[derive(Debug, Default)]
+struct Cache {
+    states: Vec<BooleanState>,
+    ids: HashMap<BooleanState, StateID>,
+    transitions: HashMap<(StateID, SymbolSet), StateID>,
+}
+
+/// An automaton over the intersections and complements of sub-patterns.
+///
+/// As with the dense DFA, matches are delayed by a single transition. As with
+/// the [`LazyAutomaton`](super::lazy::LazyAutomaton), states are built as they
+/// are reached and never evicted, and are shared behind a lock. Whether a
+/// search is anchored is set when the automaton is built.
+#[derive(Debug)]
+pub struct BooleanAutomaton {
+    term: Term,
+    cache: RwLock<Cache>,
+}
+
+impl BooleanAutomaton {
+    /// Build a new [`BooleanAutomaton`] from a [`AST`].
+    ///
+    /// Each largest sub-pattern without an intersection or complement is built
+    /// into an [`AutomatonType`] by the `leaf` function, which is expected to
+    /// support anchored searches. If `reverse` is set, then the automaton
+    /// matches the reverse of the pattern (i.e., from the last frame to the
+    /// first), where each leaf is expected to match in reverse, as well. An
+    /// unanchored automaton matches any sequence of frames before the pattern.
+    pub fn build(
+        ast: &AST,
+        reverse: bool,
+        anchored: Anchored,
+        leaf: impl Fn(&AST) -> Result<AutomatonType, Box<dyn Error>>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let Some(root) = &ast.root else {
+            return Err(Box::new(MatcherError::from("empty pattern")));
+        };
+
+        // Every frame satisfies the symbols of `.`, along with any others.
+        let formulas = ast.fmap();
+        let mut any = SymbolSet::new();
+
+        for formula in formulas.iter() {
+            if let Node::Operand(Atom::Any) = formula.formula {
+                any.insert(formula.symbol);
+            }
+        }
+
+        let mut frames = vec![any];
+
+        for formula in formulas.iter() {
+            let mut frame = any;
+            frame.insert(formula.symbol);
+
+            if !frames.contains(&frame) {
+                frames.push(frame);
+            }
+        }
+
+        let term = Term::build(root, &leaf, reverse, &frames)?;
+
+        let term = match anchored {
+            Anchored::No => Term::Concatenation(Box::new(Term::Any), Box::new(term)),
+            _ => term,
+        };
+
+        // The dead and EOI states are never interned, as their configuration
+        // is irrelevant.
+        let placeholder = BooleanState {
+            config: Configuration::Empty(false),
+            matched: false,
+        };
+
+        let automaton = BooleanAutomaton {
+            cache: RwLock::new(Cache {
+                states: vec![placeholder.clone(), placeholder],
+                ..Cache::default()
+            }),
+            term,
+        };
+
+        automaton.intern(BooleanState {
+            config: automaton.term.start(),
+            matched: false,
+        });
+
+        Ok(automaton)
+    }
+
+    /// Retrieve the start state.
+    pub fn start_state(&self) -> StateID {
+        START
+    }
+
+    /// Take the transition on the symbols satisfied by a frame from a state.
+    pub fn next_state(&self, sid: StateID, symbols: &SymbolSet) -> StateID {
+        if sid == DEAD || sid == EOI {
+            return DEAD;
+        }
+
+        if let Some(next) = self.cache.read().unwrap().transitions.get(&(sid, *symbols)) {
+            return *next;
+        }
+
+        let state = self.state(sid);
+
+        let config = self.term.step(&state.config, symbols);
+        let matched = self.term.accepts(&state.config);
+
+        let next = match !matched && self.term.is_dead(&config) {
+            true => DEAD,
+            false => self.intern(BooleanState { config, matched }),
+        };
+
+        self.cache
+            .write()
+            .unwrap()
+            .transitions
+            .insert((sid, *symbols), next);
+
+        next
+    }
+
+    /// Take the transition on a byte from a state.
+    ///
+    /// This is the transition on a frame that satisfies only the symbol of the
+    /// byte (or none, for [`BLANK`]).
+    pub fn next_byte_state(&self, sid: StateID, byte: u8) -> StateID {
+        let mut symbols = SymbolSet::new();
+
+        if byte != BLANK {
+            symbols.insert(byte as char);
+        }
+
+        self.next_state(sid, &symbols)
+    }
+
+    /// Take the transition on the EOI from a state.
+    pub fn next_eoi_state(&self, sid: StateID) -> StateID {
+        if sid == DEAD || sid == EOI || !self.term.accepts(&self.state(sid).config) {
+            return DEAD;
+        }
+
+        EOI
+    }
+
+    /// Check whether a state is a match state.
+    pub fn is_match_state(&self, sid: StateID) -> bool {
+        sid == EOI || (sid != DEAD && self.state(sid).matched)
+    }
+
+    /// Check whether a state is the dead state.
+    pub fn is_dead_state(&self, sid: StateID) -> bool {
+        sid == DEAD
+    }
+
+    /// Check whether a state is a start state.
+    pub fn is_start_state(&self, sid: StateID) -> bool {
+        sid == START
+    }
+
+    /// Retrieve a state by its [`StateID`].
+    fn state(&self, sid: StateID) -> BooleanState {
+        self.cache.read().unwrap().states[sid.as_usize()].clone()
+    }
+
+    /// Find the [`StateID`] of a state, adding it if it is new.
+    fn intern(&self, state: BooleanState) -> StateID {
+        let mut cache = self.cache.write().unwrap();
+
+        if let Some(sid) = cache.ids.get(&state) {
+            return *sid;
+        }
+
+        // The number of states is bounded by the memory available long before
+        // the limit of a [`StateID`] is reached.
+        let sid = StateID::must(cache.states.len());
+
+        cache.states.push(state.clone());
+        cache.ids.insert(state, sid);
+
+        sid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+    use crate::matcher::automata::dfa::{forward, reverse, DeterministicFiniteAutomaton};
+    use crate::matcher::automata::Options;
+    use crate::matcher::symbols::SymbolSet;
+
+    fn frame(symbols: &[char]) -> SymbolSet {
+        let mut set = SymbolSet::new();
+
+        for symbol in symbols {
+            set.insert(*symbol);
+        }
+
+        set
+    }
+
+    #[test]
+    fn boolean() {
+        let ast = Compiler::new().compile("[[:a:]] && ~([[:b:]])").unwrap();
+        let dfa = forward::build_all(&ast, &Options::new()).unwrap();
+        let sid = *dfa.initial().unwrap().id();
+
+        assert!(dfa
+            .successors(sid, &frame(&['a']))
+            .any(|next| dfa.is_final(next)));
+
+        // The complement is taken over the entire frame. So, a frame that
+        // satisfies both formulas does not match, even though its `a` alone
+        // would.
+        assert!(!dfa
+            .successors(sid, &frame(&['a', 'b']))
+            .any(|next| dfa.is_final(next)));
+    }
+
+    #[test]
+    fn dead() {
+        let ast = Compiler::new().compile("~([[:a:]] .*)").unwrap();
+        let dfa = forward::build_all(&ast, &Options::new()).unwrap();
+
+        let symbols = ast
+            .fmap()
+            .iter()
+            .map(|formula| formula.symbol)
+            .collect::<Vec<_>>();
+
+        // Every frame satisfies `.` (i.e., the second symbol).
+        let (a, any) = (frame(&symbols), frame(&symbols[1..]));
+
+        // Once the sub-pattern matches every further sequence of frames, its
+        // complement never matches again.
+        let mut states = vec![*dfa.initial().unwrap().id()];
+
+        for symbols in [a, any] {
+            states = dfa.step(&states, &symbols);
+        }
+
+        assert!(states.is_empty());
+
+        // Otherwise, the complement may still match.
+        let mut states = vec![*dfa.initial().unwrap().id()];
+
+        for symbols in [any, any] {
+            states = dfa.step(&states, &symbols);
+        }
+
+        assert!(states.iter().any(|sid| dfa.is_final(*sid)));
+    }
+
+    #[test]
+    fn reverse() {
+        let ast = Compiler::new().compile("[[:a:]] && ~([[:b:]])").unwrap();
+        let rdfa = reverse::build_unanchored(&ast, &Options::new()).unwrap();
+
+        let haystack = [
+            frame(&['a']),
+            frame(&['a', 'b']),
+            frame(&['b']),
+            frame(&['a']),
+        ];
+
+        let mut starts = rdfa
+            .run(&haystack)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.offset())
+            .collect::<Vec<_>>();
+
+        starts.sort_unstable();
+        starts.dedup();
+
+        // The frame with both symbols is excluded by the complement, rather
+        // than included by the left sub-pattern alone.
+        assert_eq!(vec![0, 3], starts);
+    }
+}
@@ -0,0 +1,751 @@
+//! A counting automaton.
+//!
+//! Both the dense and lazy DFA(s) are built from a pattern where a repetition
+//! `{n,m}` is expanded into `m` copies of the repeated sub-pattern. For queries
+//! over durations (e.g., thousands of frames), this grows with the bound.
+//!
+//! Instead, this automaton is a position (i.e., Glushkov) automaton where each
+//! `RangeKind::Between`/`RangeKind::AtLeast` over a single-frame sub-pattern
+//! keeps an explicit counter of its repetitions. A state is then a position of
+//! the pattern along with the values of its counter (if any). As the
+//! [`StateID`] of a state is computed from its position and counter, no state
+//! is ever stored; so, the size of the automaton is independent of the bounds
+//! of a repetition.
+//!
+//! The values of a counter are kept as a counting set (i.e., a run of every
+//! count from `lo` to `hi`), as each activation of a repetition is repeated
+//! alike. For example, a match of `[[:car:]]{1,1000}` may start at each of the
+//! last 1000 frames with a car, each of which is at a different count; these
+//! are merged (see [`CountingAutomaton::merge`]) into a single state of the
+//! counts from 1 to 1000. So, the states live in a search over every start at
+//! once do not grow with the bound. However, a search that keeps each start
+//! apart (i.e., [`Threads`](crate::matcher::threads::Threads)) still keeps a
+//! state per start. If the runs of a counter do not fit within a [`StateID`],
+//! then each state is kept at a single count, instead.
+//!
+//! Unlike a DFA, a state may transition to several states on the same byte
+//! (e.g., a counter that may either repeat or exit). Other repetitions are
+//! expanded as before.
+
+use std::error::Error;
+
+use regex_automata::util::primitives::StateID;
+use regex_automata::Anchored;
+
+use crate::compiler::ir::{Node, Operator, RangeKind, RegexOperatorKind};
+use crate::matcher::symbols;
+use crate::matcher::MatcherError;
+use crate::symbolizer::ast::{Length, SymbolicAbstractSyntaxTree as AST, SymbolicFormula};
+
+/// The configurations that are not a position.
+const DEAD: usize = 0;
+const ANCHORED: usize = 1;
+const UNANCHORED: usize = 2;
+
+/// The first configuration of a position.
+const POSITIONS: usize = 3;
+
+/// A counted repetition.
+#[derive(Clone, Copy, Debug)]
+struct Counter {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Counter {
+    /// The largest value the counter is kept at.
+    ///
+    /// Without an upper bound, every count from the lower bound onwards is
+    /// equivalent; so, the counter stops at the lower bound.
+    fn cap(&self) -> usize {
+        self.max.unwrap_or(self.min).max(1)
+    }
+}
+
+/// The kinds of edges between positions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Edge {
+    /// Move to the next position, which completes the counter (if any) of the
+    /// current position and starts the counter (if any) of the next one.
+    Step,
+
+    /// Repeat the counted sub-pattern of the current position once more.
+    Repeat,
+}
+
+/// An occurrence of a symbol in the (expanded) pattern.
+#[derive(Clone, Debug)]
+struct Position {
+    byte: u8,
+    counter: Option<usize>,
+    follows: Vec<(usize, Edge)>,
+
+    /// Whether a match may end at this position.
+    last: bool,
+}
+
+/// The positions that begin and end a sub-pattern.
+#[derive(Debug, Default)]
+struct Fragment {
+    first: Vec<usize>,
+    last: Vec<usize>,
+    nullable: bool,
+}
+
+/// A configuration of the [`CountingAutomaton`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Configuration {
+    Dead,
+    Start(Anchored),
+
+    /// A position with the run of values of its counter from the first value
+    /// to the second (or zero, if none).
+    Position(usize, usize, usize),
+}
+
+/// A position automaton with counters.
+///
+/// As with the dense DFA, matches are delayed by a single transition. So, a
+/// [`StateID`] is the configuration reached along with whether the previous
+/// configuration matched. In particular, the dead state that is reached from a
+/// match is a match state, too.
+#[derive(Debug)]
+pub struct CountingAutomaton {
+    positions: Vec<Position>,
+    counters: Vec<Counter>,
+    first: Vec<usize>,
+    nullable: bool,
+
+    /// The first configuration of each position (after [`POSITIONS`]).
+    offsets: Vec<usize>,
+
+    /// Whether a counter is kept as a run of values (rather than one value).
+    runs: bool,
+
+    /// The symbols matched approximately.
+    approximate: Vec<char>,
+}
+
+impl CountingAutomaton {
+    /// Build a new [`CountingAutomaton`] from a [`AST`].
+    ///
+    /// If `reverse` is set, then the automaton matches the reverse of the
+    /// pattern (i.e., from the last frame to the first).
+    pub fn build(ast: &AST, reverse: bool) -> Result<Self, Box<dyn Error>> {
+        let mut automaton = CountingAutomaton {
+            positions: Vec::new(),
+            counters: Vec::new(),
+            first: Vec::new(),
+            nullable: true,
+            offsets: Vec::new(),
+            runs: true,
+            approximate: ast
+                .approximations()
+                .into_iter()
+                .flat_map(|approximation| approximation.symbols)
+                .collect(),
+        };
+
+        if let Some(root) = &ast.root {
+            let fragment = automaton.compile(root, None, reverse);
+
+            for p in fragment.last.iter() {
+                automaton.positions[*p].last = true;
+            }
+
+            automaton.first = fragment.first;
+            automaton.nullable = fragment.nullable;
+        }
+
+        // A run of values is kept for a counter only if they fit.
+        if automaton.size() > StateID::LIMIT {
+            automaton.runs = false;
+
+            if automaton.size() > StateID::LIMIT {
+                return Err(Box::new(MatcherError::from(
+                    "pattern exceeds the limit of a counting automaton",
+                )));
+            }
+        }
+
+        automaton.offsets = automaton
+            .positions
+            .iter()
+            .scan(0, |offset, position| {
+                let current = *offset;
+                *offset += automaton.width(position);
+                Some(current)
+            })
+            .collect();
+
+        Ok(automaton)
+    }
+
+    /// The size of the automaton (i.e., the number of its [`StateID`]).
+    ///
+    /// Each configuration is doubled by whether the previous one matched.
+    fn size(&self) -> usize {
+        self.positions
+            .iter()
+            .fold(POSITIONS, |size, position| {
+                size.saturating_add(self.width(position))
+            })
+            .saturating_mul(2)
+    }
+
+    /// The number of configurations of a position.
+    ///
+    /// With runs, there is a configuration for each pair of values `lo <= hi`.
+    fn width(&self, position: &Position) -> usize {
+        match position.counter.map(|c| self.counters[c].cap()) {
+            Some(cap) if self.runs => cap.saturating_mul(cap.saturating_add(1)) / 2,
+            Some(cap) => cap,
+            None => 1,
+        }
+    }
+
+    /// Check whether any repetition is counted.
+    pub fn is_counting(&self) -> bool {
+        !self.counters.is_empty()
+    }
+
+    /// Retrieve the start state.
+    pub fn start_state(&self, anchored: Anchored) -> StateID {
+        match anchored {
+            Anchored::No => self.encode(Configuration::Start(Anchored::No), false),
+            _ => self.encode(Configuration::Start(Anchored::Yes), false),
+        }
+    }
+
+    /// Take the transitions on a byte from a state.
+    ///
+    /// If no transition is possible, then the dead state is returned.
+    pub fn next_states(&self, sid: StateID, byte: u8) -> Vec<StateID> {
+        let (config, _) = self.decode(sid);
+        let matched = self.is_final(config);
+
+        let mut nexts = match config {
+            Configuration::Dead => Vec::new(),
+            Configuration::Start(anchored) => {
+                let mut nexts = self
+                    .first
+                    .iter()
+                    .filter(|q| self.positions[**q].byte == byte)
+                    .map(|q| self.enter(*q))
+                    .collect::<Vec<_>>();
+
+                // An unanchored search may start from any position.
+                if anchored == Anchored::No {
+                    nexts.push(config);
+                }
+
+                nexts
+            }
+            Configuration::Position(p, lo, hi) => self.positions[p]
+                .follows
+                .iter()
+                .filter(|(q, _)| self.positions[*q].byte == byte)
+                .filter_map(|(q, edge)| self.follow(p, (lo, hi), *q, *edge))
+                .collect(),
+        };
+
+        if nexts.is_empty() {
+            nexts.push(Configuration::Dead);
+        }
+
+        nexts
+            .into_iter()
+            .map(|next| self.encode(next, matched))
+            .collect()
+    }
+
+    /// Take the transition on the EOI from a state.
+    pub fn next_eoi_state(&self, sid: StateID) -> StateID {
+        let (config, _) = self.decode(sid);
+        self.encode(Configuration::Dead, self.is_final(config))
+    }
+
+    /// Check whether a state is a match state.
+    pub fn is_match_state(&self, sid: StateID) -> bool {
+        self.decode(sid).1
+    }
+
+    /// Check whether a state is the dead state.
+    pub fn is_dead_state(&self, sid: StateID) -> bool {
+        self.decode(sid) == (Configuration::Dead, false)
+    }
+
+    /// Check whether a state is a start state.
+    ///
+    /// An unanchored start state is reached again after every byte. So, it may
+    /// also be a match state, in which case it is not considered a start state.
+    pub fn is_start_state(&self, sid: StateID) -> bool {
+        matches!(self.decode(sid), (Configuration::Start(..), false))
+    }
+
+    /// Merge the runs of the same position within a set of states.
+    ///
+    /// Each state reached by a search over several starts at once is at a run
+    /// of values of its counter. As each value is repeated alike, the runs of
+    /// the same position that overlap (or are adjacent) are merged into one.
+    /// The result is sorted without duplicates.
+    pub fn merge(&self, states: &mut Vec<StateID>) {
+        if self.runs && self.is_counting() {
+            let mut configs = states
+                .iter()
+                .map(|sid| self.decode(*sid))
+                .collect::<Vec<_>>();
+
+            // Order the runs of each position (and match) by their first value.
+            configs.sort_unstable_by_key(|(config, matched)| match config {
+                Configuration::Position(p, lo, hi) => (1, *p, *matched, *lo, *hi),
+                _ => (0, 0, *matched, 0, 0),
+            });
+
+            let mut merged: Vec<(Configuration, bool)> = Vec::with_capacity(configs.len());
+
+            for (config, matched) in configs {
+                if let (
+                    Some((Configuration::Position(q, _, end), m)),
+                    Configuration::Position(p, lo, hi),
+                ) = (merged.last_mut(), config)
+                {
+                    if *q == p && *m == matched && lo <= end.saturating_add(1) {
+                        *end = (*end).max(hi);
+                        continue;
+                    }
+                }
+
+                merged.push((config, matched));
+            }
+
+            *states = merged
+                .into_iter()
+                .map(|(config, matched)| self.encode(config, matched))
+                .collect();
+        }
+
+        states.sort_unstable();
+        states.dedup();
+    }
+
+    /// Compile a node of the pattern into its positions.
+    ///
+    /// The `counter` is that of the counted repetition the node is within (if
+    /// any). A node may be compiled several times (i.e., when expanding an
+    /// uncounted repetition), where each copy has its own positions.
+    fn compile(
+        &mut self,
+        node: &Node<SymbolicFormula>,
+        counter: Option<usize>,
+        reverse: bool,
+    ) -> Fragment {
+        match node {
+            Node::Operand(formula) => {
+                let mut bytes = vec![formula.symbol as u8];
+
+                // A symbol matched approximately may be substituted by its
+                // mismatch (i.e., as an alternation of both).
+                if self.approximate.contains(&formula.symbol) {
+                    bytes.push(symbols::mismatch(formula.symbol));
+                }
+
+                let first = (self.positions.len()..)
+                    .take(bytes.len())
+                    .collect::<Vec<_>>();
+
+                for byte in bytes {
+                    self.positions.push(Position {
+                        byte,
+                        counter,
+                        follows: Vec::new(),
+                        last: false,
+                    });
+                }
+
+                Fragment {
+                    last: first.clone(),
+                    first,
+                    nullable: false,
+                }
+            }
+            Node::UnaryExpr { op, child } => match op {
+                Operator::RegexOperator(RegexOperatorKind::KleeneStar) => {
+                    let fragment = self.compile(child, counter, reverse);
+                    self.star(fragment)
+                }
+                Operator::RegexOperator(RegexOperatorKind::Range(kind)) => {
+                    let (min, max) = match kind {
+                        RangeKind::Exactly(size) => (*size, Some(*size)),
+                        RangeKind::AtLeast(min) => (*min, None),
+                        RangeKind::Between(min, max) => (*min, Some(*max)),
+                    };
+
+                    self.range(child, min, max, counter, reverse)
+                }
+                Operator::RegexOperator(RegexOperatorKind::Approximate(..)) => {
+                    self.compile(child, counter, reverse)
+                }
+                _ => Fragment::empty(),
+            },
+            Node::BinaryExpr { op, left, right } => match op {
+                Operator::RegexOperator(RegexOperatorKind::Concatenation) => {
+                    let (left, right) = match reverse {
+                        false => (left, right),
+                        true => (right, left),
+                    };
+
+                    let left = self.compile(left, counter, reverse);
+                    let right = self.compile(right, counter, reverse);
+
+                    self.concat(left, right)
+                }
+                Operator::RegexOperator(RegexOperatorKind::Alternation) => {
+                    let left = self.compile(left, counter, reverse);
+                    let right = self.compile(right, counter, reverse);
+
+                    Fragment {
+                        first: [left.first, right.first].concat(),
+                        last: [left.last, right.last].concat(),
+                        nullable: left.nullable || right.nullable,
+                    }
+                }
+                _ => Fragment::empty(),
+            },
+        }
+    }
+
+    /// Compile a repetition of a node between `min` and `max` times.
+    ///
+    /// A single-frame node (outside of any other counted repetition) is
+    /// counted. Otherwise, the repetition is expanded into copies of the node.
+    fn range(
+        &mut self,
+        child: &Node<SymbolicFormula>,
+        min: usize,
+        max: Option<usize>,
+        counter: Option<usize>,
+        reverse: bool,
+    ) -> Fragment {
+        if max == Some(0) {
+            return Fragment::empty();
+        }
+
+        if counter.is_none() && AST::lengthit(child) == Length::new(1, Some(1)) {
+            let id = self.counters.len();
+            self.counters.push(Counter { min, max });
+
+            let fragment = self.compile(child, Some(id), reverse);
+
+            // As the node is a single frame, every position is both first and
+            // last. So, each position may be followed by any first position.
+            for p in fragment.last.iter() {
+                for q in fragment.first.iter() {
+                    self.positions[*p].follows.push((*q, Edge::Repeat));
+                }
+            }
+
+            return Fragment {
+                nullable: min == 0,
+                ..fragment
+            };
+        }
+
+        let mut fragment = Fragment::empty();
+
+        for _ in 0..min {
+            let next = self.compile(child, counter, reverse);
+            fragment = self.concat(fragment, next);
+        }
+
+        match max {
+            Some(max) => {
+                for _ in min..max {
+                    let next = self.compile(child, counter, reverse);
+                    let next = Fragment {
+                        nullable: true,
+                        ..next
+                    };
+
+                    fragment = self.concat(fragment, next);
+                }
+            }
+            None => {
+                let next = self.compile(child, counter, reverse);
+                let next = self.star(next);
+
+                fragment = self.concat(fragment, next);
+            }
+        }
+
+        fragment
+    }
+
+    /// Concatenate two fragments.
+    fn concat(&mut self, left: Fragment, right: Fragment) -> Fragment {
+        for p in left.last.iter() {
+            for q in right.first.iter() {
+                self.positions[*p].follows.push((*q, Edge::Step));
+            }
+        }
+
+        let first = match left.nullable {
+            true => [left.first, right.first.clone()].concat(),
+            false => left.first,
+        };
+
+        let last = match right.nullable {
+            true => [right.last, left.last].concat(),
+            false => right.last,
+        };
+
+        Fragment {
+            first,
+            last,
+            nullable: left.nullable && right.nullable,
+        }
+    }
+
+    /// Repeat a fragment any number of times.
+    fn star(&mut self, fragment: Fragment) -> Fragment {
+        for p in fragment.last.iter() {
+            for q in fragment.first.iter() {
+                self.positions[*p].follows.push((*q, Edge::Step));
+            }
+        }
+
+        Fragment {
+            nullable: true,
+            ..fragment
+        }
+    }
+
+    /// The configuration when entering a position (i.e., with its counter, if
+    /// any, at one).
+    fn enter(&self, q: usize) -> Configuration {
+        match self.positions[q].counter {
+            Some(..) => Configuration::Position(q, 1, 1),
+            None => Configuration::Position(q, 0, 0),
+        }
+    }
+
+    /// Follow an edge from a position with a run of counts to the next
+    /// position.
+    ///
+    /// This is `None` if the counter of the current position does not allow
+    /// it for any of its counts (i.e., the repetition is either incomplete or
+    /// complete).
+    fn follow(
+        &self,
+        p: usize,
+        (lo, hi): (usize, usize),
+        q: usize,
+        edge: Edge,
+    ) -> Option<Configuration> {
+        let counter = self.positions[p].counter.map(|c| self.counters[c]);
+
+        match (edge, counter) {
+            (Edge::Repeat, Some(counter)) => {
+                if counter.max.is_some_and(|max| lo >= max) {
+                    return None;
+                }
+
+                // The counts that reach the upper bound (if any) are dropped.
+                let cap = counter.cap();
+                Some(Configuration::Position(
+                    q,
+                    (lo + 1).min(cap),
+                    (hi + 1).min(cap),
+                ))
+            }
+            (Edge::Step, Some(counter)) if hi < counter.min => None,
+            _ => Some(self.enter(q)),
+        }
+    }
+
+    /// Check whether a match ends at a configuration.
+    fn is_final(&self, config: Configuration) -> bool {
+        match config {
+            Configuration::Dead => false,
+            Configuration::Start(..) => self.nullable,
+            Configuration::Position(p, _, hi) => {
+                let position = &self.positions[p];
+
+                position.last && position.counter.is_none_or(|c| hi >= self.counters[c].min)
+            }
+        }
+    }
+
+    /// Compute the [`StateID`] of a configuration.
+    fn encode(&self, config: Configuration, matched: bool) -> StateID {
+        let index = match config {
+            Configuration::Dead => DEAD,
+            Configuration::Start(Anchored::No) => UNANCHORED,
+            Configuration::Start(..) => ANCHORED,
+            Configuration::Position(p, lo, hi) => {
+                let index = match self.runs {
+                    true => (hi.saturating_sub(1) * hi) / 2 + lo.saturating_sub(1),
+                    false => hi.saturating_sub(1),
+                };
+
+                POSITIONS + self.offsets[p] + index
+            }
+        };
+
+        // The size of the automaton was checked against the limit when built.
+        StateID::must(index * 2 + usize::from(matched))
+    }
+
+    /// Recover the configuration of a [`StateID`].
+    fn decode(&self, sid: StateID) -> (Configuration, bool) {
+        let (index, matched) = (sid.as_usize() / 2, sid.as_usize() % 2 == 1);
+
+        let config = match index {
+            DEAD => Configuration::Dead,
+            ANCHORED => Configuration::Start(Anchored::Yes),
+            UNANCHORED => Configuration::Start(Anchored::No),
+            _ => {
+                let index = index - POSITIONS;
+                let p = self.offsets.partition_point(|offset| *offset <= index) - 1;
+
+                let index = index - self.offsets[p];
+
+                match self.positions[p].counter {
+                    // The runs that end at `hi` begin at `(hi - 1) * hi / 2`.
+                    Some(..) if self.runs => {
+                        let hi = (1 + 8 * index).isqrt().div_ceil(2);
+                        let lo = index - (hi - 1) * hi / 2 + 1;
+
+                        Configuration::Position(p, lo, hi)
+                    }
+                    Some(..) => Configuration::Position(p, index + 1, index + 1),
+                    None => Configuration::Position(p, 0, 0),
+                }
+            }
+        };
+
+        (config, matched)
+    }
+}
+
+impl Fragment {
+    /// The fragment of an empty sub-pattern.
+    fn empty() -> Self {
+        Fragment {
+            first: Vec::new(),
+            last: Vec::new(),
+            nullable: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex_automata::Anchored;
+
+    use crate::compiler::Compiler;
+
+    use super::{Configuration, CountingAutomaton};
+
+    #[test]
+    fn counting() {
+        let ast = Compiler::new().compile("[[:a:]]{2,100000}[[:b:]]").unwrap();
+
+        let automaton = CountingAutomaton::build(&ast, false).unwrap();
+
+        // The repetition is counted rather than expanded.
+        assert!(automaton.is_counting());
+        assert_eq!(2, automaton.positions.len());
+
+        let run = |bytes: &[u8]| {
+            let mut states = vec![automaton.start_state(Anchored::Yes)];
+
+            for byte in bytes {
+                states = states
+                    .iter()
+                    .flat_map(|sid| automaton.next_states(*sid, *byte))
+                    .filter(|sid| !automaton.is_dead_state(*sid))
+                    .collect();
+            }
+
+            states
+                .iter()
+                .any(|sid| automaton.is_match_state(automaton.next_eoi_state(*sid)))
+        };
+
+        assert!(!run(b"ab"));
+        assert!(run(b"aab"));
+        assert!(run(&[vec![b'a'; 5000], vec![b'b']].concat()));
+        assert!(!run(b"aaba"));
+    }
+
+    #[test]
+    fn stream() {
+        let live = |bound: usize| {
+            let pattern = format!("[[:a:]]{{1,{bound}}}[[:b:]]");
+            let ast = Compiler::new().compile(&pattern).unwrap();
+            let automaton = CountingAutomaton::build(&ast, false).unwrap();
+
+            let mut states = vec![automaton.start_state(Anchored::No)];
+            let mut live = 0;
+
+            for _ in 0..1200 {
+                states = states
+                    .iter()
+                    .flat_map(|sid| automaton.next_states(*sid, b'a'))
+                    .filter(|sid| !automaton.is_dead_state(*sid))
+                    .collect();
+
+                automaton.merge(&mut states);
+                live = live.max(states.len());
+            }
+
+            assert!(states
+                .iter()
+                .flat_map(|sid| automaton.next_states(*sid, b'b'))
+                .any(|sid| automaton.is_match_state(automaton.next_eoi_state(sid))));
+
+            live
+        };
+
+        // The counts of every start are merged into a single run. So, the
+        // number of live states is independent of the bound.
+        assert_eq!(live(10), live(100));
+        assert_eq!(live(10), live(1000));
+    }
+
+    #[test]
+    fn runs() {
+        let ast = Compiler::new().compile("[[:a:]]{3,5}[[:b:]]").unwrap();
+        let automaton = CountingAutomaton::build(&ast, false).unwrap();
+
+        let start = automaton.start_state(Anchored::No);
+        let mut states = vec![start];
+
+        // The matches of each start within `aaaaaab` (i.e., by whether `b`
+        // follows a run of 3 to 5 `a`) are those of the merged runs.
+        for (i, byte) in b"aaaaaab".iter().enumerate() {
+            states = states
+                .iter()
+                .flat_map(|sid| automaton.next_states(*sid, *byte))
+                .filter(|sid| !automaton.is_dead_state(*sid))
+                .collect();
+
+            automaton.merge(&mut states);
+
+            // Every configuration survives a round trip through its state.
+            for sid in states.iter() {
+                let (config, matched) = automaton.decode(*sid);
+                assert_eq!(*sid, automaton.encode(config, matched));
+            }
+
+            if i == 5 {
+                assert!(states.contains(&automaton.encode(Configuration::Position(0, 1, 5), false)));
+            }
+        }
+
+        assert!(states
+            .iter()
+            .any(|sid| automaton.is_match_state(automaton.next_eoi_state(*sid))));
+    }
+}
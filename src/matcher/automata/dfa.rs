@@ -2,7 +2,7 @@ use std::error::Error;
 
 use regex_automata::HalfMatch;
 
-use crate::datastream::frame::Frame;
+use crate::matcher::symbols::SymbolSet;
 
 pub mod forward;
 pub mod reverse;
@@ -15,7 +15,7 @@ pub trait DeterministicFiniteAutomaton {
     ///
     /// The main interface for which all DFA's must implement is to simulate the
     /// corresponding DFA and return a set of valid [`HalfMatch`].
-    fn run(&self, haystack: &[Frame]) -> Result<Vec<HalfMatch>, Box<dyn Error>>;
+    fn run(&self, haystack: &[SymbolSet]) -> Result<Vec<HalfMatch>, Box<dyn Error>>;
 }
 
 /// The default size to offset all matches by.
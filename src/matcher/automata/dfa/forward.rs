@@ -1,16 +1,15 @@
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
 
-use regex_automata::dfa::{dense, Automaton, StartError, StartKind};
+use regex_automata::dfa::{dense, StartError, StartKind};
 use regex_automata::nfa::thompson;
+use regex_automata::util::primitives::StateID;
 use regex_automata::util::start::Config;
 use regex_automata::util::syntax;
-use regex_automata::{Anchored, HalfMatch, PatternID};
+use regex_automata::{Anchored, HalfMatch, MatchKind, PatternID};
 
-use crate::compiler::ir::ast::SpatialFormula;
-use crate::datastream::frame::Frame;
-use crate::matcher::automata::{AutomatonType, State};
-use crate::monitor::Monitor;
+use crate::matcher::automata::{AutomatonType, Options, State};
+use crate::matcher::symbols::{SymbolSet, BLANK};
 use crate::symbolizer::ast::SymbolicAbstractSyntaxTree as AST;
 
 use super::DeterministicFiniteAutomaton;
@@ -19,28 +18,28 @@ use super::DeterministicFiniteAutomaton;
 ///
 /// This DFA is configured for anchored searches. Therefore, it should be ran
 /// only to find the end position of a search.
-pub struct DeterministicFiniteAutomata<'a> {
+pub struct DeterministicFiniteAutomata {
     pub automata: AutomatonType,
-    pub fmap: HashMap<char, &'a SpatialFormula>,
 }
 
-impl DeterministicFiniteAutomaton for DeterministicFiniteAutomata<'_> {
+impl DeterministicFiniteAutomaton for DeterministicFiniteAutomata {
     /// Simulate the DFA.
     ///
-    /// This simulates the DFA on a slice of [`Frame`]. The default behavior is
+    /// This simulates the DFA on a slice of [`SymbolSet`] (i.e., the symbols
+    /// satisfied by each frame). The default behavior is
     /// to find the longest leftmost match. It is assumed that all matches are
     /// anchored (i.e., a match always begins at the first frame provided).
     ///
     /// As a result of this behavior, it is recommended to call run incrementally
     /// to collect all possible matches over the complete haystack.
-    fn run(&self, haystack: &[Frame]) -> Result<Vec<HalfMatch>, Box<dyn Error>> {
+    fn run(&self, haystack: &[SymbolSet]) -> Result<Vec<HalfMatch>, Box<dyn Error>> {
         let mut mats = Vec::new();
         let mut states = HashSet::new();
 
         // Initialize states with the start state of the DFA.
         states.insert(self.initial()?);
 
-        for (at, frame) in haystack.iter().enumerate() {
+        for (at, symbols) in haystack.iter().enumerate() {
             // Get the next set of states.
             //
             // This should generate a new [`HashSet`] with only the next set of
@@ -48,7 +47,7 @@ impl DeterministicFiniteAutomaton for DeterministicFiniteAutomata<'_> {
             // states to reduce memory usage.
             states = states
                 .into_iter()
-                .flat_map(|state| self.transition(state, frame))
+                .flat_map(|state| self.transition(state, symbols))
                 .collect();
 
             // For each state, take action upon it.
@@ -84,55 +83,240 @@ impl DeterministicFiniteAutomaton for DeterministicFiniteAutomata<'_> {
     }
 }
 
-impl<'a> DeterministicFiniteAutomata<'a> {
+impl DeterministicFiniteAutomata {
     /// Create a new forward-matching DFA.
     ///
     /// This function is exposed if a different configuration is requierd.
     /// Otherwise, for all other cases, use the [`self::build`] interface to
     /// construct this DFA.
-    pub fn new(automata: AutomatonType, fmap: HashMap<char, &'a SpatialFormula>) -> Self {
-        DeterministicFiniteAutomata { automata, fmap }
+    pub fn new(automata: AutomatonType) -> Self {
+        DeterministicFiniteAutomata { automata }
     }
 
-    /// Take the next transition on the `Frame`.
+    /// Take the next transition on the symbols satisfied by a frame.
     ///
     /// For this implementation, whether to take a transition is determined by
-    /// whether the [`Monitor`] evaluates to true on the [`Frame`]. The cases are
+    /// whether the symbol is in the [`SymbolSet`] of the frame. The cases are
     /// as follows:
     ///
-    /// I. If true, transition on the corresponding symbol from the [`State`].
-    /// II. If false, transition on a blank symbol from the [`State`].
+    /// I. If present, transition on the corresponding symbol from the [`State`].
+    /// II. If the set is empty, transition on a blank symbol from the [`State`].
     ///
     /// For (II), this is similar to transitioning on a byte that is not in teh
     /// pattern of a traditional RE.
-    fn transition(&self, state: State, frame: &Frame) -> HashSet<State> {
-        let monitor = Monitor::new();
+    fn transition(&self, state: State, symbols: &SymbolSet) -> HashSet<State> {
         let mut nexts = HashSet::new();
 
-        for (symbol, formula) in self.fmap.iter() {
-            if monitor.evaluate(frame, formula) {
-                let sid = self.automata.next_state(*state.id(), *symbol as u8);
-                let next = State::new(sid, &self.automata);
+        if let Some(sid) = self.automata.next_frame_state(*state.id(), symbols) {
+            nexts.insert(State::new(sid, &self.automata));
+            return nexts;
+        }
 
-                nexts.insert(next);
+        for symbol in symbols.bytes() {
+            for sid in self.automata.next_states(*state.id(), symbol) {
+                nexts.insert(State::new(sid, &self.automata));
             }
         }
 
         if nexts.is_empty() {
-            let sid = self.automata.next_state(*state.id(), b'Z');
-            let next = State::new(sid, &self.automata);
+            for sid in self.automata.next_states(*state.id(), BLANK) {
+                nexts.insert(State::new(sid, &self.automata));
+            }
+        }
+
+        nexts
+    }
+
+    /// Find the end of the longest non-empty match anchored at `start`.
+    ///
+    /// Unlike [`DeterministicFiniteAutomaton::run`], this tracks the set of
+    /// live states directly and records the configurations (i.e., the set of
+    /// states before consuming the frame at a position) that are known to
+    /// never reach a match into the [`FailureMemo`]. As the DFA is
+    /// deterministic with respect to a configuration, a later search that
+    /// reaches a failed configuration stops immediately.
+    ///
+    /// When searching from successive starts, each configuration is therefore
+    /// visited a constant number of times, which keeps the overall search
+    /// linear in the length of the haystack (see Reps, "Maximal-Munch
+    /// Tokenization in Linear Time").
+    pub fn longest(
+        &self,
+        haystack: &[SymbolSet],
+        start: usize,
+        memo: &mut FailureMemo,
+    ) -> Result<Option<usize>, Box<dyn Error>> {
+        let mut states = vec![*self.initial()?.id()];
+        let mut visited = Vec::new();
+        let mut end = None;
+
+        let mut at = start;
+        loop {
+            // The initial configuration is never memoized as it may only reach
+            // an empty match, which is not reported.
+            if at > start {
+                if memo.contains(&states, at) {
+                    break;
+                }
+
+                visited.push((states.clone(), at));
+            }
+
+            if at == haystack.len() {
+                if at > start && states.iter().any(|sid| self.is_final(*sid)) {
+                    end = Some(at);
+                }
+
+                break;
+            }
+
+            states = self.step(&states, &haystack[at]);
+
+            // Matches are delayed by a single transition. So, a match state
+            // after consuming the frame at `at` ends a match at `at`.
+            if at > start && states.iter().any(|sid| self.automata.is_match_state(*sid)) {
+                end = Some(at);
+            }
+
+            if states.is_empty() {
+                break;
+            }
+
+            at += 1;
+        }
 
-            nexts.insert(next);
+        // Every configuration visited after the last match cannot reach a
+        // match; otherwise, it would have been found.
+        let last = end.unwrap_or(start);
+
+        for (states, at) in visited.into_iter().filter(|(_, at)| *at > last) {
+            memo.insert(states, at);
         }
 
+        Ok(end)
+    }
+
+    /// Take the next transition from a set of live states.
+    ///
+    /// The result is sorted without duplicates (see [`AutomatonType::merge`]),
+    /// and dead states are removed.
+    pub fn step(&self, states: &[StateID], symbols: &SymbolSet) -> Vec<StateID> {
+        let mut nexts = states
+            .iter()
+            .flat_map(|sid| self.successors(*sid, symbols))
+            .collect::<Vec<_>>();
+
+        self.automata.merge(&mut nexts);
+
         nexts
     }
 
+    /// Take the next transitions from a single live state.
+    ///
+    /// This follows the same cases as [`Self::transition`], except that dead
+    /// states are removed.
+    pub fn successors(
+        &self,
+        sid: StateID,
+        symbols: &SymbolSet,
+    ) -> impl Iterator<Item = StateID> + '_ {
+        self.edges(sid, symbols).map(|(_, next)| next)
+    }
+
+    /// Take the next transitions from a single live state along with the
+    /// symbol of each transition.
+    ///
+    /// An automaton that steps over entire frames takes a single transition,
+    /// which is recorded with the first symbol of the frame (or [`BLANK`]).
+    fn edges(&self, sid: StateID, symbols: &SymbolSet) -> impl Iterator<Item = (u8, StateID)> + '_ {
+        let blank = symbols.is_empty().then_some(BLANK);
+
+        let frame = self
+            .automata
+            .next_frame_state(sid, symbols)
+            .map(|next| (symbols.bytes().next().unwrap_or(BLANK), next));
+
+        let bytes = match frame {
+            Some(..) => None,
+            None => Some(symbols.bytes().chain(blank)),
+        };
+
+        bytes
+            .into_iter()
+            .flatten()
+            .flat_map(move |symbol| {
+                self.automata
+                    .next_states(sid, symbol)
+                    .map(move |next| (symbol, next))
+            })
+            .chain(frame)
+            .filter(|(_, sid)| !self.automata.is_dead_state(*sid))
+    }
+
+    /// Find the symbols taken along a path that matches the entire haystack.
+    ///
+    /// A frame may satisfy several symbols, but only some of them may lead to a
+    /// match. So, each state reached is recorded with the state and symbol it
+    /// was first reached from, and a path is then traced back from a state that
+    /// matches at the end (i.e., with the fewest mismatches, if any). If the
+    /// haystack does not match, then [`None`] is returned.
+    pub fn path(&self, haystack: &[SymbolSet]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        let mut layers: Vec<HashMap<StateID, (StateID, u8)>> = Vec::new();
+        let mut states = vec![*self.initial()?.id()];
+
+        for symbols in haystack.iter() {
+            let mut layer = HashMap::new();
+
+            for sid in states.iter() {
+                for (symbol, next) in self.edges(*sid, symbols) {
+                    layer.entry(next).or_insert((*sid, symbol));
+                }
+            }
+
+            states = layer.keys().copied().collect();
+            states.sort_unstable();
+
+            layers.push(layer);
+        }
+
+        let Some(mut sid) = states
+            .into_iter()
+            .filter(|sid| self.is_final(*sid))
+            .min_by_key(|sid| self.automata.errors(*sid))
+        else {
+            return Ok(None);
+        };
+
+        let mut path = vec![BLANK; haystack.len()];
+
+        for (at, layer) in layers.iter().enumerate().rev() {
+            let (prev, symbol) = layer[&sid];
+
+            path[at] = symbol;
+            sid = prev;
+        }
+
+        Ok(Some(path))
+    }
+
+    /// Check whether a match ends at the current position from a state.
+    ///
+    /// As matches are delayed by a single transition, this takes the EOI
+    /// transition to check whether the frames consumed so far match.
+    pub fn is_final(&self, sid: StateID) -> bool {
+        self.automata
+            .is_match_state(self.automata.next_eoi_state(sid))
+    }
+
     /// Check EOI.
     ///
     /// The End of Input (EOI) is checked for a final match. If taking the EOI
     /// transition results in a match state, then return as final match.
-    fn eoi(&self, state: State, haystack: &[Frame]) -> Result<Option<HalfMatch>, Box<dyn Error>> {
+    fn eoi(
+        &self,
+        state: State,
+        haystack: &[SymbolSet],
+    ) -> Result<Option<HalfMatch>, Box<dyn Error>> {
         if let State::Accepting(..) = self.transitioneoi(state) {
             return Ok(Some(HalfMatch::new(PatternID::new(0)?, haystack.len())));
         }
@@ -154,7 +338,7 @@ impl<'a> DeterministicFiniteAutomata<'a> {
     /// Retrieve the initial [`State`] to start from an Automata.
     ///
     /// For further information, see `regex_automata::util::start`.
-    fn initial(&self) -> Result<State, StartError> {
+    pub fn initial(&self) -> Result<State, StartError> {
         // Retrieve the start state.
         //
         // The start state is anchored as all inputs to this
@@ -177,6 +361,39 @@ impl<'a> DeterministicFiniteAutomata<'a> {
     }
 }
 
+/// A record of the configurations of a DFA that never reach a match.
+///
+/// A configuration is the set of live states before consuming the frame at a
+/// position. The configurations are grouped by position, as only a few
+/// distinct sets of states are typically reached at any one position.
+#[derive(Debug, Default)]
+pub struct FailureMemo {
+    failed: Vec<Vec<Vec<StateID>>>,
+}
+
+impl FailureMemo {
+    /// Create a new [`FailureMemo`] for a haystack of the `len` provided.
+    pub fn new(len: usize) -> Self {
+        FailureMemo {
+            failed: vec![Vec::new(); len + 1],
+        }
+    }
+
+    /// Check whether the configuration is known to never reach a match.
+    pub fn contains(&self, states: &[StateID], at: usize) -> bool {
+        self.failed
+            .get(at)
+            .is_some_and(|failed| failed.iter().any(|f| f == states))
+    }
+
+    /// Record a configuration that never reaches a match.
+    pub fn insert(&mut self, states: Vec<StateID>, at: usize) {
+        if let Some(failed) = self.failed.get_mut(at) {
+            failed.push(states);
+        }
+    }
+}
+
 /// Build a forward searching DFA.
 ///
 /// The `regex-automata` library is used primarily here to construct the
@@ -195,11 +412,40 @@ pub fn build(ast: &AST) -> Result<DeterministicFiniteAutomata, Box<dyn Error>> {
         .thompson(thompson::Config::new().reverse(false).utf8(true))
         .build(&super::super::super::regexify(ast))?;
 
-    let fmap = ast
-        .fmap()
-        .iter()
-        .map(|x| (x.symbol, &x.formula))
-        .collect::<HashMap<char, &SpatialFormula>>();
+    Ok(DeterministicFiniteAutomata::new(AutomatonType::Dense(
+        Box::new(automata),
+    )))
+}
+
+/// Build a forward searching DFA that recognizes all matches.
+///
+/// Unlike [`build`], the DFA does not stop at the first (i.e., preferred) match
+/// of a pattern. Therefore, a match state is reached whenever the frames
+/// consumed so far match, which is required when the DFA is never restarted
+/// (e.g., online).
+///
+/// The kind of DFA built is determined by the [`Options`] provided. The other
+/// backends also recognize all matches, as they simulate every thread.
+pub fn build_all(
+    ast: &AST,
+    options: &Options,
+) -> Result<DeterministicFiniteAutomata, Box<dyn Error>> {
+    let automata = options.build(ast, false, |pattern, limit| {
+        Ok(dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .minimize(true)
+                    .accelerate(false)
+                    .match_kind(MatchKind::All)
+                    .start_kind(StartKind::Anchored)
+                    .specialize_start_states(true)
+                    .dfa_size_limit(limit)
+                    .determinize_size_limit(limit),
+            )
+            .syntax(syntax::Config::new().unicode(false).utf8(true))
+            .thompson(thompson::Config::new().reverse(false).utf8(true))
+            .build(pattern)?)
+    })?;
 
-    Ok(DeterministicFiniteAutomata::new(automata, fmap))
+    Ok(DeterministicFiniteAutomata::new(automata))
 }
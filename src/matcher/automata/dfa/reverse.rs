@@ -1,54 +1,65 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::error::Error;
 
-use regex_automata::dfa::{dense, Automaton, StartError, StartKind};
+use regex_automata::dfa::{dense, StartError, StartKind};
 use regex_automata::nfa::thompson;
 use regex_automata::util::start::Config;
 use regex_automata::util::syntax;
-use regex_automata::{Anchored, HalfMatch, PatternID};
+use regex_automata::{Anchored, HalfMatch, MatchKind, PatternID};
 
-use crate::compiler::ir::ast::SpatialFormula;
-use crate::datastream::frame::Frame;
-use crate::matcher::automata::{AutomatonType, State};
-use crate::monitor::Monitor;
+use crate::matcher::automata::{AutomatonType, Options, State};
+use crate::matcher::symbols::{SymbolSet, BLANK};
 use crate::symbolizer::ast::SymbolicAbstractSyntaxTree as AST;
 
 use super::{DeterministicFiniteAutomaton, OFFSET};
 
 /// A reverse matching DFA.
 ///
-/// This DFA is configured for anchored searches. Therefore, it should be ran
-/// only to find the start position of a search.
-pub struct DeterministicFiniteAutomata<'a> {
+/// By default, this DFA is configured for anchored searches. Therefore, it
+/// should be ran only to find the start position of a search. If configured
+/// for unanchored searches, it finds the start positions of all matches that
+/// end anywhere within the haystack.
+pub struct DeterministicFiniteAutomata {
     pub automata: AutomatonType,
-    pub fmap: HashMap<char, &'a SpatialFormula>,
+    pub anchored: Anchored,
 }
 
-impl DeterministicFiniteAutomaton for DeterministicFiniteAutomata<'_> {
+impl DeterministicFiniteAutomaton for DeterministicFiniteAutomata {
     /// Simulate the DFA.
     ///
-    /// This simulates the DFA on a slice of [`Frame`]. The default behavior is
+    /// This simulates the DFA on a slice of [`SymbolSet`] (i.e., the symbols
+    /// satisfied by each frame). The default behavior is
     /// to find the longest leftmost match. It is assumed that all matches are
     /// anchored (i.e., a match always begins at the first frame provided).
     ///
     /// As a result of this behavior, it is recommended to call run incrementally
     /// to collect all possible matches over the complete haystack.
-    fn run(&self, haystack: &[Frame]) -> Result<Vec<HalfMatch>, Box<dyn Error>> {
+    fn run(&self, haystack: &[SymbolSet]) -> Result<Vec<HalfMatch>, Box<dyn Error>> {
         let mut mats = Vec::new();
         let mut states = HashSet::new();
 
         // Initialize states with the start state of the DFA.
         states.insert(self.initial()?);
 
-        for (at, frame) in haystack.iter().enumerate().rev() {
+        for (at, symbols) in haystack.iter().enumerate().rev() {
             // Get the next set of states.
             //
             // This should generate a new [`HashSet`] with only the next set of
             // states. We do not keep a historical record of previously visited
             // states to reduce memory usage.
-            states = states
+            let mut sids = states
                 .into_iter()
-                .flat_map(|state| self.transition(state, frame))
+                .flat_map(|state| self.transition(state, symbols))
+                .map(|state| *state.id())
+                .collect::<Vec<_>>();
+
+            // The states of every start reached so far are merged, such that
+            // their number is independent of the bounds of a repetition.
+            self.automata.merge(&mut sids);
+
+            states = sids
+                .into_iter()
+                .map(|sid| State::new(sid, &self.automata))
                 .collect();
 
             // For each state, take action upon it.
@@ -90,45 +101,54 @@ impl DeterministicFiniteAutomaton for DeterministicFiniteAutomata<'_> {
     }
 }
 
-impl<'a> DeterministicFiniteAutomata<'a> {
+impl DeterministicFiniteAutomata {
     /// Create a new reverse-matching DFA.
     ///
     /// This function is exposed if a different configuration is requierd.
     /// Otherwise, for all other cases, use the [`self::build`] interface to
     /// construct this DFA.
-    pub fn new(automata: AutomatonType, fmap: HashMap<char, &'a SpatialFormula>) -> Self {
-        DeterministicFiniteAutomata { automata, fmap }
+    pub fn new(automata: AutomatonType) -> Self {
+        DeterministicFiniteAutomata {
+            automata,
+            anchored: Anchored::Yes,
+        }
     }
 
-    /// Take the next transition on the `Frame`.
+    /// Set whether searches are anchored at the end of the haystack.
+    pub fn anchored(mut self, anchored: Anchored) -> Self {
+        self.anchored = anchored;
+        self
+    }
+
+    /// Take the next transition on the symbols satisfied by a frame.
     ///
     /// For this implementation, whether to take a transition is determined by
-    /// whether the [`Monitor`] evaluates to true on the [`Frame`]. The cases are
+    /// whether the symbol is in the [`SymbolSet`] of the frame. The cases are
     /// as follows:
     ///
-    /// I. If true, transition on the corresponding symbol from the [`State`].
-    /// II. If false, transition on a blank symbol from the [`State`].
+    /// I. If present, transition on the corresponding symbol from the [`State`].
+    /// II. If the set is empty, transition on a blank symbol from the [`State`].
     ///
     /// For (II), this is similar to transitioning on a byte that is not in teh
     /// pattern of a traditional RE.
-    fn transition(&self, state: State, frame: &Frame) -> HashSet<State> {
-        let monitor = Monitor::new();
+    fn transition(&self, state: State, symbols: &SymbolSet) -> HashSet<State> {
         let mut nexts = HashSet::new();
 
-        for (symbol, formula) in self.fmap.iter() {
-            if monitor.evaluate(frame, formula) {
-                let sid = self.automata.next_state(*state.id(), *symbol as u8);
-                let next = State::new(sid, &self.automata);
+        if let Some(sid) = self.automata.next_frame_state(*state.id(), symbols) {
+            nexts.insert(State::new(sid, &self.automata));
+            return nexts;
+        }
 
-                nexts.insert(next);
+        for symbol in symbols.bytes() {
+            for sid in self.automata.next_states(*state.id(), symbol) {
+                nexts.insert(State::new(sid, &self.automata));
             }
         }
 
         if nexts.is_empty() {
-            let sid = self.automata.next_state(*state.id(), b'Z');
-            let next = State::new(sid, &self.automata);
-
-            nexts.insert(next);
+            for sid in self.automata.next_states(*state.id(), BLANK) {
+                nexts.insert(State::new(sid, &self.automata));
+            }
         }
 
         nexts
@@ -163,12 +183,12 @@ impl<'a> DeterministicFiniteAutomata<'a> {
     fn initial(&self) -> Result<State, StartError> {
         // Retrieve the start state.
         //
-        // The start state is anchored as all inputs to this
-        // [`DeterministicFiniteAutomata`] begin searching at index 0. Therefore,
-        // matches are only found starting from the beginning (i.e., anchored).
+        // The start state is anchored by default as all inputs to this
+        // [`DeterministicFiniteAutomata`] begin searching at the last index.
+        // Therefore, matches are only found ending at the end (i.e., anchored).
         let sid = self
             .automata
-            .start_state(&Config::new().anchored(Anchored::Yes))?;
+            .start_state(&Config::new().anchored(self.anchored))?;
 
         // The start state shall never be the match state.
         //
@@ -201,11 +221,41 @@ pub fn build(ast: &AST) -> Result<DeterministicFiniteAutomata, Box<dyn Error>> {
         .thompson(thompson::Config::new().reverse(true).utf8(true))
         .build(&super::super::super::regexify(ast))?;
 
-    let fmap = ast
-        .fmap()
-        .iter()
-        .map(|x| (x.symbol, &x.formula))
-        .collect::<HashMap<char, &SpatialFormula>>();
+    Ok(DeterministicFiniteAutomata::new(AutomatonType::Dense(
+        Box::new(automata),
+    )))
+}
+
+/// Build an unanchored reverse searching DFA.
+///
+/// Unlike [`build`], this DFA reports every position where a match starts (of
+/// any match that ends within the haystack). Therefore, it is configured to
+/// report all matches rather than stopping at the first one. A dense DFA also
+/// supports anchored searches, as the sub-patterns of a
+/// [`BooleanAutomaton`](crate::matcher::automata::boolean::BooleanAutomaton)
+/// are searched from each start.
+///
+/// The kind of DFA built is determined by the [`Options`] provided.
+pub fn build_unanchored(
+    ast: &AST,
+    options: &Options,
+) -> Result<DeterministicFiniteAutomata, Box<dyn Error>> {
+    let automata = options.build(ast, true, |pattern, limit| {
+        Ok(dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .minimize(true)
+                    .accelerate(false)
+                    .match_kind(MatchKind::All)
+                    .start_kind(StartKind::Both)
+                    .specialize_start_states(true)
+                    .dfa_size_limit(limit)
+                    .determinize_size_limit(limit),
+            )
+            .syntax(syntax::Config::new().unicode(false).utf8(true))
+            .thompson(thompson::Config::new().reverse(true).utf8(true))
+            .build(pattern)?)
+    })?;
 
-    Ok(DeterministicFiniteAutomata::new(automata, fmap))
+    Ok(DeterministicFiniteAutomata::new(automata).anchored(Anchored::No))
 }
@@ -0,0 +1,338 @@
+//! A lazily built DFA.
+//!
+//! Building a dense DFA up front requires determinizing (and minimizing) the
+//! entire NFA of a pattern, which grows quickly with bounded repetitions (e.g.,
+//! `{1,2000}`). Instead, this DFA is built from the Thompson NFA one state at a
+//! time, only as states are reached during a search.
+//!
+//! Each state of the DFA is a set of NFA states. Unlike the lazy DFA of
+//! `regex-automata`, states are never evicted. So, a [`StateID`] remains valid
+//! for as long as the DFA exists, which is required as several states are kept
+//! across frames (e.g., online). The memory used by states is therefore
+//! bounded by the number of states reached, rather than by the number of
+//! states possible. The transitions between them, of which there may be many
+//! more, are only cached up to a budget, past which they are evicted and built
+//! again as they are taken.
+//!
+//! The states and transitions are shared behind a lock. So, the DFA may be
+//! searched from several threads at once.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::RwLock;
+
+use regex_automata::nfa::thompson::{self, WhichCaptures, NFA};
+use regex_automata::util::primitives::StateID;
+use regex_automata::util::syntax;
+use regex_automata::Anchored;
+
+/// The [`StateID`] of the dead state.
+const DEAD: StateID = StateID::ZERO;
+
+/// A state of the [`LazyAutomaton`].
+///
+/// As with the dense DFA, matches are delayed by a single transition. So, a
+/// state is a match state if the set of NFA states it was reached from
+/// contained a match.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct LazyState {
+    /// The sorted set of NFA states.
+    set: Vec<StateID>,
+    matched: bool,
+}
+
+/// The size (in bytes) of a cached transition.
+const TRANSITION: usize = std::mem::size_of::<((StateID, Option<u8>), StateID)>();
+
+/// The states built so far.
+#[derive(Debug, Default)]
+struct Cache {
+    states: Vec<LazyState>,
+    ids: HashMap<LazyState, StateID>,
+
+    /// The transitions taken so far, where the EOI is the `None` byte.
+    transitions: HashMap<(StateID, Option<u8>), StateID>,
+
+    /// The start states (anchored and unanchored, respectively).
+    starts: [Option<StateID>; 2],
+
+    /// The NFA states visited by an epsilon closure (see
+    /// [`Cache::closure`]), which is reused across closures.
+    seen: Vec<bool>,
+}
+
+/// A DFA built lazily from a Thompson NFA.
+#[derive(Debug)]
+pub struct LazyAutomaton {
+    nfa: NFA,
+    cache: RwLock<Cache>,
+
+    /// The limit (in bytes) on the memory used by cached transitions.
+    capacity: usize,
+}
+
+impl LazyAutomaton {
+    /// Create a new [`LazyAutomaton`] from a Thompson NFA.
+    pub fn new(nfa: NFA) -> Self {
+        let mut cache = Cache {
+            seen: vec![false; nfa.states().len()],
+            ..Cache::default()
+        };
+
+        // The dead state is always the first state.
+        cache.intern(LazyState {
+            set: Vec::new(),
+            matched: false,
+        });
+
+        LazyAutomaton {
+            nfa,
+            cache: RwLock::new(cache),
+            capacity: super::BUDGET,
+        }
+    }
+
+    /// Set the limit (in bytes) on the memory used by cached transitions.
+    ///
+    /// Once exceeded, every cached transition is evicted. The states are kept,
+    /// as they may still be referenced (see the [module](self) documentation).
+    pub fn capacity(mut self, bytes: usize) -> Self {
+        self.capacity = bytes;
+        self
+    }
+
+    /// Build a new [`LazyAutomaton`] from a pattern.
+    pub fn build(pattern: &str, reverse: bool) -> Result<Self, Box<dyn Error>> {
+        let nfa = thompson::Compiler::new()
+            .syntax(syntax::Config::new().unicode(false).utf8(true))
+            .configure(
+                thompson::Config::new()
+                    .reverse(reverse)
+                    .utf8(true)
+                    .which_captures(WhichCaptures::None),
+            )
+            .build(pattern)?;
+
+        Ok(LazyAutomaton::new(nfa))
+    }
+
+    /// Retrieve the start state.
+    pub fn start_state(&self, anchored: Anchored) -> StateID {
+        let (index, start) = match anchored {
+            Anchored::No => (1, self.nfa.start_unanchored()),
+            _ => (0, self.nfa.start_anchored()),
+        };
+
+        if let Some(sid) = self.cache.read().unwrap().starts[index] {
+            return sid;
+        }
+
+        let mut cache = self.cache.write().unwrap();
+
+        let set = cache.closure(&self.nfa, [start]);
+        let sid = cache.intern(LazyState {
+            set,
+            matched: false,
+        });
+
+        cache.starts[index] = Some(sid);
+
+        sid
+    }
+
+    /// Take the transition on a byte from a state.
+    pub fn next_state(&self, sid: StateID, byte: u8) -> StateID {
+        self.transition(sid, Some(byte))
+    }
+
+    /// Take the transition on the EOI from a state.
+    pub fn next_eoi_state(&self, sid: StateID) -> StateID {
+        self.transition(sid, None)
+    }
+
+    /// Check whether a state is a match state.
+    pub fn is_match_state(&self, sid: StateID) -> bool {
+        self.cache.read().unwrap().states[sid.as_usize()].matched
+    }
+
+    /// Check whether a state is the dead state.
+    pub fn is_dead_state(&self, sid: StateID) -> bool {
+        sid == DEAD
+    }
+
+    /// Check whether a state is a start state.
+    pub fn is_start_state(&self, sid: StateID) -> bool {
+        self.cache.read().unwrap().starts.contains(&Some(sid))
+    }
+
+    /// The number of states built so far.
+    pub fn len(&self) -> usize {
+        self.cache.read().unwrap().states.len()
+    }
+
+    /// The memory (in bytes) used by cached transitions.
+    pub fn memory_usage(&self) -> usize {
+        self.cache.read().unwrap().transitions.len() * TRANSITION
+    }
+
+    /// Check whether no states (besides the dead state) have been built.
+    pub fn is_empty(&self) -> bool {
+        self.len() <= 1
+    }
+
+    /// Take the transition on a byte (or the EOI, if `None`) from a state.
+    ///
+    /// The transition is built (and cached) the first time it is taken, or
+    /// the first time since it was evicted (see [`LazyAutomaton::capacity`]).
+    fn transition(&self, sid: StateID, byte: Option<u8>) -> StateID {
+        if let Some(next) = self.cache.read().unwrap().transitions.get(&(sid, byte)) {
+            return *next;
+        }
+
+        let mut cache = self.cache.write().unwrap();
+
+        let mut matched = false;
+        let mut nexts = Vec::new();
+
+        for id in cache.states[sid.as_usize()].set.iter() {
+            match self.nfa.state(*id) {
+                thompson::State::Match { .. } => matched = true,
+                thompson::State::ByteRange { trans } => {
+                    if byte.is_some_and(|byte| trans.matches_byte(byte)) {
+                        nexts.push(trans.next);
+                    }
+                }
+                thompson::State::Sparse(sparse) => {
+                    if let Some(next) = byte.and_then(|byte| sparse.matches_byte(byte)) {
+                        nexts.push(next);
+                    }
+                }
+                thompson::State::Dense(dense) => {
+                    if let Some(next) = byte.and_then(|byte| dense.matches_byte(byte)) {
+                        nexts.push(next);
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        let set = cache.closure(&self.nfa, nexts);
+        let next = cache.intern(LazyState { set, matched });
+
+        if (cache.transitions.len() + 1) * TRANSITION > self.capacity {
+            cache.transitions.clear();
+        }
+
+        cache.transitions.insert((sid, byte), next);
+
+        next
+    }
+}
+
+impl Cache {
+    /// Find the [`StateID`] of a state, adding it if it is new.
+    fn intern(&mut self, state: LazyState) -> StateID {
+        if let Some(sid) = self.ids.get(&state) {
+            return *sid;
+        }
+
+        // The number of states is bounded by the memory available long before
+        // the limit of a [`StateID`] is reached.
+        let sid = StateID::must(self.states.len());
+
+        self.states.push(state.clone());
+        self.ids.insert(state, sid);
+
+        sid
+    }
+
+    /// Compute the epsilon closure of a set of NFA states.
+    ///
+    /// The result is sorted, and only includes states that consume a byte or
+    /// match, as no other state affects the transitions of the DFA.
+    fn closure(&mut self, nfa: &NFA, ids: impl IntoIterator<Item = StateID>) -> Vec<StateID> {
+        let mut stack = ids.into_iter().collect::<Vec<_>>();
+        let mut visited = Vec::new();
+        let mut set = Vec::new();
+
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut self.seen[id.as_usize()], true) {
+                continue;
+            }
+
+            visited.push(id);
+
+            match nfa.state(id) {
+                thompson::State::Union { alternates } => stack.extend(alternates.iter()),
+                thompson::State::BinaryUnion { alt1, alt2 } => stack.extend([*alt1, *alt2]),
+                thompson::State::Capture { next, .. } => stack.push(*next),
+                // Patterns never contain look-around assertions; so, they are
+                // never satisfied.
+                thompson::State::Look { .. } | thompson::State::Fail => continue,
+                _ => set.push(id),
+            }
+        }
+
+        // Only the states visited are reset, such that a closure takes time
+        // proportional to its size rather than to the size of the NFA.
+        for id in visited {
+            self.seen[id.as_usize()] = false;
+        }
+
+        set.sort_unstable();
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex_automata::Anchored;
+
+    use super::LazyAutomaton;
+
+    #[test]
+    fn lazy() {
+        let automaton = LazyAutomaton::build("ab{1,2000}", false).unwrap();
+        let mut sid = automaton.start_state(Anchored::Yes);
+
+        // Matches are delayed by a single transition.
+        sid = automaton.next_state(sid, b'a');
+        assert!(!automaton.is_match_state(automaton.next_eoi_state(sid)));
+
+        sid = automaton.next_state(sid, b'b');
+        assert!(!automaton.is_match_state(sid));
+        assert!(automaton.is_match_state(automaton.next_eoi_state(sid)));
+
+        sid = automaton.next_state(sid, b'b');
+        assert!(automaton.is_match_state(sid));
+
+        // The match is reported before the dead state is reached.
+        sid = automaton.next_state(sid, b'a');
+        assert!(automaton.is_match_state(sid));
+        assert!(automaton.is_dead_state(automaton.next_state(sid, b'a')));
+
+        // Only the states reached are built.
+        assert!(automaton.len() < 10);
+    }
+
+    #[test]
+    fn capacity() {
+        let automaton = LazyAutomaton::build("ab{1,2000}", false)
+            .unwrap()
+            .capacity(1024);
+
+        let mut sid = automaton.next_state(automaton.start_state(Anchored::Yes), b'a');
+
+        for _ in 0..2000 {
+            sid = automaton.next_state(sid, b'b');
+            assert!(automaton.memory_usage() <= 1024);
+        }
+
+        // The transitions evicted are built again as they are taken.
+        assert!(automaton.is_match_state(automaton.next_eoi_state(sid)));
+
+        sid = automaton.next_state(sid, b'b');
+        assert!(automaton.is_match_state(sid));
+        assert!(automaton.is_dead_state(automaton.next_state(sid, b'b')));
+    }
+}
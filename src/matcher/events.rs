@@ -0,0 +1,186 @@
+//! The events of an online search.
+//!
+//! A longest match is only final once no thread may extend it any further.
+//! For live monitoring, this may be long after the match became possible (or
+//! even found). So, the progress of each match is reported as it happens:
+//!
+//! * [`Event::PrefixStarted`] once a match becomes possible to complete.
+//! * [`Event::MatchExtended`] each time a match is found (or grows) that may
+//!   still grow.
+//! * [`Event::MatchClosed`] once the longest match is final.
+//!
+//! A prefix is started and a match is closed once per match, regardless of how
+//! many frames it takes for the match to close.
+
+use super::threads::Threads;
+use super::Match;
+
+/// An event of an online search.
+///
+/// The indices of each [`Match`] are absolute (see [`Threads::advance`]).
+#[derive(Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The frames of the [`Match`] are a prefix of a match (i.e., a match may
+    /// start at [`Match::start`]).
+    PrefixStarted(Match),
+
+    /// A match was found, but it may still be extended by future frames.
+    MatchExtended(Match),
+
+    /// The longest match is final.
+    MatchClosed(Match),
+}
+
+impl Event {
+    /// The [`Match`] of the [`Event`].
+    pub fn as_match(&self) -> &Match {
+        match self {
+            Event::PrefixStarted(m) | Event::MatchExtended(m) | Event::MatchClosed(m) => m,
+        }
+    }
+}
+
+/// The events reported of a search so far.
+///
+/// This tracks the progress of a set of [`Threads`] reporting matches of
+/// [`MatchKind::LeftmostLongest`](super::MatchKind::LeftmostLongest) so that
+/// each [`Event`] is reported only once.
+#[derive(Debug, Default)]
+pub struct Events {
+    /// The start of the prefix reported that is not yet part of a match, if
+    /// any.
+    prefix: Option<usize>,
+
+    /// The last match reported as extended, if any.
+    extended: Option<Match>,
+
+    /// The end of the last match reported (as extended or closed). A thread
+    /// that starts before it is already part of a reported match.
+    floor: usize,
+}
+
+impl Events {
+    /// Create a new [`Events`].
+    pub fn new() -> Self {
+        Events::default()
+    }
+
+    /// Update the [`Events`] with the matches released by the [`Threads`]
+    /// after they were advanced (or finished).
+    ///
+    /// The events returned are ordered by the matches closed, then any match
+    /// extended, then any prefix started.
+    ///
+    /// A prefix is reported once, until a match is found from it or every
+    /// thread dies. So, a pattern that remains possible to complete over
+    /// several frames is not reported again for each later start. A match is
+    /// reported as extended whenever its end advances, but only when it is the
+    /// leftmost match known. So, it is always closed with the same start.
+    pub fn update(&mut self, threads: &Threads, mats: Vec<Match>) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        for m in mats {
+            self.found(&m);
+
+            if self.extended.as_ref().is_some_and(|e| e.start < m.end) {
+                self.extended = None;
+            }
+
+            events.push(Event::MatchClosed(m));
+        }
+
+        if let Some(m) = threads.pending() {
+            // A thread with an earlier start may still find a match that
+            // overlaps (and, therefore, discards) this one.
+            let leftmost = threads.live().is_none_or(|start| start >= m.start);
+
+            if leftmost && self.extended.as_ref() != Some(&m) {
+                self.found(&m);
+                self.extended = Some(m.clone());
+
+                events.push(Event::MatchExtended(m));
+            }
+        }
+
+        let next = threads.starts().filter(|start| *start >= self.floor).min();
+
+        match next {
+            Some(start) if self.prefix.is_none() => {
+                self.prefix = Some(start);
+                events.push(Event::PrefixStarted(Match::new(start, threads.position())));
+            }
+            None => self.prefix = None,
+            _ => (),
+        }
+
+        events
+    }
+
+    /// Mark the threads that start before the end of a [`Match`] reported as
+    /// part of it.
+    fn found(&mut self, m: &Match) {
+        self.floor = self.floor.max(m.end);
+
+        if self.prefix.is_some_and(|start| start < m.end) {
+            self.prefix = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+
+    use super::super::online::Matcher;
+    use super::super::symbols::SymbolSet;
+    use super::super::{Match, MatchKind};
+    use super::{Event, Events};
+
+    #[test]
+    fn events() {
+        let ast = Compiler::new().compile("[[:a:]]{2}[[:b:]]*").unwrap();
+
+        // The symbols of `a` and `b`, respectively, or neither.
+        let symbols = [0, 0, 1, 1, 2, 0, 2, 0, 0]
+            .into_iter()
+            .map(|i| {
+                let mut symbols = SymbolSet::new();
+
+                if let Some(symbol) = ['a', 'b'].get(i) {
+                    symbols.insert(*symbol);
+                }
+
+                symbols
+            })
+            .collect::<Vec<_>>();
+
+        let matcher = Matcher::from(&ast).kind(MatchKind::LeftmostLongest);
+
+        let mut threads = matcher.threads();
+        let mut events = Events::new();
+        let mut reported = Vec::new();
+
+        for symbols in symbols.iter() {
+            let mats = matcher.advance(&mut threads, symbols).unwrap();
+            reported.extend(events.update(&threads, mats));
+        }
+
+        let mats = threads.finish();
+        reported.extend(events.update(&threads, mats));
+
+        assert_eq!(
+            vec![
+                Event::PrefixStarted(Match::new(0, 1)),
+                Event::MatchExtended(Match::new(0, 2)),
+                Event::MatchExtended(Match::new(0, 3)),
+                Event::MatchExtended(Match::new(0, 4)),
+                Event::MatchClosed(Match::new(0, 4)),
+                Event::PrefixStarted(Match::new(5, 6)),
+                Event::PrefixStarted(Match::new(7, 8)),
+                Event::MatchExtended(Match::new(7, 9)),
+                Event::MatchClosed(Match::new(7, 9)),
+            ],
+            reported
+        );
+    }
+}
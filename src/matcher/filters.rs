@@ -0,0 +1,151 @@
+//! The temporal filters of spatial formulas.
+//!
+//! A filter (e.g., `<kofn 4 5>`) smooths the truth of a spatial formula across
+//! the frames of a stream. Therefore, it is applied as the symbols of each frame
+//! are evaluated (see [`Evaluator`]), in the order of the frames, and before any
+//! automaton steps over them.
+//!
+//! [`Evaluator`]: super::symbols::Evaluator
+
+use std::collections::VecDeque;
+
+use crate::compiler::ir::ast::SpatialFormula;
+use crate::compiler::ir::{FilterOperatorKind, Node, Operator, TimeUnitKind};
+
+/// The state of a filter over the frames evaluated so far.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    kind: FilterOperatorKind,
+
+    /// The timestamp and truth of the formula over the last frames (up to the
+    /// window).
+    history: VecDeque<(f64, bool)>,
+
+    /// The number of frames of the `history` where the formula holds.
+    count: usize,
+
+    /// The timestamp of the first frame of the stream.
+    start: Option<f64>,
+}
+
+impl Filter {
+    /// Create a new [`Filter`].
+    pub fn new(kind: FilterOperatorKind) -> Self {
+        Filter {
+            kind,
+            history: VecDeque::new(),
+            count: 0,
+            start: None,
+        }
+    }
+
+    /// Split a spatial formula into the kind of its filter (if any) and the
+    /// formula it filters, which is evaluated against each frame.
+    pub fn split(formula: &SpatialFormula) -> (Option<&FilterOperatorKind>, &SpatialFormula) {
+        match formula {
+            Node::UnaryExpr {
+                op: Operator::FilterOperator(kind),
+                child,
+            } => (Some(kind), child),
+            _ => (None, formula),
+        }
+    }
+
+    /// Update the [`Filter`] with the truth of its formula at the next frame,
+    /// along with the timestamp of that frame.
+    ///
+    /// This returns whether the filtered formula holds at that frame. A
+    /// frequency is not known until a full window of frames is seen (i.e., at
+    /// the start of a stream); so, it does not hold before then.
+    pub fn update(&mut self, holds: bool, timestamp: f64) -> bool {
+        let start = *self.start.get_or_insert(timestamp);
+
+        self.history.push_back((timestamp, holds));
+        self.count += usize::from(holds);
+
+        // Drop the frames that no longer fall within the window.
+        while let Some((first, held)) = self.history.front().copied() {
+            let expired = match &self.kind {
+                FilterOperatorKind::KOfN(_, n) => self.history.len() > *n,
+                FilterOperatorKind::Hold(n) => self.history.len() > n.saturating_add(1),
+                FilterOperatorKind::Frequency(_, window, TimeUnitKind::Frames) => {
+                    self.history.len() as f64 > *window
+                }
+                FilterOperatorKind::Frequency(_, window, TimeUnitKind::Seconds) => {
+                    timestamp - first > *window
+                }
+            };
+
+            if !expired {
+                break;
+            }
+
+            self.history.pop_front();
+            self.count -= usize::from(held);
+        }
+
+        match self.kind {
+            FilterOperatorKind::KOfN(k, _) => self.count >= k,
+            FilterOperatorKind::Hold(_) => self.count >= 1,
+            FilterOperatorKind::Frequency(percentage, window, ref unit) => {
+                let full = match unit {
+                    TimeUnitKind::Frames => self.history.len() as f64 >= window,
+                    TimeUnitKind::Seconds => timestamp - start >= window,
+                };
+
+                full && 100.0 * self.count as f64 >= percentage * self.history.len() as f64
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ir::{FilterOperatorKind, TimeUnitKind};
+
+    use super::Filter;
+
+    #[test]
+    fn filter() {
+        let truths = [true, true, false, true, false, false, false, true];
+
+        // A frame is dropped after the fourth frame.
+        let timestamps = [0.0, 1.0, 2.0, 3.0, 5.0, 6.0, 7.0, 8.0];
+
+        let run = |kind| {
+            let mut filter = Filter::new(kind);
+
+            truths
+                .iter()
+                .zip(timestamps)
+                .map(|(holds, timestamp)| filter.update(*holds, timestamp))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![false, true, true, true, false, false, false, false],
+            run(FilterOperatorKind::KOfN(2, 3))
+        );
+        assert_eq!(
+            vec![true, true, true, true, true, true, false, true],
+            run(FilterOperatorKind::Hold(2))
+        );
+        // A frequency does not hold until its window is full.
+        assert_eq!(
+            vec![false, false, true, true, false, false, false, false],
+            run(FilterOperatorKind::Frequency(
+                60.0,
+                3.0,
+                TimeUnitKind::Frames
+            ))
+        );
+        assert_eq!(
+            vec![false, false, true, true, true, false, false, false],
+            run(FilterOperatorKind::Frequency(
+                50.0,
+                2.0,
+                TimeUnitKind::Seconds
+            ))
+        );
+    }
+}
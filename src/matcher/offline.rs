@@ -1,23 +1,47 @@
+use std::collections::VecDeque;
 use std::error::Error;
 
 use crate::datastream::frame::Frame;
+use crate::monitor::Monitor;
 use crate::symbolizer::ast::SymbolicAbstractSyntaxTree;
 
 use super::super::matcher::Matching;
-use super::automata::dfa::forward::DeterministicFiniteAutomata;
-use super::automata::dfa::{forward, DeterministicFiniteAutomaton};
-use super::Match;
+use super::automata::dfa::forward::{DeterministicFiniteAutomata, FailureMemo};
+use super::automata::dfa::{forward, reverse, DeterministicFiniteAutomaton};
+use super::automata::Options;
+use super::record::Record;
+use super::symbols::{Evaluator, SymbolSet};
+use super::threads::Threads;
+use super::{Match, MatchKind};
 
 /// An interface for [`Matching`] offline.
 ///
 /// This [`Matcher`] uses a forward-based algorithm to perform matching over the
-/// provided input.
+/// provided input. To find all matches, an unanchored reverse DFA is also used
+/// to locate where matches may start (see [`Matcher::find_iter`]).
+///
+/// By default, the leftmost-longest non-overlapping matches are reported (i.e.,
+/// [`MatchKind::LeftmostLongest`]).
 pub struct Matcher<'a> {
-    pub dfa: DeterministicFiniteAutomata<'a>,
+    pub dfa: DeterministicFiniteAutomata,
+    pub rdfa: reverse::DeterministicFiniteAutomata,
+
+    pub evaluator: Evaluator<'a>,
+    pub kind: MatchKind,
 }
 
 impl Matching for Matcher<'_> {
-    /// Find the leftmost match from the sequence of [`Frame`].
+    /// The [`MatchKind`] reported by [`Matching::find`].
+    fn kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    /// Evaluate the [`SymbolSet`] satisfied by a [`Frame`].
+    fn evaluate(&self, frame: &Frame) -> SymbolSet {
+        self.evaluator.evaluate(frame)
+    }
+
+    /// Find the leftmost match from the symbols of a sequence of [`Frame`].
     ///
     /// This algorithm utilizes an anchored forward DFA. Therefore, the `end`
     /// index needs to be found, accordingly.
@@ -35,12 +59,12 @@ impl Matching for Matcher<'_> {
     ///
     /// As such, the [`Match`] acts as the index relative to the length of the
     /// slice of [`Frame`] provided.
-    fn leftmost(&self, frames: &[Frame]) -> Result<Option<Match>, Box<dyn Error>> {
+    fn leftmost(&self, symbols: &[SymbolSet]) -> Result<Option<Match>, Box<dyn Error>> {
         let start: usize = 0;
 
         let end = self
             .dfa
-            .run(frames)?
+            .run(symbols)?
             .into_iter()
             .filter(|m| start != start + m.offset())
             .map(|m| start + m.offset())
@@ -52,23 +76,192 @@ impl Matching for Matcher<'_> {
 
         Ok(None)
     }
+
+    /// Find every [`Match`] of the [`MatchKind`] from the symbols of a set of
+    /// [`Frame`].
+    fn find(&self, symbols: &[SymbolSet]) -> Result<Vec<Match>, Box<dyn Error>> {
+        self.find_iter(symbols)?.collect()
+    }
+
+    /// Resolve the [`Frame`](s) of a [`Match`] (and their symbols) into a
+    /// [`Record`].
+    fn record(&self, frames: &[Frame], symbols: &[SymbolSet]) -> Result<Record, Box<dyn Error>> {
+        Record::new(&self.dfa, &self.evaluator, frames, symbols)
+    }
+}
+
+impl Matcher<'_> {
+    /// Find all matches of the [`MatchKind`].
+    ///
+    /// For [`MatchKind::LeftmostLongest`], this is the equivalent of calling
+    /// [`Matching::leftmost`] from every offset of the haystack, and skipping
+    /// past each match found. However, it does so in a number of DFA steps
+    /// linear in the length of the haystack.
+    ///
+    /// First, a single unanchored pass of the reverse DFA finds every position
+    /// where a match may start. Then, an anchored forward search is run from
+    /// each such position (in order) to find the longest match. This mirrors
+    /// the search of `regex-automata` (i.e., an unanchored forward pass to find
+    /// the end followed by an anchored reverse pass to find the start); here,
+    /// the directions are flipped as it is the start that must be leftmost and
+    /// the end that must be longest. Forward searches share a [`FailureMemo`],
+    /// so that no configuration is explored more than once.
+    ///
+    /// For all other kinds, a set of [`Threads`] is advanced over the haystack
+    /// instead, where a new thread is only started where a match may start.
+    pub fn find_iter<'h>(
+        &self,
+        symbols: &'h [SymbolSet],
+    ) -> Result<Matches<'_, 'h>, Box<dyn Error>> {
+        let mut starts = vec![false; symbols.len() + 1];
+
+        for m in self.rdfa.run(symbols)? {
+            starts[m.offset()] = true;
+        }
+
+        let threads = match self.kind {
+            MatchKind::LeftmostLongest => None,
+            kind => Some(Threads::new(kind)),
+        };
+
+        Ok(Matches {
+            dfa: &self.dfa,
+            haystack: symbols,
+            starts,
+            memo: FailureMemo::new(symbols.len()),
+            offset: 0,
+            threads,
+            queue: VecDeque::new(),
+        })
+    }
+
+    /// Set the [`Monitor`] used to evaluate spatial formulas.
+    pub fn monitor(mut self, monitor: Monitor) -> Self {
+        self.evaluator.monitor = monitor;
+        self
+    }
+
+    /// Set the [`MatchKind`] to report.
+    pub fn kind(mut self, kind: MatchKind) -> Self {
+        self.kind = kind;
+        self
+    }
+}
+
+impl<'a> Matcher<'a> {
+    /// Create a new [`Matcher`] with the [`Options`] provided.
+    ///
+    /// Here we use the forward factory to construct a DFA from the s-AST
+    /// provided that is first converted into an RE. The forward DFA recognizes
+    /// all matches, so that the longest match is found regardless of the
+    /// priority of alternations in the pattern.
+    pub fn new(
+        ast: &'a SymbolicAbstractSyntaxTree,
+        options: &Options,
+    ) -> Result<Self, Box<dyn Error>> {
+        let dfa = forward::build_all(ast, options)?;
+        let rdfa = reverse::build_unanchored(ast, options)?;
+        let evaluator = Evaluator::from(ast);
+
+        Ok(Matcher {
+            dfa,
+            rdfa,
+            evaluator,
+            kind: MatchKind::LeftmostLongest,
+        })
+    }
 }
 
 impl<'a> From<&'a SymbolicAbstractSyntaxTree> for Matcher<'a> {
     fn from(ast: &'a SymbolicAbstractSyntaxTree) -> Self {
         // Construct the DFA.
         //
-        // Here we use the forward factory to construct a DFA from the s-AST
-        // provided that is first converted into an RE.
-        //
         // # Panics
         //
         // Here, we assume that all previous methods have passed where we can
         // safely assume that constructing a valid DFA is guaranteed. This may
         // need further handled in the future for patterns that may break the
         // underlying library used.
-        let dfa = forward::build(ast).unwrap();
+        Matcher::new(ast, &Options::default()).unwrap()
+    }
+}
+
+/// An iterator over all matches of a [`MatchKind`].
+///
+/// This is created by [`Matcher::find_iter`].
+pub struct Matches<'m, 'h> {
+    dfa: &'m DeterministicFiniteAutomata,
+    haystack: &'h [SymbolSet],
+
+    /// Whether a match may start at each position of the haystack.
+    starts: Vec<bool>,
+
+    memo: FailureMemo,
+    offset: usize,
+
+    /// The [`Threads`] of the search, unless leftmost-longest.
+    threads: Option<Threads>,
+
+    /// The matches found but not yet returned.
+    queue: VecDeque<Match>,
+}
+
+impl Matches<'_, '_> {
+    /// Find the next leftmost-longest match.
+    fn longest(&mut self) -> Option<Result<Match, Box<dyn Error>>> {
+        while self.offset < self.haystack.len() {
+            let start = self.offset;
+
+            if !self.starts[start] {
+                self.offset += 1;
+                continue;
+            }
 
-        Matcher { dfa }
+            match self.dfa.longest(self.haystack, start, &mut self.memo) {
+                Ok(Some(end)) => {
+                    self.offset = end;
+                    return Some(Ok(Match::new(start, end)));
+                }
+                Ok(None) => self.offset += 1,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        None
+    }
+}
+
+impl Iterator for Matches<'_, '_> {
+    type Item = Result<Match, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(threads) = &mut self.threads else {
+            return self.longest();
+        };
+
+        loop {
+            if let Some(m) = self.queue.pop_front() {
+                return Some(Ok(m));
+            }
+
+            if self.offset > self.haystack.len() {
+                return None;
+            }
+
+            if self.offset == self.haystack.len() {
+                self.queue.extend(threads.finish());
+                self.offset += 1;
+                continue;
+            }
+
+            let at = self.offset;
+
+            match threads.advance(self.dfa, &self.haystack[at], self.starts[at]) {
+                Ok(mats) => self.queue.extend(mats),
+                Err(e) => return Some(Err(e)),
+            }
+
+            self.offset += 1;
+        }
     }
 }
@@ -1,26 +1,48 @@
 use std::error::Error;
 
 use crate::datastream::frame::Frame;
+use crate::monitor::Monitor;
 use crate::symbolizer::ast::SymbolicAbstractSyntaxTree;
 
 use super::super::matcher::Matching;
-use super::automata::dfa::reverse::DeterministicFiniteAutomata;
-use super::automata::dfa::{reverse, DeterministicFiniteAutomaton};
-use super::Match;
+use super::automata::dfa::forward;
+use super::automata::dfa::forward::DeterministicFiniteAutomata;
+use super::automata::Options;
+use super::record::Record;
+use super::symbols::{Evaluator, SymbolSet};
+use super::threads::Threads;
+use super::{Match, MatchKind};
 
 /// An interface for [`Matching`] online.
 ///
-/// This [`Matcher`] uses a reverse-based algorithm to perform matching over the
-/// provided input.
+/// This [`Matcher`] advances a set of [`Threads`] through a forward DFA by a
+/// single [`Frame`] at a time. Therefore, the work done per [`Frame`] is
+/// bounded by the size of the DFA and not by the length of the stream.
+///
+/// By default, every end that matches is reported with its leftmost start
+/// (i.e., [`MatchKind::AllEnds`]).
 pub struct Matcher<'a> {
-    pub dfa: DeterministicFiniteAutomata<'a>,
+    pub dfa: DeterministicFiniteAutomata,
+    pub evaluator: Evaluator<'a>,
+    pub kind: MatchKind,
 }
 
 impl<'a> Matching for Matcher<'a> {
-    /// Find the leftmost match from the sequence of [`Frame`].
+    /// The [`MatchKind`] reported by [`Matching::find`].
+    fn kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    /// Evaluate the [`SymbolSet`] satisfied by a [`Frame`].
+    fn evaluate(&self, frame: &Frame) -> SymbolSet {
+        self.evaluator.evaluate(frame)
+    }
+
+    /// Find the leftmost match from the symbols of a sequence of [`Frame`].
     ///
-    /// This algorithm utilizes an anchored reverse DFA. Therefore, the `start`
-    /// index needs to be found, accordingly.
+    /// The [`Match`] found must end at the last [`Frame`] provided, and it is
+    /// the longest such [`Match`]. This is equivalent to advancing a new set
+    /// of [`Threads`] over each [`Frame`] and keeping the last result.
     ///
     /// The indices of the [`Match`] returned correspond to the indices relative
     /// to the sequences of [`Frame`] provided. Therefore, it is not necessarily
@@ -35,22 +57,92 @@ impl<'a> Matching for Matcher<'a> {
     ///
     /// As such, the [`Match`] acts as the index relative to the length of the
     /// slice of [`Frame`] provided.
-    fn leftmost(&self, frames: &[Frame]) -> Result<Option<Match>, Box<dyn Error>> {
-        let end: usize = frames.len();
+    fn leftmost(&self, symbols: &[SymbolSet]) -> Result<Option<Match>, Box<dyn Error>> {
+        let mut threads = Threads::new(MatchKind::AllEnds);
+        let mut m = None;
 
-        let start = self
-            .dfa
-            .run(frames)?
-            .into_iter()
-            .filter(|m| end != m.offset())
-            .map(|m| m.offset())
-            .min();
+        for symbols in symbols.iter() {
+            m = threads.advance(&self.dfa, symbols, true)?.pop();
+        }
+
+        Ok(m)
+    }
+
+    /// Find every [`Match`] of the [`MatchKind`] from the symbols of a set of
+    /// [`Frame`].
+    ///
+    /// This is equivalent to advancing the [`Threads`] over each [`Frame`] and
+    /// finishing at the end.
+    fn find(&self, symbols: &[SymbolSet]) -> Result<Vec<Match>, Box<dyn Error>> {
+        let mut threads = self.threads();
+        let mut mats = Vec::new();
 
-        if let Some(start) = start {
-            return Ok(Some(Match::new(start, end)));
+        for symbols in symbols.iter() {
+            mats.extend(self.advance(&mut threads, symbols)?);
         }
 
-        Ok(None)
+        mats.extend(threads.finish());
+
+        Ok(mats)
+    }
+
+    /// Resolve the [`Frame`](s) of a [`Match`] (and their symbols) into a
+    /// [`Record`].
+    fn record(&self, frames: &[Frame], symbols: &[SymbolSet]) -> Result<Record, Box<dyn Error>> {
+        Record::new(&self.dfa, &self.evaluator, frames, symbols)
+    }
+}
+
+impl Matcher<'_> {
+    /// Set the [`Monitor`] used to evaluate spatial formulas.
+    pub fn monitor(mut self, monitor: Monitor) -> Self {
+        self.evaluator.monitor = monitor;
+        self
+    }
+
+    /// Set the [`MatchKind`] to report.
+    pub fn kind(mut self, kind: MatchKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Create a new set of [`Threads`] to search with.
+    pub fn threads(&self) -> Threads {
+        Threads::new(self.kind)
+    }
+
+    /// Advance the [`Threads`] by the symbols of the next [`Frame`].
+    ///
+    /// A new thread is started at the [`Frame`] before every thread takes its
+    /// next transition. The matches that are known to be final at this
+    /// [`Frame`] are returned (see [`Threads::advance`]), and the remaining
+    /// ones are returned once the [`Threads`] are finished.
+    pub fn advance(
+        &self,
+        threads: &mut Threads,
+        symbols: &SymbolSet,
+    ) -> Result<Vec<Match>, Box<dyn Error>> {
+        threads.advance(&self.dfa, symbols, true)
+    }
+}
+
+impl<'a> Matcher<'a> {
+    /// Create a new [`Matcher`] with the [`Options`] provided.
+    ///
+    /// Here we use the forward factory to construct a DFA from the s-AST
+    /// provided that is first converted into an RE.
+    pub fn new(
+        ast: &'a SymbolicAbstractSyntaxTree,
+        options: &Options,
+    ) -> Result<Self, Box<dyn Error>> {
+        let dfa = forward::build_all(ast, options)?;
+        let evaluator = Evaluator::from(ast);
+
+        Ok(Matcher {
+            dfa,
+            evaluator,
+            kind: MatchKind::AllEnds,
+        })
     }
 }
 
@@ -58,17 +150,173 @@ impl<'a> From<&'a SymbolicAbstractSyntaxTree> for Matcher<'a> {
     fn from(ast: &'a SymbolicAbstractSyntaxTree) -> Self {
         // Construct the DFA.
         //
-        // Here we use the forward factory to construct a DFA from the s-AST
-        // provided that is first converted into an RE.
-        //
         // # Panics
         //
         // Here, we assume that all previous methods have passed where we can
         // safely assume that constructing a valid DFA is guaranteed. This may
         // need further handled in the future for patterns that may break the
         // underlying library used.
-        let dfa = reverse::build(ast).unwrap();
+        Matcher::new(ast, &Options::default()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+
+    use super::super::automata::{Backend, Options};
+    use super::super::symbols::SymbolSet;
+    use super::super::{MatchKind, Matching};
+    use super::Matcher;
+
+    #[test]
+    fn find() {
+        let ast = Compiler::new().compile("[[:a:]]*[[:b:]]{1,}").unwrap();
+
+        // The symbols of `a` and `b`, respectively, or neither.
+        let symbols = [0, 0, 1, 1, 2, 0, 1]
+            .into_iter()
+            .map(|i| {
+                let mut symbols = SymbolSet::new();
+
+                if let Some(symbol) = ['a', 'b'].get(i) {
+                    symbols.insert(*symbol);
+                }
+
+                symbols
+            })
+            .collect::<Vec<_>>();
+
+        let cases = [
+            (MatchKind::LeftmostLongest, vec![(0, 4), (5, 7)]),
+            (MatchKind::LeftmostShortest, vec![(0, 3), (3, 4), (5, 7)]),
+            (MatchKind::AllEnds, vec![(0, 3), (0, 4), (5, 7)]),
+            (
+                MatchKind::AllOverlapping,
+                vec![
+                    (0, 3),
+                    (1, 3),
+                    (2, 3),
+                    (0, 4),
+                    (1, 4),
+                    (2, 4),
+                    (3, 4),
+                    (5, 7),
+                    (6, 7),
+                ],
+            ),
+        ];
+
+        for (kind, expected) in cases {
+            for backend in [Backend::Dense, Backend::Lazy, Backend::Counting] {
+                let options = Options::new().backend(backend);
+                let matcher = Matcher::new(&ast, &options).unwrap().kind(kind);
+
+                let mats = matcher
+                    .find(&symbols)
+                    .unwrap()
+                    .into_iter()
+                    .map(|m| (m.start, m.end))
+                    .collect::<Vec<_>>();
+
+                assert_eq!(expected, mats, "{:?} ({:?})", kind, backend);
+            }
+        }
+
+        // A match may span at most three frames.
+        let matcher = Matcher::from(&ast);
+        let mut threads = matcher.threads().window(3);
+        let mut mats = Vec::new();
+
+        for symbols in symbols.iter() {
+            for m in matcher.advance(&mut threads, symbols).unwrap() {
+                mats.push((m.start, m.end));
+            }
+        }
+
+        assert_eq!(vec![(0, 3), (1, 4), (5, 7)], mats);
+        assert_eq!(5, threads.earliest());
+    }
+
+    /// The symbols of each frame of a stream, where each frame is either `a`,
+    /// `b`, or neither (i.e., `_`).
+    fn stream(frames: &str) -> Vec<SymbolSet> {
+        frames
+            .chars()
+            .map(|c| {
+                let mut symbols = SymbolSet::new();
+
+                if c != '_' {
+                    symbols.insert(c);
+                }
+
+                symbols
+            })
+            .collect()
+    }
+
+    #[test]
+    fn window() {
+        let ast = Compiler::new().compile("[[:a:]]*[[:b:]]{1,}").unwrap();
+        let symbols = stream("aabb_ab");
+
+        // A match may span at most three frames.
+        let cases = [
+            (MatchKind::LeftmostLongest, vec![(0, 3), (3, 4), (5, 7)]),
+            (MatchKind::LeftmostShortest, vec![(0, 3), (3, 4), (5, 7)]),
+            (MatchKind::AllEnds, vec![(0, 3), (1, 4), (5, 7)]),
+            (
+                MatchKind::AllOverlapping,
+                vec![
+                    (0, 3),
+                    (1, 3),
+                    (2, 3),
+                    (1, 4),
+                    (2, 4),
+                    (3, 4),
+                    (5, 7),
+                    (6, 7),
+                ],
+            ),
+        ];
+
+        for (kind, expected) in cases {
+            let matcher = Matcher::from(&ast).kind(kind);
+            let mut threads = matcher.threads().window(3);
+            let mut mats = Vec::new();
+
+            for symbols in symbols.iter() {
+                mats.extend(matcher.advance(&mut threads, symbols).unwrap());
+            }
+
+            mats.extend(threads.finish());
+
+            let mats = mats
+                .into_iter()
+                .map(|m| (m.start, m.end))
+                .collect::<Vec<_>>();
+
+            assert_eq!(expected, mats, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn leftmost() {
+        let ast = Compiler::new().compile("[[:a:]]*[[:b:]]{1,}").unwrap();
+        let matcher = Matcher::from(&ast);
+
+        let leftmost = |frames: &str| {
+            matcher
+                .leftmost(&stream(frames))
+                .unwrap()
+                .map(|m| (m.start, m.end))
+        };
 
-        Matcher { dfa }
+        // The longest match ending at the last frame, however long it is.
+        assert_eq!(Some((0, 3)), leftmost("aab"));
+        assert_eq!(Some((0, 6)), leftmost("aaaabb"));
+        assert_eq!(Some((1, 3)), leftmost("_ab"));
+        assert_eq!(None, leftmost("aabb_"));
+        assert_eq!(None, leftmost("aa"));
     }
 }
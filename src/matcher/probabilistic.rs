@@ -0,0 +1,496 @@
+//! Probabilistic matching of SpREs.
+//!
+//! A [`Frame`] does not necessarily satisfy a spatial formula with certainty,
+//! as the annotations it is evaluated against are only detected with some
+//! confidence (i.e., their scores). Instead of a single [`SymbolSet`], each
+//! [`Frame`] is therefore evaluated into its [`Likelihoods`], which is a
+//! distribution over the sets of symbols that it may satisfy.
+//!
+//! The probability that a sequence of [`Frame`](s) matches is then computed
+//! by simulating the DFA over these distributions (i.e., similar to the
+//! forward algorithm of a hidden Markov model), where the frames are assumed to
+//! be independent.
+//!
+//! The formulas of a [`Frame`] are assumed to be independent, as well, even if
+//! they are evaluated against the same annotations. So, they are not
+//! correlated (e.g., `[[:car:]] | [!([:car:])]` is not matched with a
+//! probability of 1 by a car detected with a score of 0.5, but of 0.75).
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use regex_automata::util::primitives::StateID;
+
+use crate::datastream::frame::Frame;
+use crate::monitor::Monitor;
+use crate::symbolizer::ast::SymbolicAbstractSyntaxTree;
+
+use super::automata::dfa::forward;
+use super::automata::dfa::forward::DeterministicFiniteAutomata;
+use super::automata::Options;
+use super::filters::Filter;
+use super::record::Record;
+use super::symbols::{self, Evaluator, SymbolSet};
+use super::{Match, MatchKind, MatcherError};
+
+/// The maximum number of formulas that a single [`Frame`] may satisfy with
+/// uncertainty.
+///
+/// The number of outcomes of a [`Frame`] is exponential in the number of such
+/// formulas. Typically, only a few formulas of a pattern are uncertain for any
+/// one [`Frame`] (e.g., most classes are not detected at all). Beyond this,
+/// the least uncertain formulas are taken as certain (see
+/// [`Matcher::evaluate`]).
+pub const LIMIT: usize = 16;
+
+/// The probability of each symbol satisfied by a single [`Frame`].
+#[derive(Clone, Debug, Default)]
+pub struct Likelihoods {
+    /// The symbols satisfied regardless of the outcome.
+    certain: SymbolSet,
+
+    /// The probability of each uncertain formula along with the symbols
+    /// satisfied if it holds, and if it does not, respectively.
+    uncertain: Vec<(f64, SymbolSet, SymbolSet)>,
+}
+
+impl Likelihoods {
+    /// Iterate over every outcome (i.e., the set of symbols satisfied) and its
+    /// probability.
+    ///
+    /// The formulas are assumed to be independent. So, the probability of an
+    /// outcome is the product of the probabilities of each formula holding (or
+    /// not) in that outcome.
+    pub fn outcomes(&self) -> impl Iterator<Item = (SymbolSet, f64)> + '_ {
+        (0..1usize << self.uncertain.len()).map(move |outcome| {
+            let mut symbols = self.certain;
+            let mut probability = 1.0;
+
+            for (i, (p, holds, fails)) in self.uncertain.iter().enumerate() {
+                match outcome & (1 << i) != 0 {
+                    true => {
+                        symbols = symbols.union(holds);
+                        probability *= p;
+                    }
+                    false => {
+                        symbols = symbols.union(fails);
+                        probability *= 1.0 - p;
+                    }
+                }
+            }
+
+            (symbols, probability)
+        })
+    }
+}
+
+/// An interface for matching probabilistically.
+///
+/// This [`Matcher`] reports the sequences of [`Frame`](s) that match with at
+/// least a probability of the `threshold`. Of these, the matches reported are
+/// determined by the [`MatchKind`], as with any other matcher. So, with a
+/// certain evaluation of every formula, the same matches are reported.
+///
+/// By default, the leftmost-longest non-overlapping matches are reported (i.e.,
+/// [`MatchKind::LeftmostLongest`]).
+pub struct Matcher<'a> {
+    pub dfa: DeterministicFiniteAutomata,
+    pub evaluator: Evaluator<'a>,
+    pub kind: MatchKind,
+
+    /// The minimum probability of a match.
+    pub threshold: f64,
+}
+
+impl<'a> Matcher<'a> {
+    /// Create a new [`Matcher`] with the [`Options`] provided.
+    ///
+    /// The forward DFA recognizes all matches, so that the probability of a
+    /// match may be found at every position from a single simulation.
+    pub fn new(
+        ast: &'a SymbolicAbstractSyntaxTree,
+        options: &Options,
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(Matcher {
+            dfa: forward::build_all(ast, options)?,
+            evaluator: Evaluator::from(ast),
+            kind: MatchKind::LeftmostLongest,
+            threshold: 0.5,
+        })
+    }
+}
+
+impl Matcher<'_> {
+    /// Set the [`Monitor`] used to evaluate spatial formulas.
+    pub fn monitor(mut self, monitor: Monitor) -> Self {
+        self.evaluator.monitor = monitor;
+        self
+    }
+
+    /// Set the [`MatchKind`] to report.
+    pub fn kind(mut self, kind: MatchKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the minimum probability of a match.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Evaluate the [`Likelihoods`] of a [`Frame`].
+    ///
+    /// The probability of each formula is evaluated by the [`Monitor`] (see
+    /// [`Monitor::probability`]). However, a formula smoothed by a [`Filter`]
+    /// is evaluated as before, such that it either holds or not. So, as with
+    /// [`Evaluator::evaluate`], the [`Frame`](s) of a stream must be evaluated
+    /// in order, and only once each.
+    ///
+    /// If more than [`LIMIT`] formulas are uncertain, then the probability of
+    /// each outcome is approximated: only the [`LIMIT`] most uncertain (i.e.,
+    /// closest to 0.5) are kept, and each of the others is taken to hold if it
+    /// is more likely than not, with a warning.
+    pub fn evaluate(&self, frame: &Frame) -> Result<Likelihoods, Box<dyn Error>> {
+        let filtered = self.evaluator.evaluate(frame);
+        let mut likelihoods = Likelihoods::default();
+
+        for (symbol, formula) in self.evaluator.formulas.iter() {
+            let mut holds = SymbolSet::new();
+            let mut fails = SymbolSet::new();
+
+            holds.insert(*symbol);
+
+            if self.evaluator.approximate.contains(symbol) {
+                fails.insert(symbols::mismatch(*symbol) as char);
+            }
+
+            let probability = match Filter::split(formula).0 {
+                Some(..) if filtered.contains(*symbol) => 1.0,
+                Some(..) => 0.0,
+                None => self.evaluator.monitor.probability(frame, formula),
+            };
+
+            if probability >= 1.0 {
+                likelihoods.certain = likelihoods.certain.union(&holds);
+            } else if probability <= 0.0 {
+                likelihoods.certain = likelihoods.certain.union(&fails);
+            } else {
+                likelihoods.uncertain.push((probability, holds, fails));
+            }
+        }
+
+        if likelihoods.uncertain.len() > LIMIT {
+            eprintln!(
+                "matcher: warning: frame {} satisfies more than {} formulas with uncertainty; the least uncertain are taken as certain",
+                frame.index, LIMIT
+            );
+
+            likelihoods
+                .uncertain
+                .sort_by(|(a, ..), (b, ..)| (a - 0.5).abs().total_cmp(&(b - 0.5).abs()));
+
+            for (probability, holds, fails) in likelihoods.uncertain.split_off(LIMIT) {
+                let symbols = match probability >= 0.5 {
+                    true => holds,
+                    false => fails,
+                };
+
+                likelihoods.certain = likelihoods.certain.union(&symbols);
+            }
+        }
+
+        Ok(likelihoods)
+    }
+
+    /// Find every [`Match`] of the [`MatchKind`] (and its probability) from
+    /// the [`Likelihoods`] of a set of [`Frame`].
+    ///
+    /// The probability of every match from each start is found by a single
+    /// forward simulation. As a configuration of the DFA that is dead remains
+    /// so, the simulation stops once the probability of the live
+    /// configurations falls below the `threshold`.
+    pub fn find(&self, likelihoods: &[Likelihoods]) -> Result<Vec<(Match, f64)>, Box<dyn Error>> {
+        let mut mats = Vec::new();
+        let mut start = 0;
+
+        while start < likelihoods.len() {
+            let ends = self.ends(likelihoods, start)?;
+
+            let next = match self.kind {
+                MatchKind::LeftmostLongest => ends.last(),
+                MatchKind::LeftmostShortest => ends.first(),
+                MatchKind::AllOverlapping | MatchKind::AllEnds => {
+                    mats.extend(ends.iter().map(|(end, p)| (Match::new(start, *end), *p)));
+                    start += 1;
+                    continue;
+                }
+            };
+
+            match next {
+                Some((end, p)) => {
+                    mats.push((Match::new(start, *end), *p));
+                    start = *end;
+                }
+                None => start += 1,
+            }
+        }
+
+        // As with [`super::threads::Threads`], these are reported in order of
+        // their ends, and only the leftmost start of each end is kept for
+        // [`MatchKind::AllEnds`].
+        if let MatchKind::AllOverlapping | MatchKind::AllEnds = self.kind {
+            mats.sort_by_key(|(m, _)| (m.end, m.start));
+
+            if self.kind == MatchKind::AllEnds {
+                mats.dedup_by_key(|(m, _)| m.end);
+            }
+        }
+
+        Ok(mats)
+    }
+
+    /// Find the end of every match from a start whose probability is at least
+    /// the `threshold`, in order.
+    fn ends(
+        &self,
+        likelihoods: &[Likelihoods],
+        start: usize,
+    ) -> Result<Vec<(usize, f64)>, Box<dyn Error>> {
+        let mut configurations = HashMap::from([(vec![*self.dfa.initial()?.id()], 1.0)]);
+        let mut ends = Vec::new();
+
+        for (at, likelihoods) in likelihoods.iter().enumerate().skip(start) {
+            configurations = self.forward(&configurations, likelihoods);
+
+            let live = configurations.values().sum::<f64>();
+
+            if live < self.threshold {
+                break;
+            }
+
+            let p = configurations
+                .iter()
+                .filter(|(states, _)| states.iter().any(|sid| self.dfa.is_final(*sid)))
+                .map(|(_, p)| p)
+                .sum::<f64>();
+
+            if p >= self.threshold {
+                ends.push((at + 1, p.min(1.0)));
+            }
+        }
+
+        Ok(ends)
+    }
+
+    /// Take the next transitions from a distribution of configurations (i.e.,
+    /// sets of live states) over the outcomes of a [`Frame`].
+    ///
+    /// A configuration without any live states is dropped.
+    fn forward(
+        &self,
+        configurations: &HashMap<Vec<StateID>, f64>,
+        likelihoods: &Likelihoods,
+    ) -> HashMap<Vec<StateID>, f64> {
+        let mut nexts = HashMap::new();
+
+        for (states, q) in configurations.iter() {
+            for (symbols, p) in likelihoods.outcomes() {
+                let next = self.dfa.step(states, &symbols);
+
+                if !next.is_empty() {
+                    *nexts.entry(next).or_insert(0.0) += q * p;
+                }
+            }
+        }
+
+        nexts
+    }
+
+    /// Resolve the [`Frame`](s) of a [`Match`] (and their [`Likelihoods`]) into
+    /// a [`Record`] of its probability.
+    ///
+    /// A match may be matched by several outcomes of its [`Frame`](s). So, the
+    /// [`Record`] is resolved against the most probable outcome that matches
+    /// (i.e., similar to the Viterbi algorithm).
+    pub fn record(
+        &self,
+        frames: &[Frame],
+        likelihoods: &[Likelihoods],
+        probability: f64,
+    ) -> Result<Record, Box<dyn Error>> {
+        let mut layers = Vec::new();
+        let mut configurations = HashMap::from([(vec![*self.dfa.initial()?.id()], 1.0)]);
+
+        for likelihoods in likelihoods.iter() {
+            let mut nexts: HashMap<Vec<StateID>, f64> = HashMap::new();
+            let mut layer = HashMap::new();
+
+            for (states, q) in configurations.iter() {
+                for (symbols, p) in likelihoods.outcomes() {
+                    let next = self.dfa.step(states, &symbols);
+
+                    if next.is_empty() || nexts.get(&next).is_some_and(|best| *best >= q * p) {
+                        continue;
+                    }
+
+                    nexts.insert(next.clone(), q * p);
+                    layer.insert(next, (states.clone(), symbols));
+                }
+            }
+
+            configurations = nexts;
+            layers.push(layer);
+        }
+
+        let mut states = configurations
+            .into_iter()
+            .filter(|(states, _)| states.iter().any(|sid| self.dfa.is_final(*sid)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(states, _)| states)
+            .ok_or_else(|| MatcherError::from("frames do not match"))?;
+
+        let mut outcomes = vec![SymbolSet::new(); layers.len()];
+
+        for (at, layer) in layers.iter().enumerate().rev() {
+            let (prev, symbols) = &layer[&states];
+
+            outcomes[at] = *symbols;
+            states = prev.clone();
+        }
+
+        let mut record = Record::new(&self.dfa, &self.evaluator, frames, &outcomes)?;
+        record.probability = Some(probability);
+
+        Ok(record)
+    }
+}
+
+impl<'a> From<&'a SymbolicAbstractSyntaxTree> for Matcher<'a> {
+    fn from(ast: &'a SymbolicAbstractSyntaxTree) -> Self {
+        Matcher::new(ast, &Options::default()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+    use crate::datastream::frame::sample::detections::{
+        Annotation, BoundingBox, DetectionRecord, Geometry, Point,
+    };
+    use crate::datastream::frame::sample::Sample;
+    use crate::datastream::frame::Frame;
+    use crate::matcher::{Match, MatchKind};
+
+    use super::Matcher;
+
+    fn frame(index: usize, annotations: &[(&str, f64)]) -> Frame {
+        let mut record = DetectionRecord::new(String::from("cam"), index as f64, None);
+
+        for (label, score) in annotations {
+            let bbox = BoundingBox::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+            let annotation =
+                Annotation::new(label.to_string(), *score, Geometry::BoundingBox(bbox));
+
+            record
+                .annotations
+                .entry(label.to_string())
+                .or_default()
+                .push(annotation);
+        }
+
+        let mut frame = Frame::new(index, index as f64);
+        frame.samples.push(Sample::ObjectDetection(record));
+
+        frame
+    }
+
+    #[test]
+    fn probabilistic() {
+        let ast = Compiler::new()
+            .compile("[[:car:]]([[:car:]] | [[:pedestrian:]])")
+            .unwrap();
+        let matcher = Matcher::from(&ast).threshold(0.6);
+
+        let frames = [
+            frame(0, &[("car", 0.9)]),
+            frame(1, &[("car", 0.3), ("pedestrian", 0.6)]),
+            frame(2, &[("car", 0.4)]),
+            frame(3, &[("car", 0.3)]),
+        ];
+
+        let likelihoods = frames
+            .iter()
+            .map(|frame| matcher.evaluate(frame).unwrap())
+            .collect::<Vec<_>>();
+
+        // Either a car or a pedestrian follows with a probability of 0.72.
+        let mats = matcher.find(&likelihoods).unwrap();
+        assert_eq!(1, mats.len());
+        assert_eq!(Match::new(0, 2), mats[0].0);
+        assert!((0.648 - mats[0].1).abs() < 1e-9);
+
+        let matcher = matcher.threshold(0.1).kind(MatchKind::AllOverlapping);
+        let probabilities = matcher
+            .find(&likelihoods)
+            .unwrap()
+            .into_iter()
+            .map(|(m, p)| ((m.start, m.end), (p * 100.0).round() / 100.0))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![((0, 2), 0.65), ((1, 3), 0.12), ((2, 4), 0.12)],
+            probabilities
+        );
+
+        // The pedestrian is more likely than the car.
+        let record = matcher
+            .record(&frames[..2], &likelihoods[..2], 0.648)
+            .unwrap();
+        assert_eq!(Some(0.648), record.probability);
+        assert_eq!(
+            vec!['a', 'c'],
+            record.steps.iter().map(|s| s.symbol).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn limit() {
+        let labels = (0..20).map(|i| format!("c{}", i)).collect::<Vec<_>>();
+
+        let pattern = labels
+            .iter()
+            .map(|label| format!("[[:{}:]]", label))
+            .collect::<Vec<_>>()
+            .join("|");
+
+        let ast = Compiler::new().compile(&pattern).unwrap();
+        let matcher = Matcher::from(&ast);
+
+        // Each formula is uncertain, but the first is the least.
+        let annotations = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_str(), if i == 0 { 0.99 } else { 0.5 }))
+            .collect::<Vec<_>>();
+
+        let likelihoods = matcher.evaluate(&frame(0, &annotations)).unwrap();
+        assert_eq!(super::LIMIT, likelihoods.uncertain.len());
+        assert!(likelihoods.certain.contains('a'));
+
+        let mats = matcher.find(&[likelihoods]).unwrap();
+        assert_eq!(vec![(Match::new(0, 1), 1.0)], mats);
+    }
+
+    #[test]
+    fn independent() {
+        let ast = Compiler::new().compile("[[:car:]] | [!([:car:])]").unwrap();
+        let matcher = Matcher::from(&ast);
+
+        let likelihoods = matcher.evaluate(&frame(0, &[("car", 0.5)])).unwrap();
+
+        // The formulas are not correlated, although they are of the same car.
+        let mats = matcher.find(&[likelihoods]).unwrap();
+        assert_eq!(vec![(Match::new(0, 1), 0.75)], mats);
+    }
+}
@@ -0,0 +1,334 @@
+//! The records of matches.
+//!
+//! A [`Match`](super::Match) only holds the positions it spans. A [`Record`]
+//! resolves it against the [`Frame`](s) it spans, so that it can be explained
+//! (e.g., which formula each [`Frame`] satisfied, and by which annotations).
+
+use std::borrow::Borrow;
+use std::error::Error;
+
+use crate::compiler::ir::ast::{Atom, SpatialFormula};
+use crate::datastream::frame::Frame;
+use crate::monitor::s4::Region;
+
+use super::automata::dfa::forward::DeterministicFiniteAutomata;
+use super::symbols::{self, Evaluator, SymbolSet};
+use super::MatcherError;
+
+/// A match resolved against the [`Frame`](s) it spans.
+#[derive(Clone, Debug)]
+pub struct Record {
+    /// The [`Frame::index`] of the first [`Frame`] (inclusive).
+    pub start: usize,
+
+    /// The [`Frame::index`] after the last [`Frame`] (exclusive).
+    pub end: usize,
+
+    /// The timestamp of the first [`Frame`].
+    pub start_time: f64,
+
+    /// The timestamp of the last [`Frame`].
+    pub end_time: f64,
+
+    /// The [`Step`] taken at each [`Frame`].
+    pub steps: Vec<Step>,
+
+    /// The number of [`Frame`](s) that violate their spatial formula (i.e.,
+    /// of a pattern matched approximately).
+    pub errors: usize,
+
+    /// The robustness of the match, which is that of its weakest [`Step`]
+    /// (i.e., the match holds no more robustly than any of its frames).
+    pub score: f64,
+
+    /// The probability of the match, if matched probabilistically (see
+    /// [`probabilistic`](super::probabilistic)).
+    pub probability: Option<f64>,
+}
+
+/// The spatial formula taken at a single [`Frame`] of a match.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub index: usize,
+    pub timestamp: f64,
+
+    /// The symbol of the spatial formula taken.
+    pub symbol: char,
+    pub formula: SpatialFormula,
+
+    /// The regions that witness the formula (e.g., the annotations of a car).
+    pub witnesses: Vec<Region>,
+
+    /// Whether the [`Frame`] violates the formula (i.e., it was matched
+    /// approximately), in which case there are no witnesses.
+    pub mismatch: bool,
+
+    /// Whether the [`Frame`] satisfies the formula only by its filter (e.g.,
+    /// it is held from a previous frame by `<hold n>`), in which case there
+    /// are no witnesses.
+    pub held: bool,
+
+    /// The degree to which the [`Frame`] satisfies the formula (see
+    /// [`Monitor::robustness`](crate::monitor::Monitor::robustness)). As the
+    /// filtered formula holds at a frame that is `held`, its degree is never
+    /// negative.
+    pub robustness: f64,
+}
+
+impl Record {
+    /// Create a new [`Record`] of the [`Frame`](s) of a match.
+    ///
+    /// The symbols satisfied by each [`Frame`] must be provided, as well. A
+    /// [`Frame`] may satisfy several formulas; so, the formulas taken are those
+    /// along a path of the DFA that matches all [`Frame`](s).
+    pub fn new<F: Borrow<Frame>>(
+        dfa: &DeterministicFiniteAutomata,
+        evaluator: &Evaluator,
+        frames: &[F],
+        symbols: &[SymbolSet],
+    ) -> Result<Self, Box<dyn Error>> {
+        let (first, last) = match (frames.first(), frames.last()) {
+            (Some(first), Some(last)) => (first.borrow(), last.borrow()),
+            _ => return Err(Box::new(MatcherError::from("empty match"))),
+        };
+
+        let path = dfa
+            .path(symbols)?
+            .ok_or_else(|| MatcherError::from("frames do not match"))?;
+
+        let any = SpatialFormula::from(Atom::Any);
+        let mut steps = Vec::new();
+
+        for (frame, byte) in frames.iter().zip(path) {
+            let frame = frame.borrow();
+
+            let (symbol, mismatch) = match symbols::mismatched(byte) {
+                Some(symbol) => (symbol, true),
+                None => (byte as char, false),
+            };
+
+            // A frame that satisfies no formula may still be matched (i.e.,
+            // by a complement), in which case it is matched as any frame.
+            let formula = match byte {
+                symbols::BLANK => &any,
+                _ => evaluator
+                    .formula(symbol)
+                    .ok_or_else(|| MatcherError::from(format!("unknown symbol `{}`", symbol)))?,
+            };
+
+            // The symbols of each frame were evaluated through the filter of
+            // its formula (if any). So, a frame whose formula does not hold
+            // on its own may still be matched by it.
+            let held = !mismatch && !evaluator.monitor.evaluate(frame, formula);
+
+            let mut robustness = evaluator.monitor.robustness(frame, formula);
+
+            if held {
+                robustness = robustness.max(0.0);
+            }
+
+            steps.push(Step {
+                index: frame.index,
+                timestamp: frame.timestamp,
+                symbol,
+                formula: formula.clone(),
+                witnesses: match mismatch || held {
+                    true => Vec::new(),
+                    false => evaluator
+                        .monitor
+                        .witness(frame, formula)
+                        .unwrap_or_default(),
+                },
+                mismatch,
+                held,
+                robustness,
+            });
+        }
+
+        let errors = steps.iter().filter(|step| step.mismatch).count();
+        let score = steps
+            .iter()
+            .map(|step| step.robustness)
+            .fold(f64::INFINITY, f64::min);
+
+        Ok(Record {
+            start: first.index,
+            end: last.index + 1,
+            start_time: first.timestamp,
+            end_time: last.timestamp,
+            steps,
+            errors,
+            score,
+            probability: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+    use crate::datastream::frame::sample::detections::{
+        Annotation, BoundingBox, DetectionRecord, Geometry, Point,
+    };
+    use crate::datastream::frame::sample::Sample;
+    use crate::datastream::frame::Frame;
+    use crate::matcher::{offline, Matching};
+    use crate::monitor::s4::Region;
+
+    fn frame(index: usize, annotations: &[(&str, f64, f64)]) -> Frame {
+        let mut record = DetectionRecord::new(String::from("cam"), index as f64 / 10.0, None);
+
+        for (label, x, y) in annotations {
+            let bbox = BoundingBox::new(Point::new(*x, *y), Point::new(x + 10.0, y + 10.0));
+            let annotation = Annotation::new(label.to_string(), 1.0, Geometry::BoundingBox(bbox));
+
+            record
+                .annotations
+                .entry(label.to_string())
+                .or_default()
+                .push(annotation);
+        }
+
+        let mut frame = Frame::new(index, index as f64 / 10.0);
+        frame.samples.push(Sample::ObjectDetection(record));
+
+        frame
+    }
+
+    #[test]
+    fn record() {
+        let ast = Compiler::new()
+            .compile("[[:car:] | [:bus:]][<nonempty>([:car:] & [:pedestrian:])]")
+            .unwrap();
+        let matcher = offline::Matcher::from(&ast);
+
+        let frames = [
+            frame(10, &[("car", 0.0, 0.0), ("bus", 50.0, 0.0)]),
+            frame(
+                11,
+                &[
+                    ("car", 0.0, 0.0),
+                    ("car", 90.0, 90.0),
+                    ("pedestrian", 5.0, 5.0),
+                ],
+            ),
+        ];
+
+        let symbols = frames
+            .iter()
+            .map(|frame| matcher.evaluate(frame))
+            .collect::<Vec<_>>();
+
+        let m = matcher.find(&symbols).unwrap().pop().unwrap();
+        let record = matcher
+            .record(&frames[m.start..m.end], &symbols[m.start..m.end])
+            .unwrap();
+
+        assert_eq!((10, 12), (record.start, record.end));
+        assert_eq!((1.0, 1.1), (record.start_time, record.end_time));
+        assert_eq!(
+            vec!['a', 'b'],
+            record.steps.iter().map(|s| s.symbol).collect::<Vec<_>>()
+        );
+
+        let labels = |witnesses: &[Region]| {
+            witnesses
+                .iter()
+                .map(|region| match region {
+                    Region::Annotation(_, annotation) => annotation.label.clone(),
+                    _ => String::new(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The far car does not intersect the pedestrian.
+        assert_eq!(vec!["car", "bus"], labels(&record.steps[0].witnesses));
+        assert_eq!(
+            vec!["car", "pedestrian"],
+            labels(&record.steps[1].witnesses)
+        );
+
+        // The car and pedestrian overlap by a quarter.
+        assert_eq!(
+            vec![1.0, 0.25],
+            record
+                .steps
+                .iter()
+                .map(|s| s.robustness)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0.25, record.score);
+    }
+
+    #[test]
+    fn score() {
+        let frames = [
+            frame(0, &[("car", 0.0, 0.0)]),
+            frame(1, &[("car", 5.0, 0.0)]),
+        ];
+
+        let record = |pattern: &str| {
+            let ast = Compiler::new().compile(pattern).unwrap();
+            let matcher = offline::Matcher::from(&ast);
+
+            let symbols = frames
+                .iter()
+                .map(|frame| matcher.evaluate(frame))
+                .collect::<Vec<_>>();
+
+            matcher.record(&frames, &symbols).unwrap()
+        };
+
+        // The width of each car (10) exceeds the value by its margin, relative
+        // to the width.
+        assert_eq!(0.05, record("[<width>[:car:] > 9.5]{2}").score);
+        assert_eq!(0.8, record("[<width>[:car:] > 2]{2}").score);
+
+        // The width of each car misses the value by 2, relative to the value.
+        assert_eq!(2.0 / 12.0, record("[!(<width>[:car:] > 12)]{2}").score);
+
+        // A width equal to the value does not satisfy a strict comparison; so,
+        // its negation does.
+        assert!(record("[!(<width>[:car:] > 10)]{2}").score > 0.0);
+    }
+
+    #[test]
+    fn held() {
+        let ast = Compiler::new()
+            .compile("[<hold 2>[:pedestrian:]]{3}")
+            .unwrap();
+        let matcher = offline::Matcher::from(&ast);
+
+        let frames = [
+            frame(0, &[("pedestrian", 0.0, 0.0)]),
+            frame(1, &[]),
+            frame(2, &[]),
+        ];
+
+        let symbols = frames
+            .iter()
+            .map(|frame| matcher.evaluate(frame))
+            .collect::<Vec<_>>();
+
+        let record = matcher.record(&frames, &symbols).unwrap();
+
+        assert_eq!(
+            vec![false, true, true],
+            record.steps.iter().map(|s| s.held).collect::<Vec<_>>()
+        );
+        assert_eq!(1, record.steps[0].witnesses.len());
+        assert!(record.steps[1].witnesses.is_empty());
+
+        // The pedestrian is held for the frames it is missing from; so, they
+        // satisfy the formula, if only barely.
+        assert_eq!(
+            vec![1.0, 0.0, 0.0],
+            record
+                .steps
+                .iter()
+                .map(|s| s.robustness)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0.0, record.score);
+    }
+}
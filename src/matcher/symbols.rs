@@ -0,0 +1,205 @@
+//! The symbols satisfied by frames.
+//!
+//! Evaluating a spatial formula against a [`Frame`] is the most expensive step
+//! of matching. Therefore, each [`Frame`] is evaluated against every formula
+//! exactly once, and the result is kept as a [`SymbolSet`] for as long as the
+//! [`Frame`] is searched over. The automata then step over these sets, instead.
+
+use std::cell::RefCell;
+
+use crate::compiler::ir::ast::SpatialFormula;
+use crate::datastream::frame::Frame;
+use crate::monitor::Monitor;
+use crate::symbolizer::ast::SymbolicAbstractSyntaxTree;
+
+use super::filters::Filter;
+
+/// The symbol transitioned on when no formula is satisfied by a [`Frame`].
+///
+/// This must never be a symbol of the alphabet used by the symbolizer, so that
+/// it never appears within a pattern.
+pub const BLANK: u8 = b'_';
+
+/// The symbol transitioned on when a [`Frame`] does not satisfy the formula of
+/// a symbol that is matched approximately.
+///
+/// Each symbol of the alphabet (i.e., an ASCII letter) is mapped to a distinct
+/// control or punctuation byte. So, these never collide with the alphabet nor
+/// with [`BLANK`].
+pub fn mismatch(symbol: char) -> u8 {
+    match symbol {
+        'A'..='Z' => symbol as u8 - b'A' + 1,
+        'a'..='z' => symbol as u8 - b'a' + 27,
+        _ => unreachable!("symbol `{}` is not in the alphabet", symbol),
+    }
+}
+
+/// The symbol that a mismatch was transitioned on for, if any (see
+/// [`mismatch`]).
+pub fn mismatched(byte: u8) -> Option<char> {
+    match byte {
+        1..=26 => Some((byte - 1 + b'A') as char),
+        27..=52 => Some((byte - 27 + b'a') as char),
+        _ => None,
+    }
+}
+
+/// A set of symbols satisfied by a single [`Frame`].
+///
+/// All symbols are ASCII characters. So, the set is represented as a bitset
+/// where each bit corresponds to the byte of a symbol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SymbolSet {
+    bits: u128,
+}
+
+impl SymbolSet {
+    /// Create a new empty [`SymbolSet`].
+    pub fn new() -> Self {
+        SymbolSet { bits: 0 }
+    }
+
+    /// Add a symbol to the [`SymbolSet`].
+    pub fn insert(&mut self, symbol: char) {
+        debug_assert!(symbol.is_ascii());
+        self.bits |= 1 << (symbol as u32);
+    }
+
+    /// Check whether a symbol is in the [`SymbolSet`].
+    pub fn contains(&self, symbol: char) -> bool {
+        symbol.is_ascii() && self.bits & (1 << (symbol as u32)) != 0
+    }
+
+    /// Check whether the [`SymbolSet`] has no symbols.
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// The symbols in either [`SymbolSet`].
+    pub fn union(&self, other: &SymbolSet) -> SymbolSet {
+        SymbolSet {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    /// Iterate over the bytes of the symbols in the [`SymbolSet`].
+    pub fn bytes(&self) -> impl Iterator<Item = u8> {
+        let mut bits = self.bits;
+
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+
+            let byte = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+
+            Some(byte)
+        })
+    }
+}
+
+/// An evaluator of the symbols satisfied by a [`Frame`].
+///
+/// This holds the mapping between each symbol and its spatial formula along
+/// with the [`Monitor`] used to evaluate them.
+///
+/// A formula may be smoothed by a [`Filter`] over the frames evaluated before
+/// it. So, the [`Frame`](s) of a stream must be evaluated in order, and only
+/// once each.
+pub struct Evaluator<'a> {
+    pub formulas: Vec<(char, &'a SpatialFormula)>,
+    pub monitor: Monitor,
+
+    /// The symbols matched approximately, which also satisfy their
+    /// [`mismatch`] whenever their formula is not satisfied.
+    pub approximate: Vec<char>,
+
+    /// The [`Filter`] (if any) of each formula.
+    filters: RefCell<Vec<Option<Filter>>>,
+}
+
+impl<'a> Evaluator<'a> {
+    /// Create a new [`Evaluator`].
+    pub fn new(formulas: Vec<(char, &'a SpatialFormula)>) -> Self {
+        let filters = formulas
+            .iter()
+            .map(|(_, formula)| Filter::split(formula).0.cloned().map(Filter::new))
+            .collect();
+
+        Evaluator {
+            formulas,
+            monitor: Monitor::new(),
+            approximate: Vec::new(),
+            filters: RefCell::new(filters),
+        }
+    }
+
+    /// Evaluate the set of symbols satisfied by the [`Frame`].
+    pub fn evaluate(&self, frame: &Frame) -> SymbolSet {
+        let mut symbols = SymbolSet::new();
+        let mut filters = self.filters.borrow_mut();
+
+        for ((symbol, formula), filter) in self.formulas.iter().zip(filters.iter_mut()) {
+            let mut holds = self.monitor.evaluate(frame, formula);
+
+            if let Some(filter) = filter {
+                holds = filter.update(holds, frame.timestamp);
+            }
+
+            if holds {
+                symbols.insert(*symbol);
+            } else if self.approximate.contains(symbol) {
+                symbols.insert(mismatch(*symbol) as char);
+            }
+        }
+
+        symbols
+    }
+
+    /// The spatial formula of a symbol, if any.
+    pub fn formula(&self, symbol: char) -> Option<&'a SpatialFormula> {
+        self.formulas
+            .iter()
+            .find(|(s, _)| *s == symbol)
+            .map(|(_, formula)| *formula)
+    }
+}
+
+impl<'a> From<&'a SymbolicAbstractSyntaxTree> for Evaluator<'a> {
+    fn from(ast: &'a SymbolicAbstractSyntaxTree) -> Self {
+        let mut evaluator = Evaluator::new(
+            ast.fmap()
+                .into_iter()
+                .map(|x| (x.symbol, &x.formula))
+                .collect(),
+        );
+
+        evaluator.approximate = ast
+            .approximations()
+            .into_iter()
+            .flat_map(|approximation| approximation.symbols)
+            .collect();
+
+        evaluator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SymbolSet;
+
+    #[test]
+    fn symbol_set() {
+        let mut symbols = SymbolSet::new();
+        assert!(symbols.is_empty());
+
+        for symbol in ['a', 'Z', 'z'] {
+            symbols.insert(symbol);
+        }
+
+        assert!(symbols.contains('a'));
+        assert!(!symbols.contains('b'));
+        assert_eq!(vec![b'Z', b'a', b'z'], symbols.bytes().collect::<Vec<u8>>());
+    }
+}
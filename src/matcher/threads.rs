@@ -0,0 +1,431 @@
+//! The threads of an incremental search.
+//!
+//! A search is advanced by a single frame at a time through a forward DFA that
+//! recognizes all matches (see [`forward::build_all`]). Each thread is a state
+//! of the DFA along with the positions a match may start from to reach it.
+//! Therefore, the work done per frame is bounded by the size of the DFA (and
+//! the size of the `window`, if any) and not by the length of the stream.
+//!
+//! [`forward::build_all`]: super::automata::dfa::forward::build_all
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+
+use regex_automata::util::primitives::StateID;
+
+use super::automata::dfa::forward::DeterministicFiniteAutomata;
+use super::symbols::SymbolSet;
+use super::{Match, MatchKind};
+
+/// The live threads of a search.
+#[derive(Debug)]
+pub struct Threads {
+    /// The positions a match may start from (in ascending order) per state.
+    states: HashMap<StateID, Vec<usize>>,
+
+    /// The number of frames advanced by so far.
+    position: usize,
+
+    /// A limit on the number of frames a match may span.
+    window: Option<usize>,
+
+    kind: MatchKind,
+
+    /// The end of the last match reported (for non-overlapping kinds).
+    barrier: usize,
+
+    /// The best end found so far per start (for leftmost kinds).
+    pending: BTreeMap<usize, usize>,
+}
+
+impl Threads {
+    /// Create a new empty set of [`Threads`] reporting matches of `kind`.
+    pub fn new(kind: MatchKind) -> Self {
+        Threads {
+            states: HashMap::new(),
+            position: 0,
+            window: None,
+            kind,
+            barrier: 0,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Set the `window` of the [`Threads`].
+    pub fn window(mut self, size: usize) -> Self {
+        self.window = Some(size);
+        self
+    }
+
+    /// The number of frames advanced by so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The earliest position a future match may start from.
+    ///
+    /// Any frame before this position is never part of a future match, so it
+    /// may be safely discarded.
+    pub fn earliest(&self) -> usize {
+        let mut earliest = self
+            .states
+            .values()
+            .map(|starts| starts[0])
+            .min()
+            .unwrap_or(self.position);
+
+        if let Some(window) = self.window {
+            earliest = earliest.max((self.position + 1).saturating_sub(window));
+        }
+
+        // A pending match is within the `window` as it was already found.
+        if let Some(pending) = self.pending.keys().next() {
+            earliest = earliest.min(*pending);
+        }
+
+        earliest.min(self.position)
+    }
+
+    /// The earliest start of any live thread.
+    ///
+    /// A live thread is a prefix of a match that may still be completed by the
+    /// frames to come.
+    pub fn live(&self) -> Option<usize> {
+        self.starts().min()
+    }
+
+    /// The earliest start of each live thread (in no particular order).
+    pub fn starts(&self) -> impl Iterator<Item = usize> + '_ {
+        self.states.values().map(|starts| starts[0])
+    }
+
+    /// The leftmost match found that is not yet known to be final (for
+    /// leftmost kinds).
+    pub fn pending(&self) -> Option<Match> {
+        self.pending
+            .first_key_value()
+            .map(|(start, end)| Match::new(*start, *end))
+    }
+
+    /// Advance the [`Threads`] by the symbols of the next frame.
+    ///
+    /// If `start` is set, a new thread is started at the frame before every
+    /// thread takes its next transition. The matches returned are those that
+    /// are known to be final at this frame, and their indices are absolute.
+    /// That is, they count every frame the [`Threads`] have been advanced by.
+    pub fn advance(
+        &mut self,
+        dfa: &DeterministicFiniteAutomata,
+        symbols: &SymbolSet,
+        start: bool,
+    ) -> Result<Vec<Match>, Box<dyn Error>> {
+        let at = self.position;
+        let initial = (*dfa.initial()?.id(), vec![at]);
+
+        // The earliest start of a match that may still end at this frame.
+        let horizon = self
+            .window
+            .map_or(0, |window| (at + 1).saturating_sub(window))
+            .max(self.barrier);
+
+        let mut nexts: HashMap<StateID, Vec<usize>> = HashMap::new();
+
+        let initial = start.then_some((&initial.0, &initial.1));
+
+        for (sid, starts) in self.states.iter().chain(initial) {
+            let starts = starts.iter().filter(|start| **start >= horizon);
+
+            for next in dfa.successors(*sid, symbols) {
+                nexts.entry(next).or_default().extend(starts.clone());
+            }
+        }
+
+        // Only the earliest start is kept for each state.
+        //
+        // As the DFA is deterministic, two threads in the same state reach the
+        // same matches from here on. So, the thread with the later start never
+        // produces a longer match and is discarded. However, the earliest start
+        // may be discarded first, instead: with a `window`, it may expire; and,
+        // for non-overlapping kinds, a pending match may overlap it (but not
+        // the later one). Similarly, for overlapping matches, every start is
+        // reported. So, these starts are kept, too.
+        let bound = match (self.window, self.kind) {
+            (Some(..), _) | (_, MatchKind::AllOverlapping) => usize::MAX,
+            _ => self.pending.values().copied().max().unwrap_or(0),
+        };
+
+        nexts.retain(|_, starts| {
+            starts.sort_unstable();
+            starts.dedup();
+
+            let keep = starts
+                .iter()
+                .position(|start| *start >= bound)
+                .map_or(starts.len(), |i| i + 1);
+
+            starts.truncate(keep);
+
+            !starts.is_empty()
+        });
+
+        self.states = nexts;
+        self.position += 1;
+
+        let finals = self
+            .states
+            .iter()
+            .filter(|(sid, _)| dfa.is_final(**sid))
+            .flat_map(|(_, starts)| starts.iter().copied())
+            .collect::<BTreeSet<usize>>();
+
+        let end = self.position;
+
+        match self.kind {
+            MatchKind::AllEnds => Ok(finals
+                .first()
+                .map(|start| Match::new(*start, end))
+                .into_iter()
+                .collect()),
+            MatchKind::AllOverlapping => Ok(finals
+                .into_iter()
+                .map(|start| Match::new(start, end))
+                .collect()),
+            MatchKind::LeftmostShortest => {
+                // The shortest match from a start is the first one found. So,
+                // its threads are no longer needed.
+                for start in finals.iter() {
+                    self.pending.entry(*start).or_insert(end);
+                }
+
+                self.remove(|start| finals.contains(&start));
+
+                Ok(self.release())
+            }
+            MatchKind::LeftmostLongest => {
+                for start in finals.iter() {
+                    self.pending.insert(*start, end);
+                }
+
+                Ok(self.release())
+            }
+        }
+    }
+
+    /// Finish the search at the end of the stream.
+    ///
+    /// As no thread may advance any further, every pending match is final.
+    pub fn finish(&mut self) -> Vec<Match> {
+        self.states.clear();
+        self.release()
+    }
+
+    /// Release the pending matches that are known to be final.
+    ///
+    /// The leftmost pending match is final once no thread with an earlier
+    /// start remains (or, for the longest match, no thread with the same
+    /// start remains, either). Every later start that it overlaps is then
+    /// discarded.
+    fn release(&mut self) -> Vec<Match> {
+        let mut mats = Vec::new();
+
+        while let Some((start, end)) = self.pending.first_key_value().map(|(s, e)| (*s, *e)) {
+            let blocked = match (self.kind, self.live()) {
+                (MatchKind::LeftmostLongest, Some(live)) => live <= start,
+                (_, Some(live)) => live < start,
+                (_, None) => false,
+            };
+
+            if blocked {
+                break;
+            }
+
+            mats.push(Match::new(start, end));
+
+            self.barrier = end;
+            self.pending.retain(|s, _| *s >= end);
+            self.remove(|s| s < end);
+        }
+
+        mats
+    }
+
+    /// Remove the starts that satisfy the predicate from every thread.
+    fn remove(&mut self, f: impl Fn(usize) -> bool) {
+        self.states.retain(|_, starts| {
+            starts.retain(|start| !f(*start));
+            !starts.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+
+    use super::super::automata::dfa::forward;
+    use super::super::automata::Options;
+    use super::super::symbols::SymbolSet;
+    use super::super::MatchKind;
+    use super::Threads;
+
+    /// The symbols of each frame of a stream, where each frame is either `a`,
+    /// `b`, or neither (i.e., `_`).
+    fn stream(frames: &str) -> Vec<SymbolSet> {
+        frames
+            .chars()
+            .map(|c| {
+                let mut symbols = SymbolSet::new();
+
+                if c != '_' {
+                    symbols.insert(c);
+                }
+
+                symbols
+            })
+            .collect()
+    }
+
+    /// Search a stream, returning the matches reported after each frame (and
+    /// at the end of the stream).
+    fn run(
+        pattern: &str,
+        kind: MatchKind,
+        window: Option<usize>,
+        frames: &str,
+    ) -> Vec<Vec<(usize, usize)>> {
+        let ast = Compiler::new().compile(pattern).unwrap();
+        let dfa = forward::build_all(&ast, &Options::default()).unwrap();
+
+        let mut threads = Threads::new(kind);
+
+        if let Some(window) = window {
+            threads = threads.window(window);
+        }
+
+        let mut reports = stream(frames)
+            .iter()
+            .map(|symbols| threads.advance(&dfa, symbols, true).unwrap())
+            .collect::<Vec<_>>();
+        reports.push(threads.finish());
+
+        reports
+            .into_iter()
+            .map(|mats| mats.into_iter().map(|m| (m.start, m.end)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn kinds() {
+        let pattern = "[[:a:]][[:b:]]*";
+
+        // A longest match is only final once no thread may extend it (i.e.,
+        // at the end of the stream, for the last one).
+        assert_eq!(
+            vec![
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![(0, 3)],
+                vec![],
+                vec![(4, 6)]
+            ],
+            run(pattern, MatchKind::LeftmostLongest, None, "abb_ab")
+        );
+
+        // A shortest match is final as soon as it is found.
+        assert_eq!(
+            vec![
+                vec![(0, 1)],
+                vec![],
+                vec![],
+                vec![],
+                vec![(4, 5)],
+                vec![],
+                vec![]
+            ],
+            run(pattern, MatchKind::LeftmostShortest, None, "abb_ab")
+        );
+
+        // Every end is reported at its frame, with the leftmost start.
+        assert_eq!(
+            vec![
+                vec![(0, 1)],
+                vec![(0, 2)],
+                vec![(0, 3)],
+                vec![],
+                vec![(4, 5)],
+                vec![(4, 6)],
+                vec![],
+            ],
+            run(pattern, MatchKind::AllEnds, None, "abb_ab")
+        );
+
+        // Every start is reported at each end.
+        assert_eq!(
+            vec![
+                vec![(0, 1)],
+                vec![(0, 2), (1, 2)],
+                vec![(1, 3), (2, 3)],
+                vec![],
+            ],
+            run("[[:a:]]{1,2}", MatchKind::AllOverlapping, None, "aaa")
+        );
+    }
+
+    #[test]
+    fn window() {
+        let pattern = "[[:a:]][[:b:]]*";
+
+        // A match may span at most two frames, so the longest is final once
+        // its start leaves the window.
+        assert_eq!(
+            vec![vec![], vec![], vec![(0, 2)], vec![], vec![], vec![]],
+            run(pattern, MatchKind::LeftmostLongest, Some(2), "abb_b")
+        );
+
+        assert_eq!(
+            vec![vec![(0, 1)], vec![(0, 2)], vec![], vec![], vec![], vec![]],
+            run(pattern, MatchKind::AllEnds, Some(2), "abb_b")
+        );
+
+        assert_eq!(
+            vec![
+                vec![(0, 1)],
+                vec![(0, 2), (1, 2)],
+                vec![(1, 3), (2, 3)],
+                vec![(2, 4), (3, 4)],
+                vec![],
+            ],
+            run("[[:a:]]{1,}", MatchKind::AllOverlapping, Some(2), "aaaa")
+        );
+    }
+
+    #[test]
+    fn unbounded() {
+        let ast = Compiler::new().compile("[[:a:]][[:b:]]*").unwrap();
+        let dfa = forward::build_all(&ast, &Options::default()).unwrap();
+
+        // Without a window, every frame since the start of a live thread is
+        // kept for as long as the match may continue.
+        let mut threads = Threads::new(MatchKind::LeftmostLongest);
+
+        for symbols in stream("abbbb").iter() {
+            assert!(threads.advance(&dfa, symbols, true).unwrap().is_empty());
+            assert_eq!(0, threads.earliest());
+        }
+
+        assert_eq!(Some(0), threads.live());
+        assert_eq!(Some((0, 5)), threads.pending().map(|m| (m.start, m.end)));
+
+        // With a window, the thread expires and no frame is kept any longer.
+        let mut threads = Threads::new(MatchKind::LeftmostLongest).window(2);
+
+        for symbols in stream("abbbb").iter() {
+            threads.advance(&dfa, symbols, true).unwrap();
+        }
+
+        assert_eq!(None, threads.live());
+        assert_eq!(5, threads.earliest());
+    }
+}
@@ -3,12 +3,27 @@
 //! Currently, the implemented monitors include evaluation of S4/S4u topological
 //! formulas interpreted over frames.
 
-use crate::compiler::ir::ast::SpatialFormula;
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::compiler::ir::ast::{Atom, SpatialFormula};
+use crate::compiler::ir::Node;
+use crate::datastream::frame::sample::signals::Signal;
 use crate::datastream::frame::sample::Sample;
 use crate::datastream::frame::Frame;
+use crate::matcher::filters::Filter;
+
+use self::s4::Region;
+use self::zones::Zones;
 
 pub mod s4;
 pub mod s4u;
+pub mod temporal;
+pub mod zones;
+
+/// The robustness of a formula over a region, tag, or signal that does not
+/// exist (see [`Monitor::robustness`]).
+pub const ABSENT: f64 = -1.0;
 
 /// The main monitor.
 ///
@@ -17,28 +32,395 @@ pub mod s4u;
 /// different sample types.
 ///
 /// For example, point clouds, object detections, etc.
-#[derive(Default)]
-pub struct Monitor {}
+#[derive(Clone, Default)]
+pub struct Monitor {
+    /// The named [`Zones`] that spatial formulas may reference.
+    zones: Zones,
+}
 
 impl Monitor {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            zones: Zones::new(),
+        }
+    }
+
+    /// Set the [`Zones`] of the [`Monitor`].
+    pub fn zones(mut self, zones: Zones) -> Self {
+        self.zones = zones;
+        self
+    }
+
+    /// Check that every zone referenced by a spatial formula is defined by the
+    /// [`Zones`] of the [`Monitor`] (see [`Zones::validate`]).
+    pub fn validate(&self, formula: &SpatialFormula) -> Result<(), Box<dyn Error>> {
+        Ok(self.zones.validate(formula)?)
     }
 
     /// The main interface to evaluating a frame sample against a spatial formula.
     ///
-    /// This considers all possible sample types.
+    /// This considers all possible sample types. A temporal filter of the
+    /// formula (if any) depends on other frames; so, only the formula it
+    /// filters is evaluated (see [`Filter`]).
     pub fn evaluate(&self, frame: &Frame, formula: &SpatialFormula) -> bool {
+        let (_, formula) = Filter::split(formula);
+        let context = Context::new(&self.zones, frame);
+
+        // Any frame holds, even one without samples.
+        if let Node::Operand(Atom::Any) = formula {
+            return true;
+        }
+
+        frame
+            .samples
+            .iter()
+            .any(|sample| s4u::Monitor::evaluate(sample, &context, formula))
+    }
+
+    /// Evaluate the robustness of a spatial formula against a frame.
+    ///
+    /// This is the degree to which the formula is satisfied by its strongest
+    /// sample, built from the scores of annotations and tags, the overlap of
+    /// regions, and the margins of comparisons (see [`s4u::Monitor::robustness`]).
+    /// It is non-negative if the formula is satisfied, and negative if not. So,
+    /// for example, a car that barely overlaps a pedestrian is less robust than
+    /// one that covers it. As with [`Monitor::evaluate`], a temporal filter of
+    /// the formula is not considered.
+    pub fn robustness(&self, frame: &Frame, formula: &SpatialFormula) -> f64 {
+        let (_, formula) = Filter::split(formula);
+        let context = Context::new(&self.zones, frame);
+
+        if let Node::Operand(Atom::Any) = formula {
+            return f64::INFINITY;
+        }
+
+        frame
+            .samples
+            .iter()
+            .map(|sample| s4u::Monitor::robustness(sample, &context, formula))
+            .reduce(f64::max)
+            .unwrap_or(ABSENT)
+    }
+
+    /// Evaluate the probability that a spatial formula is satisfied by a frame.
+    ///
+    /// The score of each annotation (and tag) is taken as the probability that
+    /// it exists, independently of every other (see
+    /// [`s4u::Monitor::probability`]). The formula is satisfied if any sample
+    /// satisfies it, where the samples are assumed to be independent, as well.
+    ///
+    /// However, a tag is a property of the frame rather than of a sample; so,
+    /// it may not be counted once per sample. Instead, the samples are combined
+    /// under each outcome of the (uncertain) tags of the formula, which are
+    /// then weighed by the probability of the outcome. As with
+    /// [`Monitor::evaluate`], a temporal filter of the formula is not
+    /// considered.
+    pub fn probability(&self, frame: &Frame, formula: &SpatialFormula) -> f64 {
+        let (_, formula) = Filter::split(formula);
+        let context = Context::new(&self.zones, frame);
+
+        if let Node::Operand(Atom::Any) = formula {
+            return 1.0;
+        }
+
+        let mut tags = Vec::new();
+        self::tags(formula, &mut tags);
+
+        // A tag that is either certainly present or absent has a single outcome.
+        let mut tags = tags
+            .into_iter()
+            .filter_map(|label| context.tags.get_key_value(label))
+            .map(|(label, score)| (*label, score.clamp(0.0, 1.0)))
+            .filter(|(_, score)| *score > 0.0 && *score < 1.0)
+            .collect::<Vec<_>>();
+
+        tags.sort_unstable_by_key(|(label, _)| *label);
+        tags.dedup_by(|(a, _), (b, _)| a == b);
+
+        (0..1usize << tags.len())
+            .map(|outcome| {
+                let mut context = context.clone();
+                let mut weight = 1.0;
+
+                for (i, (label, score)) in tags.iter().enumerate() {
+                    let present = (outcome >> i) & 1 == 1;
+
+                    weight *= match present {
+                        true => *score,
+                        false => 1.0 - *score,
+                    };
+
+                    context.outcomes.insert(label, present);
+                }
+
+                weight
+                    * (1.0
+                        - frame
+                            .samples
+                            .iter()
+                            .map(|sample| {
+                                1.0 - s4u::Monitor::probability(sample, &context, formula)
+                            })
+                            .product::<f64>())
+            })
+            .sum()
+    }
+
+    /// Find the regions that witness a spatial formula against a frame.
+    ///
+    /// This returns [`None`] if the formula is not satisfied. Otherwise, the
+    /// regions of every sample that satisfies the formula are returned.
+    pub fn witness(&self, frame: &Frame, formula: &SpatialFormula) -> Option<Vec<Region>> {
+        let (_, formula) = Filter::split(formula);
+        let context = Context::new(&self.zones, frame);
+        let mut witnesses: Option<Vec<Region>> = None;
+
+        if let Node::Operand(Atom::Any) = formula {
+            return Some(Vec::new());
+        }
+
+        for sample in frame.samples.iter() {
+            if let Some(regions) = s4u::Monitor::witness(sample, &context, formula) {
+                witnesses.get_or_insert_with(Vec::new).extend(regions);
+            }
+        }
+
+        witnesses
+    }
+}
+
+/// The data shared by all samples of a frame during evaluation.
+///
+/// Unlike object detections, which are evaluated per sample, some operands of
+/// a spatial formula (e.g., signals and tags) are properties of the frame as a
+/// whole.
+/// This allows, for example, a detection of a camera to be combined with the
+/// speed reported by the CAN bus.
+#[derive(Clone)]
+pub struct Context<'a> {
+    /// The named [`Zones`] that spatial formulas may reference.
+    pub zones: &'a Zones,
+
+    /// A mapping between the names of signals and their values.
+    ///
+    /// If several channels report a signal with the same name, the first one
+    /// is used.
+    pub signals: HashMap<&'a str, &'a Signal>,
+
+    /// A mapping between the tags of the scene and their (highest) scores.
+    pub tags: HashMap<&'a str, f64>,
+
+    /// The tags that are known to be present (or absent) in an outcome of the
+    /// frame, rather than by their scores (see [`Monitor::probability`]).
+    pub outcomes: HashMap<&'a str, bool>,
+}
+
+impl<'a> Context<'a> {
+    /// Create a new [`Context`] from the samples of a [`Frame`].
+    pub fn new(zones: &'a Zones, frame: &'a Frame) -> Self {
+        let mut signals = HashMap::new();
+        let mut tags: HashMap<&str, f64> = HashMap::new();
+
         for sample in frame.samples.iter() {
             match sample {
-                Sample::ObjectDetection(record) => {
-                    if s4u::Monitor::evaluate(&record.annotations, formula) {
-                        return true;
+                Sample::Signal(record) => {
+                    for (name, signal) in record.signals.iter() {
+                        signals.entry(name.as_str()).or_insert(signal);
                     }
                 }
-            };
+                Sample::Scene(record) => {
+                    for (label, score) in record.tags.iter() {
+                        let entry = tags.entry(label.as_str()).or_insert(*score);
+                        *entry = entry.max(*score);
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        Context {
+            zones,
+            signals,
+            tags,
+            outcomes: HashMap::new(),
+        }
+    }
+}
+
+/// Collect the labels of the tags that a spatial formula considers present
+/// (i.e., rather than compares against their scores).
+fn tags<'f>(formula: &'f SpatialFormula, labels: &mut Vec<&'f str>) {
+    match formula {
+        Node::Operand(Atom::Tag(label, None)) => labels.push(label),
+        Node::Operand(..) => (),
+        Node::UnaryExpr { child, .. } => self::tags(child, labels),
+        Node::BinaryExpr { left, right, .. } => {
+            self::tags(left, labels);
+            self::tags(right, labels);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ir::Node;
+    use crate::compiler::Compiler;
+    use crate::datastream::frame::sample::detections::{
+        Annotation, BoundingBox, DetectionRecord, Geometry, Image, ImageSource, Point, Polygon,
+    };
+    use crate::datastream::frame::sample::scenes::SceneRecord;
+    use crate::datastream::frame::sample::Sample;
+    use crate::datastream::frame::Frame;
+
+    use super::Monitor;
+
+    /// A pseudo-random number generator (i.e., xorshift), so that the frames
+    /// generated are the same across runs.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % n
+        }
+
+        /// A value of a few steps, so that values often equal the bounds of
+        /// comparisons exactly.
+        fn value(&mut self, n: u64, step: f64) -> f64 {
+            self.next(n) as f64 * step
+        }
+    }
+
+    fn frame(random: &mut Random) -> Frame {
+        let image = Image::new(ImageSource::File("cam.png".into()), 20.0, 20.0);
+        let mut record = DetectionRecord::new(String::from("cam"), 0.0, Some(image));
+
+        for label in ["car", "pedestrian"] {
+            for _ in 0..random.next(3) {
+                let (x, y) = (random.value(4, 5.0), random.value(4, 5.0));
+                let (w, h) = (random.value(3, 5.0), random.value(3, 5.0));
+
+                // A triangle does not always intersect what its bounds do.
+                let geometry = match random.next(2) {
+                    0 => Geometry::BoundingBox(BoundingBox::new(
+                        Point::new(x, y),
+                        Point::new(x + w, y + h),
+                    )),
+                    _ => Geometry::Polygon(Polygon::new(vec![
+                        Point::new(x, y),
+                        Point::new(x + w, y),
+                        Point::new(x, y + h),
+                    ])),
+                };
+
+                let score = random.value(3, 0.5);
+
+                record
+                    .annotations
+                    .entry(label.to_string())
+                    .or_default()
+                    .push(Annotation::new(label.to_string(), score, geometry));
+            }
+        }
+
+        let mut frame = Frame::new(0, 0.0);
+        frame.samples.push(Sample::ObjectDetection(record));
+
+        if random.next(2) == 0 {
+            let mut record = SceneRecord::new(String::from("weather"), 0.0);
+            record
+                .tags
+                .insert(String::from("rain"), random.value(3, 0.5));
+
+            frame.samples.push(Sample::Scene(record));
         }
 
-        false
+        frame
+    }
+
+    #[test]
+    fn probability() {
+        let car = |score: f64| {
+            let mut record = DetectionRecord::new(String::from("cam"), 0.0, None);
+            record
+                .annotations
+                .entry(String::from("car"))
+                .or_default()
+                .push(Annotation::new(
+                    String::from("car"),
+                    score,
+                    Geometry::BoundingBox(BoundingBox::new(
+                        Point::new(0.0, 0.0),
+                        Point::new(10.0, 10.0),
+                    )),
+                ));
+
+            Sample::ObjectDetection(record)
+        };
+
+        let mut scene = SceneRecord::new(String::from("weather"), 0.0);
+        scene.tags.insert(String::from("rain"), 0.6);
+
+        let mut frame = Frame::new(0, 0.0);
+        frame.samples.push(car(0.5));
+        frame.samples.push(car(0.5));
+        frame.samples.push(Sample::Scene(scene));
+
+        let monitor = Monitor::new();
+        let probability = |formula: &str| match Compiler::new().compile_temporal(formula).unwrap() {
+            Node::Operand(formula) => monitor.probability(&frame, &formula),
+            _ => unreachable!(),
+        };
+
+        // The tag is counted once for the frame, rather than once per sample.
+        assert!((probability("[[:@rain:]]") - 0.6).abs() < 1e-9);
+        assert!((probability("[!([:@rain:])]") - 0.4).abs() < 1e-9);
+        assert!((probability("[[:@rain:] & [:car:]]") - 0.6 * 0.75).abs() < 1e-9);
+        assert!((probability("[[:car:]]") - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn robustness() {
+        let formulas = [
+            "[[:car:]]",
+            "[!([:car:])]",
+            "[[:car:] & !([:pedestrian:])]",
+            "[<width>[:car:] > 5]",
+            "[!(<width>[:car:] > 5)]",
+            "[<width>[:car:] >= 5]",
+            "[<height>[:car:] < 50%]",
+            "[<nonempty>([:car:] & [:pedestrian:])]",
+            "[!(<nonempty>([:car:] & [:pedestrian:]))]",
+            "[<area>([:car:] & [:pedestrian:]) > 25]",
+            "[<area>(!([:car:])) >= 300]",
+            "[<dist>([:car:], [:pedestrian:]) > 5]",
+            "[!(<dist>([:car:], [:car:]) <= 5)]",
+            "[[:@rain:]]",
+            "[!([:@rain:] > 0.5)]",
+        ];
+
+        let monitor = Monitor::new();
+        let mut random = Random(0x2545f4914f6cdd1d);
+
+        let frames = (0..500).map(|_| frame(&mut random)).collect::<Vec<_>>();
+
+        for formula in formulas {
+            let formula = match Compiler::new().compile_temporal(formula).unwrap() {
+                Node::Operand(formula) => formula,
+                _ => unreachable!(),
+            };
+
+            for frame in frames.iter() {
+                assert_eq!(
+                    monitor.evaluate(frame, &formula),
+                    monitor.robustness(frame, &formula) >= 0.0,
+                    "{:#?}\n{:#?}",
+                    formula,
+                    frame
+                );
+            }
+        }
     }
 }
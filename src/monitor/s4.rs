@@ -1,8 +1,246 @@
-use std::collections::HashMap;
+use crate::compiler::ir::ast::{Atom, SpatialFormula};
+use crate::compiler::ir::{
+    DistanceKind, GeometricOperatorKind, MeasureKind, Node, Operator, S4OperatorKind,
+    SpatialOperatorKind, UnitKind,
+};
+use crate::datastream::frame::sample::cuboids::CuboidAnnotation;
+use crate::datastream::frame::sample::detections::{
+    Annotation, BoundingBox, Geometry, Image, Point, Polygon,
+};
+use crate::datastream::frame::sample::Sample;
 
-use crate::compiler::ir::ast::SpatialFormula;
-use crate::compiler::ir::{Node, Operator, S4OperatorKind, SpatialOperatorKind};
-use crate::datastream::frame::sample::detections::{Annotation, BoundingBox, Point};
+use super::zones::Zones;
+
+/// A region of an image evaluated by the S4 monitor.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Region {
+    /// A region annotated by the perception system, along with its index
+    /// among the annotations of the same label.
+    Annotation(usize, Annotation),
+
+    /// A region annotated in 3D, viewed from a bird's-eye view, along with its
+    /// index among the annotations of the same label.
+    Cuboid(usize, CuboidAnnotation),
+
+    /// A named zone resolved against the image.
+    Zone(String, Polygon),
+
+    /// The complement of a set of regions with respect to the image, given by
+    /// the bounds of the image and the geometries excluded from it.
+    Complement(BoundingBox, Vec<Geometry>),
+}
+
+impl Region {
+    /// The [`Geometry`] of the [`Region`].
+    ///
+    /// A complement is not a simple shape; so, it is outlined by its bounds.
+    pub fn geometry(&self) -> Geometry {
+        match self {
+            Region::Annotation(_, annotation) => annotation.geometry.clone(),
+            Region::Cuboid(_, annotation) => Geometry::Polygon(annotation.cuboid.footprint()),
+            Region::Zone(_, polygon) => Geometry::Polygon(polygon.clone()),
+            Region::Complement(..) => Geometry::BoundingBox(self.bounds()),
+        }
+    }
+
+    /// The smallest [`BoundingBox`] that contains the [`Region`].
+    pub fn bounds(&self) -> BoundingBox {
+        match self {
+            Region::Annotation(_, annotation) => annotation.geometry.bounds(),
+            Region::Cuboid(_, annotation) => annotation.cuboid.footprint().bounds(),
+            Region::Zone(_, polygon) => polygon.bounds(),
+            Region::Complement(image, excluded) => Self::uncovered(image, excluded)
+                .into_iter()
+                .reduce(|a, b| {
+                    BoundingBox::new(
+                        Point::new(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+                        Point::new(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+                    )
+                })
+                .unwrap_or(BoundingBox::new(Point::new(0.0, 0.0), Point::new(0.0, 0.0))),
+        }
+    }
+
+    /// The area of the [`Region`].
+    ///
+    /// Unlike any other measure, this is the area covered by the shape of the
+    /// [`Region`] rather than by its bounding box (e.g., the set pixels of a
+    /// mask). The area of a complement is that of the image less the area
+    /// covered by the regions excluded from it.
+    pub fn area(&self) -> f64 {
+        match self {
+            Region::Complement(image, excluded) => Self::uncovered(image, excluded)
+                .iter()
+                .map(BoundingBox::area)
+                .sum(),
+            _ => self.geometry().area(),
+        }
+    }
+
+    /// The geometries that cover the [`Region`].
+    ///
+    /// A complement is covered by the cells of the image it leaves uncovered.
+    fn geometries(&self) -> Vec<Geometry> {
+        match self {
+            Region::Complement(image, excluded) => Self::uncovered(image, excluded)
+                .into_iter()
+                .map(Geometry::BoundingBox)
+                .collect(),
+            _ => vec![self.geometry()],
+        }
+    }
+
+    /// Check whether two regions overlap.
+    ///
+    /// Two cuboids are compared in 3D; otherwise, a cuboid is compared by its
+    /// footprint.
+    pub fn intersects(&self, other: &Region) -> bool {
+        match (self, other) {
+            (Region::Annotation(_, a), Region::Annotation(_, b)) => {
+                a.geometry.intersects(&b.geometry)
+            }
+            (Region::Cuboid(_, a), Region::Cuboid(_, b)) => a.cuboid.intersects(&b.cuboid),
+            (Region::Complement(image, a), Region::Complement(_, b)) => {
+                !Self::uncovered(image, &[a.as_slice(), b.as_slice()].concat()).is_empty()
+            }
+            (Region::Complement(image, excluded), region)
+            | (region, Region::Complement(image, excluded)) => {
+                let geometry = region.geometry();
+
+                Self::uncovered(image, excluded)
+                    .into_iter()
+                    .any(|cell| Geometry::BoundingBox(cell).intersects(&geometry))
+            }
+            _ => self.geometry().intersects(&other.geometry()),
+        }
+    }
+
+    /// Check whether two regions are the same region of a sample.
+    ///
+    /// Unlike `==`, two distinct annotations with identical boxes (e.g., a
+    /// duplicate detection) are not the same region.
+    pub fn is(&self, other: &Region) -> bool {
+        match (self, other) {
+            (Region::Annotation(i, a), Region::Annotation(j, b)) => i == j && a.label == b.label,
+            (Region::Cuboid(i, a), Region::Cuboid(j, b)) => i == j && a.label == b.label,
+            _ => self == other,
+        }
+    }
+
+    /// The cells of an image that are not covered by any of the geometries.
+    ///
+    /// If every geometry is a box, then the image is divided along the edges
+    /// of the boxes; so, each cell is either entirely covered or not at all,
+    /// and the cells do not overlap. Otherwise, the geometries are rasterized
+    /// onto the pixels of the image (see [`Geometry::rasterize`]), and each cell
+    /// is a run of uncovered pixels along a column.
+    fn uncovered(image: &BoundingBox, excluded: &[Geometry]) -> Vec<BoundingBox> {
+        let boxes = excluded
+            .iter()
+            .map(|geometry| match geometry {
+                Geometry::BoundingBox(bbox) => Some(bbox.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+
+        match boxes {
+            Some(boxes) => Self::cells(image, &boxes),
+            None => Self::pixels(image, excluded),
+        }
+    }
+
+    /// The cells of an image that are not covered by any of the boxes, divided
+    /// along their edges (see [`Region::uncovered`]).
+    fn cells(image: &BoundingBox, boxes: &[BoundingBox]) -> Vec<BoundingBox> {
+        let edges = |lo: f64, hi: f64, bounds: &dyn Fn(&BoundingBox) -> (f64, f64)| {
+            let mut edges = vec![lo, hi];
+
+            for b in boxes.iter() {
+                let (min, max) = bounds(b);
+                edges.extend([min.clamp(lo, hi), max.clamp(lo, hi)]);
+            }
+
+            edges.sort_by(f64::total_cmp);
+            edges.dedup();
+            edges
+        };
+
+        let xs = edges(image.min.x, image.max.x, &|b| (b.min.x, b.max.x));
+        let ys = edges(image.min.y, image.max.y, &|b| (b.min.y, b.max.y));
+
+        let mut cells = Vec::new();
+
+        for x in xs.windows(2) {
+            for y in ys.windows(2) {
+                let center = Point::new((x[0] + x[1]) / 2.0, (y[0] + y[1]) / 2.0);
+
+                let covered = boxes.iter().any(|b| {
+                    b.min.x <= center.x
+                        && center.x <= b.max.x
+                        && b.min.y <= center.y
+                        && center.y <= b.max.y
+                });
+
+                if !covered {
+                    cells.push(BoundingBox::new(
+                        Point::new(x[0], y[0]),
+                        Point::new(x[1], y[1]),
+                    ));
+                }
+            }
+        }
+
+        cells
+    }
+
+    /// The runs of pixels of an image that are not covered by any of the
+    /// geometries (see [`Region::uncovered`]).
+    ///
+    /// The pixels at the edges of the image are clipped to its bounds.
+    fn pixels(image: &BoundingBox, excluded: &[Geometry]) -> Vec<BoundingBox> {
+        let (width, height) = (
+            image.max.x.ceil().max(0.0) as usize,
+            image.max.y.ceil().max(0.0) as usize,
+        );
+
+        let mut covered = vec![false; width * height];
+
+        for geometry in excluded.iter() {
+            for (bit, set) in covered.iter_mut().zip(geometry.rasterize(width, height)) {
+                *bit |= set;
+            }
+        }
+
+        let mut cells = Vec::new();
+
+        for (x, column) in covered.chunks(height.max(1)).enumerate() {
+            let mut y = 0;
+
+            while y < column.len() {
+                if column[y] {
+                    y += 1;
+                    continue;
+                }
+
+                let start = y;
+
+                while y < column.len() && !column[y] {
+                    y += 1;
+                }
+
+                cells.push(BoundingBox::new(
+                    Point::new(x as f64, start as f64),
+                    Point::new(
+                        (x as f64 + 1.0).min(image.max.x),
+                        (y as f64).min(image.max.y),
+                    ),
+                ));
+            }
+        }
+
+        cells
+    }
+}
 
 /// A monitor for evaluating S4 formulas.
 #[derive(Default)]
@@ -13,35 +251,105 @@ impl Monitor {
         Self {}
     }
 
-    /// Evaluate formula satisfaction against set of annotations.
+    /// Evaluate formula satisfaction against a sample of annotations.
     ///
-    /// This returns is a boolean result. If true, the formula is satisifed;
-    /// else, if false, then it is not satisfied.
-    pub fn evaluate(
-        detections: &HashMap<String, Vec<Annotation>>,
-        formula: &SpatialFormula,
-    ) -> Vec<Annotation> {
+    /// This returns the set of regions that satisfy the formula. If the set is
+    /// non-empty, the formula is satisfied; else, if empty, then it is not
+    /// satisfied.
+    pub fn evaluate(sample: &Sample, zones: &Zones, formula: &SpatialFormula) -> Vec<Region> {
         match formula {
-            Node::Operand(label) => {
+            Node::Operand(Atom::Class(label)) => {
                 // Retrieve an annotation with the same class category as
                 // specified by the label.
-                if let Some(annotation) = detections.get(label) {
-                    return annotation.clone();
+                match sample {
+                    Sample::ObjectDetection(record) => record
+                        .annotations
+                        .get(label)
+                        .map(|a| {
+                            a.iter()
+                                .cloned()
+                                .enumerate()
+                                .map(|(i, a)| Region::Annotation(i, a))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    Sample::CuboidDetection(record) => record
+                        .annotations
+                        .get(label)
+                        .map(|a| {
+                            a.iter()
+                                .cloned()
+                                .enumerate()
+                                .map(|(i, a)| Region::Cuboid(i, a))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    Sample::Signal(..) | Sample::Scene(..) => Vec::new(),
+                }
+            }
+            Node::Operand(Atom::Zone(name)) => {
+                // Retrieve the zone of the channel with the same name, if it
+                // can be resolved against the image of the sample.
+                if let Some(zone) = zones.get(sample.channel(), name) {
+                    if let Some(polygon) = zone.resolve(sample.image()) {
+                        return vec![Region::Zone(name.clone(), polygon)];
+                    }
                 }
 
                 Vec::new()
             }
-            Node::UnaryExpr { op, .. } => match op {
+            Node::Operand(Atom::Signal(..)) => panic!("monitor: s4: signal is not a region"),
+            Node::Operand(Atom::Tag(..)) => panic!("monitor: s4: tag is not a region"),
+            Node::Operand(Atom::Any) => panic!("monitor: s4: any frame is not a region"),
+            Node::UnaryExpr { op, child } => match op {
                 Operator::SpatialOperator(SpatialOperatorKind::S4Operator(
                     S4OperatorKind::Complement,
                 )) => {
-                    todo!()
+                    // The complement is taken with respect to the image. So,
+                    // without an image, it cannot be computed.
+                    let image = match sample.image() {
+                        Some(image) => image,
+                        None => return Vec::new(),
+                    };
+
+                    // The regions are excluded by their shapes (e.g., the
+                    // set pixels of a mask), rather than their bounding boxes.
+                    let complement = Region::Complement(
+                        BoundingBox::new(
+                            Point::new(0.0, 0.0),
+                            Point::new(image.width, image.height),
+                        ),
+                        Monitor::evaluate(sample, zones, child)
+                            .iter()
+                            .flat_map(Region::geometries)
+                            .collect(),
+                    );
+
+                    if complement.area() <= 0.0 {
+                        return Vec::new();
+                    }
+
+                    vec![complement]
+                }
+                Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                    GeometricOperatorKind::Measure(kind, comparison),
+                )) => {
+                    // Keep only the regions whose measurement satisfies the
+                    // comparison. Regions that cannot be measured (e.g., a
+                    // normalized measure without an image) are discarded.
+                    Monitor::evaluate(sample, zones, child)
+                        .into_iter()
+                        .filter(|region| {
+                            Self::measure(kind, &comparison.unit, region, sample.image())
+                                .is_some_and(|value| comparison.test(value))
+                        })
+                        .collect()
                 }
                 _ => panic!("monitor: s4: unrecognized unary operator"),
             },
             Node::BinaryExpr { op, left, right } => {
-                let left = Monitor::evaluate(detections, left);
-                let right = Monitor::evaluate(detections, right);
+                let left = Monitor::evaluate(sample, zones, left);
+                let right = Monitor::evaluate(sample, zones, right);
 
                 match op {
                     Operator::SpatialOperator(op) => match op {
@@ -58,7 +366,7 @@ impl Monitor {
 
                                 for l in left.iter() {
                                     for r in right.iter() {
-                                        if let Some(_bbox) = Self::intersection(&l.bbox, &r.bbox) {
+                                        if l.intersects(r) {
                                             intersections.push(l.clone());
                                             intersections.push(r.clone());
                                         }
@@ -83,26 +391,493 @@ impl Monitor {
         }
     }
 
-    /// Compute the intersection of two bounding boxes.
+    /// Measure a geometric property of a region.
+    ///
+    /// A region is measured by its bounding box (i.e., its extent), except for
+    /// its area, which is covered by its shape (see [`Region::area`]). If the
+    /// unit is [`UnitKind::Normalized`], the measurement is taken relative to
+    /// the dimensions of the image. Without an image (or with an empty one), a
+    /// normalized measurement cannot be taken; so, [`None`] is returned. The
+    /// aspect of a region without height is [`None`], likewise.
+    fn measure(
+        kind: &MeasureKind,
+        unit: &UnitKind,
+        region: &Region,
+        image: Option<&Image>,
+    ) -> Option<f64> {
+        let (width, height) = Self::scale(unit, image)?;
+        let bbox = region.bounds();
+
+        let value = match kind {
+            MeasureKind::Width => bbox.width() / width,
+            MeasureKind::Height => bbox.height() / height,
+            MeasureKind::Area => region.area() / (width * height),
+            MeasureKind::Aspect if bbox.height() > 0.0 => bbox.width() / bbox.height(),
+            MeasureKind::Aspect => return None,
+            MeasureKind::CenterX => bbox.center().x / width,
+            MeasureKind::CenterY => bbox.center().y / height,
+        };
+
+        Some(value)
+    }
+
+    /// Compute the distance between the closest regions of two sets.
     ///
-    /// If no intersection exists, then [`None`] is returned which is
-    /// semantically equivalent to the empty set.
-    fn intersection(a: &BoundingBox, b: &BoundingBox) -> Option<BoundingBox> {
-        // check if overlap exists
-        if a.min.x < b.max.x && b.min.x < a.max.x && a.min.y < b.max.y && b.min.y < a.max.y {
-            let min = Point::new(
-                std::cmp::max(a.min.x as i64, b.min.x as i64) as f64,
-                std::cmp::max(a.min.y as i64, b.min.y as i64) as f64,
-            );
+    /// The distance between two regions is measured between their bounding
+    /// boxes, either from edge to edge or from center to center. A region is
+    /// never compared against itself. If no pair of regions exists (or the
+    /// distance cannot be normalized without an image), [`None`] is returned.
+    pub fn distance(
+        kind: &DistanceKind,
+        unit: &UnitKind,
+        left: &[Region],
+        right: &[Region],
+        image: Option<&Image>,
+    ) -> Option<f64> {
+        Self::closest(kind, unit, left, right, image).map(|(distance, ..)| distance)
+    }
 
-            let max = Point::new(
-                std::cmp::min(a.max.x as i64, b.max.x as i64) as f64,
-                std::cmp::min(a.max.y as i64, b.max.y as i64) as f64,
-            );
+    /// Find the closest pair of regions of two sets along with their distance.
+    ///
+    /// See [`Monitor::distance`] for how the distance is measured.
+    pub fn closest<'r>(
+        kind: &DistanceKind,
+        unit: &UnitKind,
+        left: &'r [Region],
+        right: &'r [Region],
+        image: Option<&Image>,
+    ) -> Option<(f64, &'r Region, &'r Region)> {
+        let (width, height) = Self::scale(unit, image)?;
 
-            return Some(BoundingBox::new(min, max));
+        let mut closest: Option<(f64, &Region, &Region)> = None;
+
+        for l in left.iter() {
+            for r in right.iter() {
+                if l.is(r) {
+                    continue;
+                }
+
+                let distance = Self::between(kind, l, r, width, height);
+
+                if closest.is_none_or(|(c, ..)| distance < c) {
+                    closest = Some((distance, l, r));
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Compute the distance between two regions (see [`Monitor::distance`]).
+    pub fn gap(
+        kind: &DistanceKind,
+        unit: &UnitKind,
+        l: &Region,
+        r: &Region,
+        image: Option<&Image>,
+    ) -> Option<f64> {
+        let (width, height) = Self::scale(unit, image)?;
+        Some(Self::between(kind, l, r, width, height))
+    }
+
+    /// Compute the distance between two regions, scaled by the `width` and
+    /// `height` provided (see [`Monitor::distance`]).
+    fn between(kind: &DistanceKind, l: &Region, r: &Region, width: f64, height: f64) -> f64 {
+        // A cuboid is not axis-aligned from a bird's-eye view. So, the gap
+        // between edges is taken between the footprints, instead.
+        let footprints = match (kind, l, r) {
+            (DistanceKind::Edge, Region::Cuboid(..), _)
+            | (DistanceKind::Edge, _, Region::Cuboid(..)) => {
+                l.geometry().polygon().zip(r.geometry().polygon())
+            }
+            _ => None,
+        };
+
+        match footprints {
+            Some((a, b)) => a.distance(&b),
+            None => {
+                let (a, b) = (l.bounds(), r.bounds());
+
+                let (dx, dy) = match kind {
+                    DistanceKind::Edge => (
+                        (a.min.x - b.max.x).max(b.min.x - a.max.x).max(0.0),
+                        (a.min.y - b.max.y).max(b.min.y - a.max.y).max(0.0),
+                    ),
+                    DistanceKind::Center => {
+                        (a.center().x - b.center().x, a.center().y - b.center().y)
+                    }
+                };
+
+                (dx / width).hypot(dy / height)
+            }
         }
+    }
+
+    /// The dimensions that a value of the unit is relative to, if any.
+    ///
+    /// An image without a width or height has no dimensions to be relative to.
+    fn scale(unit: &UnitKind, image: Option<&Image>) -> Option<(f64, f64)> {
+        match unit {
+            UnitKind::Pixel => Some((1.0, 1.0)),
+            UnitKind::Normalized => image
+                .map(|image| (image.width, image.height))
+                .filter(|(width, height)| *width > 0.0 && *height > 0.0),
+        }
+    }
+
+    /// Evaluate the robustness of a formula against a sample of annotations.
+    ///
+    /// Unlike [`Monitor::evaluate`], this returns every region that the formula
+    /// considers, along with the degree to which it satisfies the formula. A
+    /// degree is non-negative if the region satisfies the formula, and negative
+    /// if it does not (e.g., a car whose width misses `> 100` by 20 has a degree
+    /// of -0.2; see [`Comparison::degree`](crate::compiler::ir::Comparison::degree)).
+    /// The degree of an annotation is its score, and those of a measure, or of
+    /// an intersection, are limited by its margin and by the overlap of the
+    /// regions (see [`Monitor::overlap`]), respectively.
+    pub fn robustness(
+        sample: &Sample,
+        zones: &Zones,
+        formula: &SpatialFormula,
+    ) -> Vec<(Region, f64)> {
+        match formula {
+            Node::Operand(Atom::Class(..)) => Monitor::evaluate(sample, zones, formula)
+                .into_iter()
+                .map(|region| {
+                    let score = match &region {
+                        Region::Annotation(_, annotation) => annotation.score,
+                        Region::Cuboid(_, annotation) => annotation.score,
+                        _ => 1.0,
+                    };
+
+                    // A detection satisfies its class, whatever its score.
+                    (region, score.max(0.0))
+                })
+                .collect(),
+            Node::UnaryExpr {
+                op:
+                    Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                        GeometricOperatorKind::Measure(kind, comparison),
+                    )),
+                child,
+            } => Monitor::robustness(sample, zones, child)
+                .into_iter()
+                .filter_map(|(region, degree)| {
+                    let value = Self::measure(kind, &comparison.unit, &region, sample.image())?;
+                    Some((region, degree.min(comparison.degree(value))))
+                })
+                .collect(),
+            Node::BinaryExpr {
+                op: Operator::SpatialOperator(SpatialOperatorKind::S4Operator(op)),
+                left,
+                right,
+            } => {
+                let left = Monitor::robustness(sample, zones, left);
+                let right = Monitor::robustness(sample, zones, right);
+
+                match op {
+                    // As with `evaluate`, an intersection is represented by
+                    // the regions of each pair, rather than by their overlap.
+                    S4OperatorKind::Intersection => {
+                        let mut pairs = Vec::new();
+
+                        for (l, dl) in left.iter() {
+                            for (r, dr) in right.iter() {
+                                let degree = dl.min(*dr).min(Self::overlap(l, r));
+
+                                pairs.push((l.clone(), degree));
+                                pairs.push((r.clone(), degree));
+                            }
+                        }
+
+                        pairs
+                    }
+                    S4OperatorKind::Union => left.into_iter().chain(right).collect(),
+                    _ => panic!("monitor: s4: unknown binary operator"),
+                }
+            }
+
+            // A zone (or complement) is not graded; so, it fully satisfies the
+            // formula, if at all.
+            _ => Monitor::evaluate(sample, zones, formula)
+                .into_iter()
+                .map(|region| (region, 1.0))
+                .collect(),
+        }
+    }
+
+    /// The degree to which two regions overlap.
+    ///
+    /// If the regions intersect (see [`Region::intersects`]), this is the area
+    /// of the intersection of their bounding boxes relative to the smaller of
+    /// the two (i.e., between 0 and 1). Otherwise, it is negative (even if
+    /// their bounding boxes touch), approaching -1 as the gap between them
+    /// grows relative to their size.
+    pub fn overlap(l: &Region, r: &Region) -> f64 {
+        let (a, b) = (l.bounds(), r.bounds());
+
+        if l.intersects(r) {
+            let width = a.max.x.min(b.max.x) - a.min.x.max(b.min.x);
+            let height = a.max.y.min(b.max.y) - a.min.y.max(b.min.y);
+
+            let smaller = a.area().min(b.area());
+
+            return match smaller > 0.0 {
+                true => (width.max(0.0) * height.max(0.0) / smaller).min(1.0),
+                false => 1.0,
+            };
+        }
+
+        let gap = Self::between(&DistanceKind::Edge, l, r, 1.0, 1.0);
+        let size = a.area().min(b.area()).sqrt();
+
+        match gap + size > 0.0 {
+            true => (-gap / (gap + size)).min(-f64::MIN_POSITIVE),
+            false => -1.0,
+        }
+    }
+
+    /// Evaluate the probability of each region of a formula against a sample
+    /// of annotations.
+    ///
+    /// The score of each annotation is taken as the probability that it exists,
+    /// independently of every other annotation. So, unlike
+    /// [`Monitor::evaluate`], this returns every region that the formula may
+    /// consider, along with the probability that it is considered. An
+    /// intersection is represented by each left region that intersects any
+    /// right region, whose probability is that it exists along with any of
+    /// the right regions it intersects. A zone (or
+    /// complement) is not uncertain; so, it is considered with a probability
+    /// of 1, if at all.
+    pub fn probability(
+        sample: &Sample,
+        zones: &Zones,
+        formula: &SpatialFormula,
+    ) -> Vec<(Region, f64)> {
+        match formula {
+            Node::Operand(Atom::Class(..)) => Monitor::evaluate(sample, zones, formula)
+                .into_iter()
+                .map(|region| {
+                    let score = match &region {
+                        Region::Annotation(_, annotation) => annotation.score,
+                        Region::Cuboid(_, annotation) => annotation.score,
+                        _ => 1.0,
+                    };
+
+                    (region, score.clamp(0.0, 1.0))
+                })
+                .collect(),
+            Node::UnaryExpr {
+                op:
+                    Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                        GeometricOperatorKind::Measure(kind, comparison),
+                    )),
+                child,
+            } => Monitor::probability(sample, zones, child)
+                .into_iter()
+                .filter(|(region, _)| {
+                    Self::measure(kind, &comparison.unit, region, sample.image())
+                        .is_some_and(|value| comparison.test(value))
+                })
+                .collect(),
+            Node::BinaryExpr {
+                op: Operator::SpatialOperator(SpatialOperatorKind::S4Operator(op)),
+                left,
+                right,
+            } => {
+                let left = Monitor::probability(sample, zones, left);
+                let right = Monitor::probability(sample, zones, right);
+
+                match op {
+                    S4OperatorKind::Intersection => {
+                        let mut regions = Vec::new();
+
+                        // A left region is considered once, however many right
+                        // regions it intersects.
+                        for (l, pl) in left.into_iter() {
+                            let mut intersects = false;
+                            let mut none = 1.0;
+
+                            for (_, pr) in right.iter().filter(|(r, _)| l.intersects(r)) {
+                                intersects = true;
+                                none *= 1.0 - pr;
+                            }
+
+                            if intersects {
+                                regions.push((l, pl * (1.0 - none)));
+                            }
+                        }
+
+                        regions
+                    }
+                    S4OperatorKind::Union => left.into_iter().chain(right).collect(),
+                    _ => panic!("monitor: s4: unknown binary operator"),
+                }
+            }
+            _ => Monitor::evaluate(sample, zones, formula)
+                .into_iter()
+                .map(|region| (region, 1.0))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ir::Node;
+    use crate::compiler::Compiler;
+    use crate::datastream::frame::sample::detections::{
+        Annotation, BoundingBox, DetectionRecord, Geometry, Image, ImageSource, Mask, Point,
+        Polygon,
+    };
+    use crate::datastream::frame::sample::Sample;
+    use crate::datastream::frame::Frame;
+    use crate::monitor;
+    use crate::monitor::zones::Zones;
+
+    use super::Monitor;
+
+    #[test]
+    fn shapes() {
+        let image = Image::new(ImageSource::File("cam.png".into()), 10.0, 10.0);
+        let mut record = DetectionRecord::new(String::from("cam"), 0.0, Some(image));
+
+        // A mask of the 2x2 pixels at the origin.
+        let mut bits = vec![false; 100];
+        for i in [0, 1, 10, 11] {
+            bits[i] = true;
+        }
+
+        let geometries = [
+            (
+                "triangle",
+                Geometry::Polygon(Polygon::new(vec![
+                    Point::new(0.0, 0.0),
+                    Point::new(10.0, 0.0),
+                    Point::new(0.0, 10.0),
+                ])),
+            ),
+            ("mask", Geometry::Mask(Mask::encode(10, 10, &bits))),
+            (
+                "car",
+                Geometry::BoundingBox(BoundingBox::new(
+                    Point::new(8.0, 8.0),
+                    Point::new(10.0, 10.0),
+                )),
+            ),
+        ];
+
+        for (label, geometry) in geometries {
+            record
+                .annotations
+                .entry(String::from(label))
+                .or_default()
+                .push(Annotation::new(String::from(label), 1.0, geometry));
+        }
+
+        let mut frame = Frame::new(0, 0.0);
+        frame.samples.push(Sample::ObjectDetection(record));
+
+        let evaluate = |formula: &str| match Compiler::new().compile_temporal(formula).unwrap() {
+            Node::Operand(formula) => monitor::Monitor::new().evaluate(&frame, &formula),
+            _ => unreachable!(),
+        };
+
+        // The area is that of the shape rather than of its bounding box.
+        assert!(evaluate("[<area>[:triangle:] < 60]"));
+        assert!(evaluate("[<area>[:mask:] < 5]"));
+
+        // The complement excludes only the pixels covered by the shape. So,
+        // the corner that the triangle leaves uncovered remains (i.e., 55 of
+        // the 100 pixels).
+        assert!(evaluate("[<area>(!([:triangle:])) > 50]"));
+        assert!(evaluate("[<area>(!([:triangle:])) < 60]"));
+        assert!(evaluate("[<area>(!([:mask:])) > 95]"));
+        assert!(evaluate("[<nonempty>([:car:] & !([:triangle:]))]"));
+        assert!(!evaluate("[<nonempty>([:mask:] & !([:mask:]))]"));
+        assert!(evaluate("[<area>(!(!([:triangle:]))) < 50]"));
+    }
+
+    #[test]
+    fn probability() {
+        let mut record = DetectionRecord::new(String::from("cam"), 0.0, None);
+
+        for (label, score, x) in [
+            ("car", 0.5, 0.0),
+            ("pedestrian", 1.0, 2.0),
+            ("pedestrian", 1.0, 4.0),
+        ] {
+            record
+                .annotations
+                .entry(String::from(label))
+                .or_default()
+                .push(Annotation::new(
+                    String::from(label),
+                    score,
+                    Geometry::BoundingBox(BoundingBox::new(
+                        Point::new(x, 0.0),
+                        Point::new(x + 5.0, 5.0),
+                    )),
+                ));
+        }
+
+        let sample = Sample::ObjectDetection(record);
+        let formula = match Compiler::new()
+            .compile_temporal("[<nonempty>([:car:] & [:pedestrian:])]")
+            .unwrap()
+        {
+            Node::Operand(formula) => formula,
+            _ => unreachable!(),
+        };
+
+        let child = match &formula {
+            Node::UnaryExpr { child, .. } => child,
+            _ => unreachable!(),
+        };
+
+        // The car is considered once, rather than once per pedestrian.
+        let regions = Monitor::probability(&sample, &Zones::new(), child);
+
+        assert_eq!(1, regions.len());
+        assert!((regions[0].1 - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty() {
+        let image = Image::new(ImageSource::File("cam.png".into()), 0.0, 10.0);
+        let mut record = DetectionRecord::new(String::from("cam"), 0.0, Some(image));
+
+        for (label, height) in [("car", 5.0), ("line", 0.0)] {
+            let bbox = BoundingBox::new(Point::new(0.0, 0.0), Point::new(5.0, height));
+
+            record
+                .annotations
+                .entry(String::from(label))
+                .or_default()
+                .push(Annotation::new(
+                    String::from(label),
+                    1.0,
+                    Geometry::BoundingBox(bbox),
+                ));
+        }
+
+        let mut frame = Frame::new(0, 0.0);
+        frame.samples.push(Sample::ObjectDetection(record));
+
+        let evaluate = |formula: &str| match Compiler::new().compile_temporal(formula).unwrap() {
+            Node::Operand(formula) => monitor::Monitor::new().evaluate(&frame, &formula),
+            _ => unreachable!(),
+        };
+
+        // An image without a width has no dimensions to normalize against.
+        assert!(!evaluate("[<width>[:car:] > 10%]"));
+        assert!(!evaluate("[<width>[:car:] < 10%]"));
+        assert!(!evaluate("[<cy>[:car:] < 100%]"));
+        assert!(evaluate("[<width>[:car:] > 1]"));
 
-        None
+        // A region without height has no aspect.
+        assert!(evaluate("[<aspect>[:car:] > 0.5]"));
+        assert!(!evaluate("[<aspect>[:line:] > 0]"));
+        assert!(!evaluate("[<aspect>[:line:] < 1000]"));
     }
 }
@@ -1,15 +1,16 @@
-use std::collections::HashMap;
+use crate::compiler::ir::ast::{Atom, SpatialFormula};
+use crate::compiler::ir::{
+    FolOperatorKind, GeometricOperatorKind, Node, Operator, S4uOperatorKind, SpatialOperatorKind,
+};
+use crate::datastream::frame::sample::Sample;
 
-use crate::compiler::ir::ast::SpatialFormula;
-use crate::compiler::ir::{FolOperatorKind, Node, Operator, S4uOperatorKind, SpatialOperatorKind};
-use crate::datastream::frame::sample::detections::Annotation;
-
-use super::s4;
+use super::s4::{self, Region};
+use super::{Context, ABSENT};
 
 /// A monitor for evaluating S4u formulas.
 ///
-/// This monitor evaluates against a series of object detection obtained from the
-/// perception stream.
+/// This monitor evaluates against a sample of object detections (2D or 3D)
+/// obtained from the perception stream.
 #[derive(Default)]
 pub struct Monitor {}
 
@@ -18,32 +19,40 @@ impl Monitor {
         Self {}
     }
 
-    /// Evaluate formula satisfaction against set of annotations.
+    /// Evaluate formula satisfaction against a sample of annotations.
     ///
     /// This returns is a boolean result. If true, the formula is satisifed;
     /// else, if false, then it is not satisfied.
-    pub fn evaluate(
-        detections: &HashMap<String, Vec<Annotation>>,
-        formula: &SpatialFormula,
-    ) -> bool {
+    pub fn evaluate(sample: &Sample, context: &Context, formula: &SpatialFormula) -> bool {
         match formula {
-            Node::Operand(label) => {
-                if detections.get(label).is_some() {
-                    return true;
-                }
-
-                false
+            Node::Operand(Atom::Class(label)) => match sample {
+                Sample::ObjectDetection(record) => record.annotations.contains_key(label),
+                Sample::CuboidDetection(record) => record.annotations.contains_key(label),
+                Sample::Signal(..) | Sample::Scene(..) => false,
+            },
+            Node::Operand(Atom::Tag(label, comparison)) => context
+                .tags
+                .get(label.as_str())
+                .is_some_and(|score| comparison.as_ref().is_none_or(|c| c.test(*score))),
+            Node::Operand(Atom::Signal(name, index, comparison)) => context
+                .signals
+                .get(name.as_str())
+                .and_then(|signal| signal.value(*index))
+                .is_some_and(|value| comparison.test(value)),
+            Node::Operand(Atom::Zone(..)) => {
+                !s4::Monitor::evaluate(sample, context.zones, formula).is_empty()
             }
+            Node::Operand(Atom::Any) => true,
             Node::UnaryExpr { op, child } => match op {
                 Operator::SpatialOperator(op) => match op {
                     SpatialOperatorKind::S4uOperator(op) => match op {
                         S4uOperatorKind::NonEmpty => {
-                            !s4::Monitor::evaluate(detections, child).is_empty()
+                            !s4::Monitor::evaluate(sample, context.zones, child).is_empty()
                         }
                     },
                     SpatialOperatorKind::FolOperator(op) => match op {
                         FolOperatorKind::Negation => {
-                            let res = Monitor::evaluate(detections, child);
+                            let res = Monitor::evaluate(sample, context, child);
                             !res
                         }
                         _ => panic!("monitor: s4u: unrecognized unary FOL operator"),
@@ -52,22 +61,285 @@ impl Monitor {
                 },
                 _ => panic!("monitor: s4u: unrecognized unary operator"),
             },
-            Node::BinaryExpr { op, left, right } => {
-                let left = Monitor::evaluate(detections, left);
-                let right = Monitor::evaluate(detections, right);
-
-                match op {
-                    Operator::SpatialOperator(kind) => match kind {
-                        SpatialOperatorKind::FolOperator(kind) => match kind {
-                            FolOperatorKind::Conjunction => left && right,
-                            FolOperatorKind::Disjunction => left || right,
-                            _ => panic!("monitor: unkown FOL operator {:#?}", kind),
+            Node::BinaryExpr { op, left, right } => match op {
+                Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                    GeometricOperatorKind::Distance(kind, comparison),
+                )) => {
+                    // The operands of a distance are sets of regions. So, they
+                    // are evaluated by the S4 monitor, accordingly.
+                    let left = s4::Monitor::evaluate(sample, context.zones, left);
+                    let right = s4::Monitor::evaluate(sample, context.zones, right);
+
+                    s4::Monitor::distance(kind, &comparison.unit, &left, &right, sample.image())
+                        .is_some_and(|distance| comparison.test(distance))
+                }
+                _ => {
+                    let left = Monitor::evaluate(sample, context, left);
+                    let right = Monitor::evaluate(sample, context, right);
+
+                    match op {
+                        Operator::SpatialOperator(kind) => match kind {
+                            SpatialOperatorKind::FolOperator(kind) => match kind {
+                                FolOperatorKind::Conjunction => left && right,
+                                FolOperatorKind::Disjunction => left || right,
+                                _ => panic!("monitor: unkown FOL operator {:#?}", kind),
+                            },
+                            _ => panic!("monitor: unknown binary operator {:#?}", kind),
                         },
-                        _ => panic!("monitor: unknown binary operator {:#?}", kind),
-                    },
-                    _ => panic!("monitor: unknown binary operator {:#?}", op),
+                        _ => panic!("monitor: unknown binary operator {:#?}", op),
+                    }
                 }
+            },
+        }
+    }
+
+    /// Find the regions that witness formula satisfaction against a sample.
+    ///
+    /// This returns [`None`] if the formula is not satisfied (see
+    /// [`Monitor::evaluate`]). Otherwise, the regions that satisfy it are
+    /// returned (e.g., the annotations of a class, or the closest pair of a
+    /// distance). Operands that are not regions (e.g., signals, tags, and
+    /// negations) are satisfied without any.
+    pub fn witness(
+        sample: &Sample,
+        context: &Context,
+        formula: &SpatialFormula,
+    ) -> Option<Vec<Region>> {
+        let nonempty = |regions: Vec<Region>| (!regions.is_empty()).then_some(regions);
+
+        match formula {
+            Node::Operand(Atom::Class(..)) | Node::Operand(Atom::Zone(..)) => {
+                nonempty(s4::Monitor::evaluate(sample, context.zones, formula))
+            }
+            Node::Operand(Atom::Tag(..))
+            | Node::Operand(Atom::Signal(..))
+            | Node::Operand(Atom::Any) => {
+                Monitor::evaluate(sample, context, formula).then(Vec::new)
             }
+            Node::UnaryExpr {
+                op:
+                    Operator::SpatialOperator(SpatialOperatorKind::S4uOperator(
+                        S4uOperatorKind::NonEmpty,
+                    )),
+                child,
+            } => nonempty(s4::Monitor::evaluate(sample, context.zones, child)),
+            Node::UnaryExpr { .. } => Monitor::evaluate(sample, context, formula).then(Vec::new),
+            Node::BinaryExpr { op, left, right } => match op {
+                Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                    GeometricOperatorKind::Distance(kind, comparison),
+                )) => {
+                    let left = s4::Monitor::evaluate(sample, context.zones, left);
+                    let right = s4::Monitor::evaluate(sample, context.zones, right);
+
+                    s4::Monitor::closest(kind, &comparison.unit, &left, &right, sample.image())
+                        .filter(|(distance, ..)| comparison.test(*distance))
+                        .map(|(_, l, r)| vec![l.clone(), r.clone()])
+                }
+                Operator::SpatialOperator(SpatialOperatorKind::FolOperator(kind)) => {
+                    let left = Monitor::witness(sample, context, left);
+                    let right = Monitor::witness(sample, context, right);
+
+                    match (kind, left, right) {
+                        (FolOperatorKind::Conjunction, Some(l), Some(r))
+                        | (FolOperatorKind::Disjunction, Some(l), Some(r)) => {
+                            Some(l.into_iter().chain(r).collect())
+                        }
+                        (FolOperatorKind::Conjunction, ..) => None,
+                        (FolOperatorKind::Disjunction, l, r) => l.or(r),
+                        _ => panic!("monitor: unkown FOL operator {:#?}", kind),
+                    }
+                }
+                _ => panic!("monitor: unknown binary operator {:#?}", op),
+            },
         }
     }
+
+    /// Evaluate the robustness of a formula against a sample of annotations.
+    ///
+    /// This is the degree to which the formula is satisfied, which is
+    /// non-negative if, and only if, it is satisfied (see [`Monitor::evaluate`]).
+    /// The degree of a region (see [`s4::Monitor::robustness`]), tag, or
+    /// comparison is combined as the negation (`!`), minimum (`&`), and maximum
+    /// (`|`) of those of its operands. A region, tag, or signal that does not
+    /// exist has a degree of [`ABSENT`].
+    pub fn robustness(sample: &Sample, context: &Context, formula: &SpatialFormula) -> f64 {
+        let strongest = |degrees: Vec<(Region, f64)>| {
+            degrees
+                .into_iter()
+                .map(|(_, degree)| degree)
+                .reduce(f64::max)
+                .unwrap_or(ABSENT)
+        };
+
+        match formula {
+            Node::Operand(Atom::Class(..)) | Node::Operand(Atom::Zone(..)) => {
+                strongest(s4::Monitor::robustness(sample, context.zones, formula))
+            }
+            Node::Operand(Atom::Tag(label, comparison)) => context
+                .tags
+                .get(label.as_str())
+                .map(|score| {
+                    comparison
+                        .as_ref()
+                        .map_or(score.max(0.0), |c| c.degree(*score))
+                })
+                .unwrap_or(ABSENT),
+            Node::Operand(Atom::Signal(name, index, comparison)) => context
+                .signals
+                .get(name.as_str())
+                .and_then(|signal| signal.value(*index))
+                .map(|value| comparison.degree(value))
+                .unwrap_or(ABSENT),
+            Node::Operand(Atom::Any) => f64::INFINITY,
+            Node::UnaryExpr { op, child } => match op {
+                Operator::SpatialOperator(SpatialOperatorKind::S4uOperator(
+                    S4uOperatorKind::NonEmpty,
+                )) => strongest(s4::Monitor::robustness(sample, context.zones, child)),
+                Operator::SpatialOperator(SpatialOperatorKind::FolOperator(
+                    FolOperatorKind::Negation,
+                )) => match Monitor::robustness(sample, context, child) {
+                    // A degree of zero satisfies its formula; so, its negation
+                    // must not (i.e., unlike -0).
+                    degree if degree >= 0.0 => -degree.max(f64::MIN_POSITIVE),
+                    degree => -degree,
+                },
+                _ => panic!("monitor: s4u: unrecognized unary operator"),
+            },
+            Node::BinaryExpr { op, left, right } => match op {
+                Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                    GeometricOperatorKind::Distance(kind, comparison),
+                )) => {
+                    let left = s4::Monitor::robustness(sample, context.zones, left);
+                    let right = s4::Monitor::robustness(sample, context.zones, right);
+
+                    let mut closest: Option<(f64, f64)> = None;
+                    let mut degree: Option<f64> = None;
+
+                    // A region is never compared against itself.
+                    for (l, dl) in left.iter() {
+                        for (r, dr) in right.iter().filter(|(r, _)| !r.is(l)) {
+                            let image = sample.image();
+
+                            if let Some(distance) =
+                                s4::Monitor::gap(kind, &comparison.unit, l, r, image)
+                            {
+                                let d = dl.min(*dr);
+
+                                // As with `evaluate`, only the closest pair of
+                                // regions that satisfy their formulas is
+                                // compared; otherwise, the pair is not.
+                                if d >= 0.0 && closest.is_none_or(|(c, _)| distance < c) {
+                                    closest = Some((distance, d));
+                                }
+
+                                let d = d.min(comparison.degree(distance));
+                                degree = Some(degree.map_or(d, |degree| degree.max(d)));
+                            }
+                        }
+                    }
+
+                    match closest {
+                        Some((distance, d)) => d.min(comparison.degree(distance)),
+                        None => degree.unwrap_or(ABSENT),
+                    }
+                }
+                Operator::SpatialOperator(SpatialOperatorKind::FolOperator(kind)) => {
+                    let left = Monitor::robustness(sample, context, left);
+                    let right = Monitor::robustness(sample, context, right);
+
+                    match kind {
+                        FolOperatorKind::Conjunction => left.min(right),
+                        FolOperatorKind::Disjunction => left.max(right),
+                        _ => panic!("monitor: unkown FOL operator {:#?}", kind),
+                    }
+                }
+                _ => panic!("monitor: unknown binary operator {:#?}", op),
+            },
+        }
+    }
+
+    /// Evaluate the probability that a formula is satisfied by a sample of
+    /// annotations.
+    ///
+    /// The probability of each region is that of [`s4::Monitor::probability`],
+    /// and a formula over several regions (or several formulas) is satisfied
+    /// with the probability that any (or each) of them is, assuming that they
+    /// are independent. The score of a tag is taken as its probability, while
+    /// a signal (or a tag compared against its score) is not uncertain.
+    pub fn probability(sample: &Sample, context: &Context, formula: &SpatialFormula) -> f64 {
+        match formula {
+            Node::Operand(Atom::Class(..)) | Node::Operand(Atom::Zone(..)) => {
+                self::any(s4::Monitor::probability(sample, context.zones, formula))
+            }
+            Node::Operand(Atom::Tag(label, None)) => match context.outcomes.get(label.as_str()) {
+                Some(present) => f64::from(u8::from(*present)),
+                None => context
+                    .tags
+                    .get(label.as_str())
+                    .map_or(0.0, |score| score.clamp(0.0, 1.0)),
+            },
+            Node::Operand(Atom::Tag(..)) => match Monitor::evaluate(sample, context, formula) {
+                true => 1.0,
+                false => 0.0,
+            },
+            Node::Operand(Atom::Signal(..)) => match Monitor::evaluate(sample, context, formula) {
+                true => 1.0,
+                false => 0.0,
+            },
+            Node::Operand(Atom::Any) => 1.0,
+            Node::UnaryExpr { op, child } => match op {
+                Operator::SpatialOperator(SpatialOperatorKind::S4uOperator(
+                    S4uOperatorKind::NonEmpty,
+                )) => self::any(s4::Monitor::probability(sample, context.zones, child)),
+                Operator::SpatialOperator(SpatialOperatorKind::FolOperator(
+                    FolOperatorKind::Negation,
+                )) => 1.0 - Monitor::probability(sample, context, child),
+                _ => panic!("monitor: s4u: unrecognized unary operator"),
+            },
+            Node::BinaryExpr { op, left, right } => match op {
+                Operator::SpatialOperator(SpatialOperatorKind::GeometricOperator(
+                    GeometricOperatorKind::Distance(kind, comparison),
+                )) => {
+                    let left = s4::Monitor::probability(sample, context.zones, left);
+                    let right = s4::Monitor::probability(sample, context.zones, right);
+
+                    let mut pairs = Vec::new();
+
+                    // A region is never compared against itself.
+                    for (l, pl) in left.iter() {
+                        for (r, pr) in right.iter().filter(|(r, _)| !r.is(l)) {
+                            let image = sample.image();
+
+                            if s4::Monitor::gap(kind, &comparison.unit, l, r, image)
+                                .is_some_and(|distance| comparison.test(distance))
+                            {
+                                pairs.push((l.clone(), pl * pr));
+                            }
+                        }
+                    }
+
+                    self::any(pairs)
+                }
+                Operator::SpatialOperator(SpatialOperatorKind::FolOperator(kind)) => {
+                    let left = Monitor::probability(sample, context, left);
+                    let right = Monitor::probability(sample, context, right);
+
+                    match kind {
+                        FolOperatorKind::Conjunction => left * right,
+                        FolOperatorKind::Disjunction => left + right - left * right,
+                        _ => panic!("monitor: unkown FOL operator {:#?}", kind),
+                    }
+                }
+                _ => panic!("monitor: unknown binary operator {:#?}", op),
+            },
+        }
+    }
+}
+
+/// The probability that any of a set of independent regions is considered.
+fn any(regions: Vec<(Region, f64)>) -> f64 {
+    1.0 - regions
+        .into_iter()
+        .map(|(_, probability)| 1.0 - probability)
+        .product::<f64>()
 }
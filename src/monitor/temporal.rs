@@ -0,0 +1,856 @@
+//! An online monitor of Metric Temporal Logic (MTL) formulas.
+//!
+//! The operands of an MTL formula are spatial formulas, which are evaluated at
+//! each frame by the [`Monitor`]. The formula itself is then evaluated at each
+//! frame (i.e., a verdict per frame) from the truth of its operands at that
+//! frame and at others within the intervals of its temporal operators.
+//!
+//! A past-time operator (e.g., `O`) is decided as soon as a frame is seen. A
+//! future-time operator (e.g., `F`) is decided once a frame satisfies it or
+//! once its interval has passed. So, the verdict of a frame may be delayed by
+//! the intervals of its future-time operators, and is only ever reported once
+//! (and in order). At the end of a stream, any frame beyond it is taken as not
+//! satisfying any formula.
+
+use std::collections::VecDeque;
+
+use crate::compiler::ir::ast::{SpatialFormula, TemporalFormula};
+use crate::compiler::ir::{Node, Operator, TemporalInterval, TemporalOperatorKind, TimeUnitKind};
+use crate::datastream::frame::Frame;
+use crate::matcher::filters::Filter;
+
+use super::Monitor;
+
+/// The verdict of an MTL formula at a frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Verdict {
+    /// The position of the [`Frame`] within the stream.
+    pub position: usize,
+
+    /// The [`Frame::index`] of the [`Frame`].
+    pub index: usize,
+
+    /// Whether the formula holds at the [`Frame`].
+    pub holds: bool,
+}
+
+/// A sub-formula of an MTL formula, along with its truth at each frame kept.
+struct Slot {
+    kind: SlotKind,
+
+    /// The truth of the sub-formula at each frame kept, if decided.
+    values: VecDeque<Option<bool>>,
+
+    /// The position of the first frame where the sub-formula is undecided.
+    pending: usize,
+}
+
+enum SlotKind {
+    /// A spatial formula, along with the state of its filter (if any).
+    Operand(SpatialFormula, Option<Filter>),
+    Unary(TemporalOperatorKind, usize),
+    Binary(TemporalOperatorKind, usize, usize),
+}
+
+/// The online monitor of an MTL formula.
+///
+/// A formula of the form `G psi` (i.e., unbounded) holds at a frame if `psi`
+/// holds at it and at every frame after it. So, a violation decides every frame
+/// up to it at once, whereas the others are only decided at the end of the
+/// stream. With [`TemporalMonitor::violations`], the verdict of each frame is
+/// that of `psi`, instead, such that a violation is reported at the frame it
+/// occurs.
+pub struct TemporalMonitor {
+    monitor: Monitor,
+
+    /// The sub-formulas, where each is after its operands (i.e., post-order).
+    slots: Vec<Slot>,
+
+    /// The timestamp and [`Frame::index`] of each frame kept.
+    frames: VecDeque<(f64, usize)>,
+
+    /// The position of the first frame kept.
+    offset: usize,
+
+    /// The position of the next verdict to report.
+    next: usize,
+
+    finished: bool,
+}
+
+impl TemporalMonitor {
+    /// Create a new [`TemporalMonitor`] of an MTL formula.
+    pub fn new(formula: &TemporalFormula) -> Self {
+        let mut monitor = TemporalMonitor {
+            monitor: Monitor::new(),
+            slots: Vec::new(),
+            frames: VecDeque::new(),
+            offset: 0,
+            next: 0,
+            finished: false,
+        };
+
+        monitor.build(formula);
+        monitor
+    }
+
+    /// Set the [`Monitor`] used to evaluate spatial formulas (e.g., with zones).
+    pub fn monitor(mut self, monitor: Monitor) -> Self {
+        self.monitor = monitor;
+        self
+    }
+
+    /// Report the verdict of `psi` at each frame for a formula `G psi` (i.e.,
+    /// unbounded), rather than that of `G psi`.
+    ///
+    /// This reports each violation of an invariant at the frame it occurs. So,
+    /// the [`TemporalMonitor`] should not have advanced yet.
+    pub fn violations(mut self) -> Self {
+        let root = self.slots.len() - 1;
+
+        if self.is_invariant(root) {
+            self.slots.pop();
+        }
+
+        self
+    }
+
+    /// Advance the [`TemporalMonitor`] by the next [`Frame`] of the stream.
+    ///
+    /// This returns the verdicts decided by the [`Frame`], in order.
+    pub fn advance(&mut self, frame: &Frame) -> Vec<Verdict> {
+        self.frames.push_back((frame.timestamp, frame.index));
+
+        for slot in self.slots.iter_mut() {
+            let value = match &mut slot.kind {
+                SlotKind::Operand(formula, filter) => {
+                    let holds = self.monitor.evaluate(frame, formula);
+
+                    Some(match filter {
+                        Some(filter) => filter.update(holds, frame.timestamp),
+                        None => holds,
+                    })
+                }
+                _ => None,
+            };
+
+            slot.values.push_back(value);
+        }
+
+        self.update()
+    }
+
+    /// Finish the stream, deciding the verdict of every remaining frame.
+    pub fn finish(&mut self) -> Vec<Verdict> {
+        self.finished = true;
+        self.update()
+    }
+
+    /// Decide the truth of each sub-formula at each frame kept (where
+    /// possible), and report the verdicts decided.
+    fn update(&mut self) -> Vec<Verdict> {
+        let end = self.offset + self.frames.len();
+
+        for i in 0..self.slots.len() {
+            let start = self.slots[i].pending;
+
+            // An unbounded `G` depends on its own truth at the next frame, so
+            // it is decided from the last frame back.
+            let backward = self.is_invariant(i);
+
+            for k in 0..end - start {
+                let position = match backward {
+                    true => end - 1 - k,
+                    false => start + k,
+                };
+
+                if self.get(i, position).is_none() {
+                    let value = self.decide(i, position);
+                    self.slots[i].values[position - self.offset] = value;
+                }
+            }
+
+            let slot = &mut self.slots[i];
+
+            while slot.pending < end && slot.values[slot.pending - self.offset].is_some() {
+                slot.pending += 1;
+            }
+        }
+
+        let root = self.slots.len() - 1;
+        let mut verdicts = Vec::new();
+
+        while let Some(holds) = self.get(root, self.next) {
+            verdicts.push(Verdict {
+                position: self.next,
+                index: self.frames[self.next - self.offset].1,
+                holds,
+            });
+
+            self.next += 1;
+        }
+
+        self.prune();
+
+        verdicts
+    }
+
+    /// Decide the truth of a sub-formula at a frame, if possible.
+    fn decide(&self, i: usize, position: usize) -> Option<bool> {
+        match &self.slots[i].kind {
+            SlotKind::Operand(..) => self.get(i, position),
+            SlotKind::Unary(kind, child) => {
+                let child = *child;
+
+                match kind {
+                    TemporalOperatorKind::Negation => self.get(child, position).map(|v| !v),
+                    TemporalOperatorKind::Next => match position + 1 < self.end() {
+                        true => self.get(child, position + 1),
+                        false if self.finished => Some(false),
+                        false => None,
+                    },
+                    TemporalOperatorKind::Previous => match position {
+                        0 => Some(false),
+                        _ => self.get(child, position - 1),
+                    },
+                    TemporalOperatorKind::Eventually(interval) => {
+                        self.future(child, position, interval, true)
+                    }
+                    TemporalOperatorKind::Globally(interval) if interval.is_unbounded() => {
+                        and(self.get(child, position), self.after(i, position))
+                    }
+                    TemporalOperatorKind::Globally(interval) => {
+                        self.future(child, position, interval, false)
+                    }
+                    TemporalOperatorKind::Once(interval) if interval.is_unbounded() => {
+                        or(self.before(i, position), self.get(child, position))
+                    }
+                    TemporalOperatorKind::Historically(interval) if interval.is_unbounded() => {
+                        match position {
+                            0 => self.get(child, position),
+                            _ => and(self.get(i, position - 1), self.get(child, position)),
+                        }
+                    }
+                    TemporalOperatorKind::Once(interval) => {
+                        self.past(child, position, interval, true)
+                    }
+                    TemporalOperatorKind::Historically(interval) => {
+                        self.past(child, position, interval, false)
+                    }
+                    _ => None,
+                }
+            }
+            SlotKind::Binary(kind, left, right) => {
+                let (left, right) = (*left, *right);
+
+                match kind {
+                    TemporalOperatorKind::Conjunction => {
+                        and(self.get(left, position), self.get(right, position))
+                    }
+                    TemporalOperatorKind::Disjunction => {
+                        or(self.get(left, position), self.get(right, position))
+                    }
+                    TemporalOperatorKind::Implication => or(
+                        self.get(left, position).map(|v| !v),
+                        self.get(right, position),
+                    ),
+                    TemporalOperatorKind::Until(interval) => {
+                        self.until(left, right, position, interval)
+                    }
+                    TemporalOperatorKind::Since(interval) if interval.is_unbounded() => or(
+                        self.get(right, position),
+                        and(self.get(left, position), self.before(i, position)),
+                    ),
+                    TemporalOperatorKind::Since(interval) => {
+                        self.since(left, right, position, interval)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Decide `F` (if `exists`) or `G` (otherwise) of a sub-formula at a frame.
+    fn future(
+        &self,
+        child: usize,
+        position: usize,
+        interval: &TemporalInterval,
+        exists: bool,
+    ) -> Option<bool> {
+        let mut unknown = false;
+        let mut passed = false;
+
+        for j in position..self.end() {
+            let distance = self.distance(position, j, interval);
+
+            if interval.exceeds(distance) {
+                passed = true;
+                break;
+            }
+
+            if interval.contains(distance) {
+                match self.get(child, j) {
+                    Some(value) if value == exists => return Some(exists),
+                    Some(_) => (),
+                    None => unknown = true,
+                }
+            }
+        }
+
+        if unknown || !(passed || self.finished) {
+            return None;
+        }
+
+        Some(!exists)
+    }
+
+    /// Decide `O` (if `exists`) or `H` (otherwise) of a sub-formula at a frame.
+    fn past(
+        &self,
+        child: usize,
+        position: usize,
+        interval: &TemporalInterval,
+        exists: bool,
+    ) -> Option<bool> {
+        let mut unknown = false;
+
+        for j in (self.offset..=position).rev() {
+            let distance = self.distance(j, position, interval);
+
+            if interval.exceeds(distance) {
+                break;
+            }
+
+            if interval.contains(distance) {
+                match self.get(child, j) {
+                    Some(value) if value == exists => return Some(exists),
+                    Some(_) => (),
+                    None => unknown = true,
+                }
+            }
+        }
+
+        match unknown {
+            true => None,
+            false => Some(!exists),
+        }
+    }
+
+    /// Decide `left U right` at a frame.
+    fn until(
+        &self,
+        left: usize,
+        right: usize,
+        position: usize,
+        interval: &TemporalInterval,
+    ) -> Option<bool> {
+        let mut unknown = false;
+        let mut passed = false;
+
+        // Whether `left` held at every frame from the `position` so far.
+        let mut holds = Some(true);
+
+        for j in position..self.end() {
+            let distance = self.distance(position, j, interval);
+
+            if interval.exceeds(distance) {
+                passed = true;
+                break;
+            }
+
+            if interval.contains(distance) {
+                match and(holds, self.get(right, j)) {
+                    Some(true) => return Some(true),
+                    Some(false) => (),
+                    None => unknown = true,
+                }
+            }
+
+            holds = and(holds, self.get(left, j));
+
+            if holds == Some(false) {
+                passed = true;
+                break;
+            }
+        }
+
+        if unknown || !(passed || self.finished) {
+            return None;
+        }
+
+        Some(false)
+    }
+
+    /// Decide `left S right` at a frame.
+    fn since(
+        &self,
+        left: usize,
+        right: usize,
+        position: usize,
+        interval: &TemporalInterval,
+    ) -> Option<bool> {
+        let mut unknown = false;
+
+        // Whether `left` held at every frame after `j` up to the `position`.
+        let mut holds = Some(true);
+
+        for j in (self.offset..=position).rev() {
+            let distance = self.distance(j, position, interval);
+
+            if interval.exceeds(distance) {
+                break;
+            }
+
+            if interval.contains(distance) {
+                match and(holds, self.get(right, j)) {
+                    Some(true) => return Some(true),
+                    Some(false) => (),
+                    None => unknown = true,
+                }
+            }
+
+            holds = and(holds, self.get(left, j));
+
+            if holds == Some(false) {
+                break;
+            }
+        }
+
+        match unknown {
+            true => None,
+            false => Some(false),
+        }
+    }
+
+    /// The truth of a sub-formula at the frame before a position, where the
+    /// frame before the stream holds no formula.
+    fn before(&self, i: usize, position: usize) -> Option<bool> {
+        match position {
+            0 => Some(false),
+            _ => self.get(i, position - 1),
+        }
+    }
+
+    /// The truth of an unbounded `G` at the frame after a position, where the
+    /// frame after the stream holds it.
+    fn after(&self, i: usize, position: usize) -> Option<bool> {
+        match position + 1 < self.end() {
+            true => self.get(i, position + 1),
+            false if self.finished => Some(true),
+            false => None,
+        }
+    }
+
+    /// Whether a sub-formula is of the form `G psi` (i.e., unbounded).
+    fn is_invariant(&self, i: usize) -> bool {
+        matches!(
+            &self.slots[i].kind,
+            SlotKind::Unary(TemporalOperatorKind::Globally(interval), _)
+                if interval.is_unbounded()
+        )
+    }
+
+    /// The truth of a sub-formula at a frame, if kept and decided.
+    fn get(&self, i: usize, position: usize) -> Option<bool> {
+        let index = position.checked_sub(self.offset)?;
+        self.slots[i].values.get(index).copied().flatten()
+    }
+
+    /// The position after the last frame seen.
+    fn end(&self) -> usize {
+        self.offset + self.frames.len()
+    }
+
+    /// The distance from one frame to a later one, in the unit of an interval.
+    fn distance(&self, from: usize, to: usize, interval: &TemporalInterval) -> f64 {
+        match interval.unit {
+            TimeUnitKind::Frames => (to - from) as f64,
+            TimeUnitKind::Seconds => {
+                self.frames[to - self.offset].0 - self.frames[from - self.offset].0
+            }
+        }
+    }
+
+    /// Remove the frames that no verdict depends on any longer.
+    ///
+    /// A frame is kept while any sub-formula is undecided at it, or while a
+    /// sub-formula to decide may still look back to it (e.g., by `O`). So, the
+    /// earliest frame each sub-formula needs is found from the formula down.
+    fn prune(&mut self) {
+        let root = self.slots.len() - 1;
+
+        let mut needs = vec![usize::MAX; self.slots.len()];
+        needs[root] = self.next;
+
+        for i in (0..self.slots.len()).rev() {
+            let need = needs[i].min(self.slots[i].pending);
+            needs[i] = need;
+
+            match &self.slots[i].kind {
+                SlotKind::Operand(..) => (),
+                SlotKind::Unary(kind, child) => {
+                    let (own, reach) = self.reach(kind, need, self.slots[i].pending);
+
+                    needs[i] = own;
+                    needs[*child] = needs[*child].min(reach);
+                }
+                SlotKind::Binary(kind, left, right) => {
+                    let (own, reach) = self.reach(kind, need, self.slots[i].pending);
+
+                    needs[i] = own;
+                    needs[*left] = needs[*left].min(reach);
+                    needs[*right] = needs[*right].min(reach);
+                }
+            }
+        }
+
+        let earliest = needs.into_iter().min().unwrap_or(self.offset);
+
+        while self.offset < earliest && !self.frames.is_empty() {
+            self.frames.pop_front();
+
+            for slot in self.slots.iter_mut() {
+                slot.values.pop_front();
+            }
+
+            self.offset += 1;
+        }
+    }
+
+    /// The earliest frames needed to decide an operator from a frame onward
+    /// (where it is decided before its `pending` frame), of the operator itself
+    /// and of its operands, respectively.
+    fn reach(
+        &self,
+        kind: &TemporalOperatorKind,
+        position: usize,
+        pending: usize,
+    ) -> (usize, usize) {
+        match kind {
+            TemporalOperatorKind::Previous => (position, position.saturating_sub(1)),
+            TemporalOperatorKind::Once(interval)
+            | TemporalOperatorKind::Historically(interval)
+            | TemporalOperatorKind::Since(interval)
+                if interval.is_unbounded() =>
+            {
+                // Only the frame before the first undecided one is needed.
+                (position.min(pending.saturating_sub(1)), position)
+            }
+            TemporalOperatorKind::Once(interval)
+            | TemporalOperatorKind::Historically(interval)
+            | TemporalOperatorKind::Since(interval) => {
+                // A frame not yet seen is at least as late as the last one.
+                let Some(last) = self.end().checked_sub(1) else {
+                    return (position, position);
+                };
+
+                let reference = position.min(last);
+                let mut earliest = reference;
+
+                // Without an upper bound, the operand is a running summary
+                // (see `build`), so only the latest frame within the interval
+                // is needed, instead of every frame before it.
+                let done = |earliest: usize| match interval.max {
+                    Some(_) => interval.exceeds(self.distance(earliest - 1, reference, interval)),
+                    None => interval.contains(self.distance(earliest, reference, interval)),
+                };
+
+                while earliest > self.offset && !done(earliest) {
+                    earliest -= 1;
+                }
+
+                (position, earliest)
+            }
+            _ => (position, position),
+        }
+    }
+
+    /// Add the [`Slot`] of each sub-formula of an MTL formula (i.e., in
+    /// post-order), and return the index of the [`Slot`] of the formula.
+    ///
+    /// A past-time operator without an upper bound (e.g., `O[2,]`) would need
+    /// every frame since the start of the stream. So, its operand is instead
+    /// the same operator unbounded (e.g., `O`), which summarizes every frame
+    /// up to each, such that only the latest frame within the interval is
+    /// needed (e.g., `O[2,] a` holds where `O a` held 2 frames before).
+    fn build(&mut self, formula: &TemporalFormula) -> usize {
+        let kind = match formula {
+            Node::Operand(formula) => {
+                let (filter, _) = Filter::split(formula);
+                SlotKind::Operand(formula.clone(), filter.cloned().map(Filter::new))
+            }
+            Node::UnaryExpr { op, child } => {
+                let child = self.build(child);
+
+                match kind(op) {
+                    TemporalOperatorKind::Once(interval) if summarized(&interval) => {
+                        let summary = TemporalOperatorKind::Once(TemporalInterval::unbounded());
+                        let summary = self.push(SlotKind::Unary(summary, child));
+
+                        SlotKind::Unary(TemporalOperatorKind::Once(interval), summary)
+                    }
+                    TemporalOperatorKind::Historically(interval) if summarized(&interval) => {
+                        let summary =
+                            TemporalOperatorKind::Historically(TemporalInterval::unbounded());
+                        let summary = self.push(SlotKind::Unary(summary, child));
+
+                        SlotKind::Unary(TemporalOperatorKind::Historically(interval), summary)
+                    }
+                    kind => SlotKind::Unary(kind, child),
+                }
+            }
+            Node::BinaryExpr { op, left, right } => {
+                let left = self.build(left);
+                let right = self.build(right);
+
+                match kind(op) {
+                    TemporalOperatorKind::Since(interval) if summarized(&interval) => {
+                        let summary = TemporalOperatorKind::Since(TemporalInterval::unbounded());
+                        let summary = self.push(SlotKind::Binary(summary, left, right));
+
+                        SlotKind::Binary(TemporalOperatorKind::Since(interval), left, summary)
+                    }
+                    kind => SlotKind::Binary(kind, left, right),
+                }
+            }
+        };
+
+        self.push(kind)
+    }
+
+    /// Add a [`Slot`], and return its index.
+    fn push(&mut self, kind: SlotKind) -> usize {
+        self.slots.push(Slot {
+            kind,
+            values: VecDeque::new(),
+            pending: 0,
+        });
+
+        self.slots.len() - 1
+    }
+}
+
+/// The [`TemporalOperatorKind`] of an [`Operator`] of an MTL formula.
+fn kind(op: &Operator) -> TemporalOperatorKind {
+    match op {
+        Operator::TemporalOperator(kind) => kind.clone(),
+        _ => unreachable!("an MTL formula only has temporal operators"),
+    }
+}
+
+/// Check whether a past-time operator over an interval is decided from a
+/// running summary of its operand (i.e., it has only a lower bound).
+fn summarized(interval: &TemporalInterval) -> bool {
+    interval.max.is_none() && !interval.is_unbounded()
+}
+
+/// The conjunction of two (possibly undecided) truths.
+fn and(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+/// The disjunction of two (possibly undecided) truths.
+fn or(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+    use crate::datastream::frame::sample::detections::{
+        Annotation, BoundingBox, DetectionRecord, Geometry, Point,
+    };
+    use crate::datastream::frame::sample::Sample;
+    use crate::datastream::frame::Frame;
+
+    use super::TemporalMonitor;
+
+    fn frame(index: usize, labels: &[&str]) -> Frame {
+        let mut record = DetectionRecord::new(String::from("cam"), index as f64, None);
+
+        for label in labels {
+            let bbox = BoundingBox::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+            let annotation = Annotation::new(label.to_string(), 1.0, Geometry::BoundingBox(bbox));
+
+            record
+                .annotations
+                .entry(label.to_string())
+                .or_default()
+                .push(annotation);
+        }
+
+        let mut frame = Frame::new(index, index as f64);
+        frame.samples.push(Sample::ObjectDetection(record));
+
+        frame
+    }
+
+    /// Run a formula, returning the verdicts reported after each frame (and
+    /// at the end of the stream).
+    fn run(formula: &str, frames: &[Frame]) -> Vec<Vec<(usize, bool)>> {
+        let formula = Compiler::new().compile_temporal(formula).unwrap();
+        verdicts(TemporalMonitor::new(&formula), frames)
+    }
+
+    /// Run a [`TemporalMonitor`], returning the verdicts reported after each
+    /// frame (and at the end of the stream).
+    fn verdicts(mut monitor: TemporalMonitor, frames: &[Frame]) -> Vec<Vec<(usize, bool)>> {
+        let mut verdicts = frames
+            .iter()
+            .map(|frame| monitor.advance(frame))
+            .collect::<Vec<_>>();
+        verdicts.push(monitor.finish());
+
+        verdicts
+            .into_iter()
+            .map(|verdicts| verdicts.iter().map(|v| (v.index, v.holds)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn temporal() {
+        let frames = [
+            frame(0, &["pedestrian"]),
+            frame(1, &[]),
+            frame(2, &["brake"]),
+            frame(3, &["pedestrian"]),
+            frame(4, &[]),
+            frame(5, &[]),
+            frame(6, &["brake"]),
+        ];
+
+        // A frame is decided once a brake is seen, or its interval has passed.
+        assert_eq!(
+            vec![
+                vec![],
+                vec![],
+                vec![(0, true), (1, true), (2, true)],
+                vec![],
+                vec![],
+                vec![],
+                vec![(3, false), (4, true), (5, true), (6, true)],
+                vec![],
+            ],
+            run("pedestrian -> F[0,2s] brake", &frames)
+        );
+
+        // A past-time formula is decided as soon as the frame is seen.
+        assert_eq!(
+            vec![
+                vec![(0, false)],
+                vec![(1, false)],
+                vec![(2, true)],
+                vec![(3, true)],
+                vec![(4, true)],
+                vec![(5, false)],
+                vec![(6, true)],
+                vec![],
+            ],
+            run("[[:brake:]] | (Y O[0,1] brake)", &frames)
+        );
+    }
+
+    #[test]
+    fn invariant() {
+        let frames = [
+            frame(0, &["pedestrian"]),
+            frame(1, &["brake"]),
+            frame(2, &["pedestrian"]),
+            frame(3, &[]),
+            frame(4, &[]),
+            frame(5, &[]),
+            frame(6, &["pedestrian"]),
+            frame(7, &["brake"]),
+        ];
+
+        let formula = "G(pedestrian -> F[0,2] brake)";
+        let formula = Compiler::new().compile_temporal(formula).unwrap();
+
+        // A violation decides every frame up to it, and the frames after the
+        // last one are only decided at the end of the stream.
+        assert_eq!(
+            vec![
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![(0, false), (1, false), (2, false)],
+                vec![],
+                vec![],
+                vec![(3, true), (4, true), (5, true), (6, true), (7, true)],
+            ],
+            verdicts(TemporalMonitor::new(&formula), &frames)
+        );
+
+        // Otherwise, each violation is reported at the frame it occurs.
+        assert_eq!(
+            vec![
+                vec![],
+                vec![(0, true), (1, true)],
+                vec![],
+                vec![],
+                vec![],
+                vec![(2, false), (3, true), (4, true), (5, true)],
+                vec![],
+                vec![(6, true), (7, true)],
+                vec![],
+            ],
+            verdicts(TemporalMonitor::new(&formula).violations(), &frames)
+        );
+    }
+
+    #[test]
+    fn open() {
+        let frames = (0..50)
+            .map(|i| match i % 7 {
+                0 => frame(i, &["brake"]),
+                3 | 4 => frame(i, &[]),
+                _ => frame(i, &["pedestrian"]),
+            })
+            .collect::<Vec<_>>();
+
+        let brake = |i: usize| i.is_multiple_of(7);
+        let pedestrian = |i: usize| !matches!(i % 7, 0 | 3 | 4);
+
+        // The frames at least 2 before a frame.
+        let within = |i: usize| 0..(i + 1).saturating_sub(2);
+
+        let cases: [(&str, &dyn Fn(usize) -> bool); 3] = [
+            ("O[2,] brake", &|i| within(i).any(brake)),
+            ("H[2,] pedestrian", &|i| within(i).all(pedestrian)),
+            ("pedestrian S[2,] brake", &|i| {
+                within(i).any(|j| brake(j) && (j + 1..=i).all(pedestrian))
+            }),
+        ];
+
+        for (formula, expected) in cases {
+            let formula = Compiler::new().compile_temporal(formula).unwrap();
+            let mut monitor = TemporalMonitor::new(&formula);
+
+            for frame in frames.iter() {
+                let verdicts = monitor.advance(frame);
+
+                let verdicts = verdicts
+                    .iter()
+                    .map(|v| (v.index, v.holds))
+                    .collect::<Vec<_>>();
+
+                assert_eq!(vec![(frame.index, expected(frame.index))], verdicts);
+
+                // Only the frames within the lower bound are kept.
+                assert!(monitor.frames.len() <= 3);
+                assert!(monitor.slots.iter().all(|slot| slot.values.len() <= 3));
+            }
+        }
+    }
+}
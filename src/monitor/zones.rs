@@ -0,0 +1,207 @@
+//! Named zones of an image.
+//!
+//! A zone is a fixed region of interest of a channel (e.g., a crosswalk or the
+//! hood of the vehicle) that may be referenced within a SpRE as a spatial
+//! operand (e.g., `@crosswalk`).
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::BufReader;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compiler::ir::ast::{Atom, SpatialFormula};
+use crate::datastream::frame::sample::detections::{BoundingBox, Image, Point, Polygon};
+
+/// A collection of zones organized by channel.
+#[derive(Clone, Debug, Default)]
+pub struct Zones {
+    /// A mapping between the channel name and its zones (by name).
+    pub channels: HashMap<String, HashMap<String, Zone>>,
+}
+
+impl Zones {
+    /// Create a new empty set of [`Zones`].
+    pub fn new() -> Self {
+        Zones {
+            channels: HashMap::new(),
+        }
+    }
+
+    /// Load a set of [`Zones`] from a zone definition file.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let reader = BufReader::new(std::fs::File::open(path).or(Err(Box::new(
+            ZonesError::from(format!("unable to read `{}`", path.display())),
+        )))?);
+
+        let data: ZoneFormat = serde_json::from_reader(reader)?;
+
+        let mut zones = Zones::new();
+
+        for c in data.channels.into_iter() {
+            for z in c.zones.into_iter() {
+                let vertices = match (z.bbox, z.polygon) {
+                    (Some(bbox), None) => Polygon::from(&BoundingBox::new(
+                        Point::new(bbox.x, bbox.y),
+                        Point::new(bbox.x + bbox.w, bbox.y + bbox.h),
+                    )),
+                    (None, Some(polygon)) => Polygon::new(
+                        polygon
+                            .into_iter()
+                            .map(|point| Point::new(point.x, point.y))
+                            .collect(),
+                    ),
+                    _ => {
+                        return Err(Box::new(ZonesError::from(format!(
+                            "zone `{}` must define exactly one of `bbox` or `polygon`",
+                            z.name
+                        ))))
+                    }
+                };
+
+                zones
+                    .channels
+                    .entry(c.channel.clone())
+                    .or_default()
+                    .insert(z.name.clone(), Zone::new(z.name, vertices, z.units));
+            }
+        }
+
+        Ok(zones)
+    }
+
+    /// Retrieve the [`Zone`] of a channel by name.
+    pub fn get(&self, channel: &str, name: &str) -> Option<&Zone> {
+        self.channels.get(channel)?.get(name)
+    }
+
+    /// Check that every zone referenced by a spatial formula is defined by
+    /// some channel.
+    ///
+    /// Otherwise, the zone would never be resolved; so, the formula would
+    /// silently never be satisfied.
+    pub fn validate(&self, formula: &SpatialFormula) -> Result<(), ZonesError> {
+        for atom in formula.operands() {
+            if let Atom::Zone(name) = atom {
+                if !self.channels.values().any(|zones| zones.contains_key(name)) {
+                    return Err(ZonesError::from(format!("unknown zone `{}`", name)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A named region of interest of a channel.
+#[derive(Clone, Debug)]
+pub struct Zone {
+    pub name: String,
+    pub region: Polygon,
+    pub units: ZoneUnits,
+}
+
+impl Zone {
+    /// Create a new [`Zone`].
+    pub fn new(name: String, region: Polygon, units: ZoneUnits) -> Self {
+        Zone {
+            name,
+            region,
+            units,
+        }
+    }
+
+    /// Resolve the region of the [`Zone`] into pixel coordinates.
+    ///
+    /// A [`ZoneUnits::Normalized`] zone is scaled by the dimensions of the
+    /// image. Without an image, such a zone cannot be resolved; so, [`None`] is
+    /// returned.
+    pub fn resolve(&self, image: Option<&Image>) -> Option<Polygon> {
+        match self.units {
+            ZoneUnits::Pixel => Some(self.region.clone()),
+            ZoneUnits::Normalized => {
+                let image = image?;
+
+                Some(Polygon::new(
+                    self.region
+                        .vertices
+                        .iter()
+                        .map(|v| Point::new(v.x * image.width, v.y * image.height))
+                        .collect(),
+                ))
+            }
+        }
+    }
+}
+
+/// The units the coordinates of a [`Zone`] are expressed in.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneUnits {
+    #[default]
+    Pixel,
+    Normalized,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ZoneFormat {
+    channels: Vec<ZoneChannel>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ZoneChannel {
+    channel: String,
+    zones: Vec<ZoneDefinition>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ZoneDefinition {
+    name: String,
+    #[serde(default)]
+    units: ZoneUnits,
+    bbox: Option<ZoneBoundingBox>,
+    polygon: Option<Vec<ZonePoint>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ZoneBoundingBox {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ZonePoint {
+    x: f64,
+    y: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ZonesError {
+    msg: String,
+}
+
+impl From<&str> for ZonesError {
+    fn from(msg: &str) -> Self {
+        ZonesError {
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl From<String> for ZonesError {
+    fn from(msg: String) -> Self {
+        ZonesError { msg }
+    }
+}
+
+impl fmt::Display for ZonesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "zones: {}", self.msg)
+    }
+}
+
+impl Error for ZonesError {}
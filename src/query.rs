@@ -0,0 +1,548 @@
+//! A query layer over named SpREs.
+//!
+//! A scenario is often phrased in terms of several events and how they relate
+//! in time (e.g., "braking occurs during a pedestrian crossing"). So, a
+//! [`Query`] names a [`SpRE`] per event, finds the intervals each matches with
+//! the existing matchers, and combines them with Allen's interval relations.
+//! Each combination of intervals that satisfies every [`Constraint`] is
+//! reported as a [`Composite`] match.
+//!
+//! The intervals are those of [`SpRE::find_iter`] (i.e., of the [`MatchKind`]
+//! of each [`SpRE`]), where each [`Match`] is a half-open interval of the
+//! positions of the [`Frame`](s) searched.
+//!
+//! [`MatchKind`]: crate::matcher::MatchKind
+
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::datastream::frame::Frame;
+use crate::matcher::Match;
+use crate::spre::SpRE;
+
+/// The relations between two intervals (see Allen's interval algebra).
+///
+/// Each relation `a R b` is described in terms of the intervals `a` and `b`,
+/// where an interval ends at the position after its last [`Frame`]. The inverse
+/// of each relation (e.g., after) is expressed by swapping its intervals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    /// The interval `a` ends before `b` starts, with at least one frame between.
+    Before,
+
+    /// The interval `a` ends where `b` starts.
+    Meets,
+
+    /// The interval `a` starts before `b` and ends within it.
+    Overlaps,
+
+    /// The interval `a` starts after and ends before `b`.
+    During,
+
+    /// Both intervals start together, but `a` ends before `b`.
+    Starts,
+
+    /// Both intervals end together, but `a` starts after `b`.
+    Finishes,
+
+    /// Both intervals start and end together.
+    Equals,
+}
+
+impl Relation {
+    /// Check whether the [`Relation`] holds between two intervals.
+    pub fn holds(&self, a: &Match, b: &Match) -> bool {
+        match self {
+            Relation::Before => a.end < b.start,
+            Relation::Meets => a.end == b.start,
+            Relation::Overlaps => a.start < b.start && b.start < a.end && a.end < b.end,
+            Relation::During => b.start < a.start && a.end < b.end,
+            Relation::Starts => a.start == b.start && a.end < b.end,
+            Relation::Finishes => a.end == b.end && b.start < a.start,
+            Relation::Equals => a.start == b.start && a.end == b.end,
+        }
+    }
+
+    /// The positions of the endpoints that a time bound of the [`Relation`]
+    /// constrains.
+    ///
+    /// These are the first endpoints the [`Relation`] orders (e.g., the end of
+    /// `a` and start of `b` for [`Relation::Before`], or the start of `b` and
+    /// start of `a` for [`Relation::During`]), from the earlier to the later.
+    /// The position of an end is that of the last [`Frame`] of its interval.
+    ///
+    /// This is `None` for [`Relation::Meets`] and [`Relation::Equals`], whose
+    /// endpoints are not apart by any time to bound.
+    fn endpoints(&self, a: &Match, b: &Match) -> Option<(usize, usize)> {
+        let last = |m: &Match| m.end.saturating_sub(1).max(m.start);
+
+        match self {
+            Relation::Before => Some((last(a), b.start)),
+            Relation::Overlaps => Some((a.start, b.start)),
+            Relation::During | Relation::Finishes => Some((b.start, a.start)),
+            Relation::Starts => Some((last(a), last(b))),
+            Relation::Meets | Relation::Equals => None,
+        }
+    }
+}
+
+impl FromStr for Relation {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "before" => Ok(Relation::Before),
+            "meets" => Ok(Relation::Meets),
+            "overlaps" => Ok(Relation::Overlaps),
+            "during" => Ok(Relation::During),
+            "starts" => Ok(Relation::Starts),
+            "finishes" => Ok(Relation::Finishes),
+            "equals" => Ok(Relation::Equals),
+            _ => Err(QueryError::from(format!("unknown relation `{}`", s))),
+        }
+    }
+}
+
+/// A [`Relation`] required between the intervals of two named [`SpRE`](s).
+#[derive(Clone, Debug)]
+pub struct Constraint {
+    a: String,
+    relation: Relation,
+    b: String,
+
+    /// The minimum and maximum time (in seconds) between the endpoints the
+    /// [`Relation`] orders, if any.
+    bounds: Option<(f64, f64)>,
+}
+
+impl Constraint {
+    /// Create a new [`Constraint`] that `a` is in the [`Relation`] to `b`.
+    pub fn new(a: &str, relation: Relation, b: &str) -> Self {
+        Constraint {
+            a: a.to_string(),
+            relation,
+            b: b.to_string(),
+            bounds: None,
+        }
+    }
+
+    /// Bound the time between the endpoints the [`Relation`] orders.
+    ///
+    /// For example, `b` must start between `min` and `max` seconds after `a`
+    /// ends for [`Relation::Before`]. The time of an endpoint is the timestamp
+    /// of the [`Frame`] at its position (i.e., the first [`Frame`] of an
+    /// interval for its start, and its last [`Frame`] for its end). The time
+    /// bounded by each [`Relation`] is:
+    ///
+    /// - [`Relation::Before`], from the end of `a` to the start of `b`.
+    /// - [`Relation::Overlaps`], from the start of `a` to the start of `b`.
+    /// - [`Relation::During`] and [`Relation::Finishes`], from the start of `b`
+    ///   to the start of `a`.
+    /// - [`Relation::Starts`], from the end of `a` to the end of `b`.
+    ///
+    /// The endpoints of [`Relation::Meets`] and [`Relation::Equals`] are always
+    /// adjacent or the same, so a [`Query`] with such a bound is rejected.
+    pub fn within(mut self, min: f64, max: f64) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    /// Check whether the [`Constraint`] holds between two intervals.
+    fn holds<F: Borrow<Frame>>(&self, a: &Match, b: &Match, frames: &[F]) -> bool {
+        if !self.relation.holds(a, b) {
+            return false;
+        }
+
+        match (self.bounds, self.relation.endpoints(a, b)) {
+            (Some((min, max)), Some((from, to))) => {
+                let delay = timestamp(frames, to) - timestamp(frames, from);
+
+                min <= delay && delay <= max
+            }
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// A combination of intervals, one per named [`SpRE`] of a [`Query`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Composite {
+    /// The interval spanned by every interval of the [`Composite`].
+    pub span: Match,
+
+    /// The interval of each named [`SpRE`] (in the order they were named).
+    pub intervals: Vec<(String, Match)>,
+}
+
+impl Composite {
+    /// Get the interval of a named [`SpRE`].
+    pub fn get(&self, name: &str) -> Option<&Match> {
+        self.intervals
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, interval)| interval)
+    }
+}
+
+/// A query over the intervals of named [`SpRE`](s).
+#[derive(Default)]
+pub struct Query {
+    patterns: Vec<(String, SpRE)>,
+    constraints: Vec<Constraint>,
+}
+
+impl Query {
+    /// Create a new (empty) [`Query`].
+    pub fn new() -> Self {
+        Query::default()
+    }
+
+    /// Name a [`SpRE`] of the [`Query`].
+    pub fn pattern(mut self, name: &str, spre: SpRE) -> Self {
+        self.patterns.push((name.to_string(), spre));
+        self
+    }
+
+    /// Add a [`Constraint`] between two named [`SpRE`](s).
+    pub fn constraint(mut self, constraint: Constraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    /// Find every [`Composite`] match over a sequence of [`Frame`].
+    ///
+    /// The matches are ordered by the intervals of the [`SpRE`](s) in the order
+    /// they were named.
+    pub fn find<F>(&self, frames: &[F]) -> Result<Vec<Composite>, Box<dyn Error>>
+    where
+        F: Borrow<Frame>,
+    {
+        let index = |name: &str| {
+            self.patterns
+                .iter()
+                .position(|(n, _)| n == name)
+                .ok_or_else(|| QueryError::from(format!("unknown pattern `{}`", name)))
+        };
+
+        // The constraints by the position of the later named pattern, such
+        // that each is checked as soon as both of its intervals are chosen.
+        let mut constraints = vec![Vec::new(); self.patterns.len()];
+
+        for constraint in self.constraints.iter() {
+            let (a, b) = (index(&constraint.a)?, index(&constraint.b)?);
+
+            if let (Some(_), Relation::Meets | Relation::Equals) =
+                (constraint.bounds, constraint.relation)
+            {
+                return Err(QueryError::from(format!(
+                    "no time to bound between `{}` and `{}` for {:?}",
+                    constraint.a, constraint.b, constraint.relation
+                ))
+                .into());
+            }
+
+            constraints[a.max(b)].push((a, b, constraint));
+        }
+
+        let intervals = self
+            .patterns
+            .iter()
+            .map(|(_, spre)| spre.find_iter(frames.iter().map(F::borrow)).collect())
+            .collect::<Result<Vec<Vec<Match>>, _>>()?;
+
+        let mut composites = Vec::new();
+        let mut chosen = Vec::with_capacity(self.patterns.len());
+
+        self.search(
+            frames,
+            &intervals,
+            &constraints,
+            &mut chosen,
+            &mut composites,
+        );
+
+        Ok(composites)
+    }
+
+    /// Choose an interval for each remaining named [`SpRE`] (i.e., by
+    /// backtracking), and collect each [`Composite`] where every [`Constraint`]
+    /// holds.
+    fn search<F: Borrow<Frame>>(
+        &self,
+        frames: &[F],
+        intervals: &[Vec<Match>],
+        constraints: &[Vec<(usize, usize, &Constraint)>],
+        chosen: &mut Vec<Match>,
+        composites: &mut Vec<Composite>,
+    ) {
+        let i = chosen.len();
+
+        if i == self.patterns.len() {
+            if let (Some(start), Some(end)) = (
+                chosen.iter().map(|m| m.start).min(),
+                chosen.iter().map(|m| m.end).max(),
+            ) {
+                composites.push(Composite {
+                    span: Match::new(start, end),
+                    intervals: self
+                        .patterns
+                        .iter()
+                        .map(|(name, _)| name.clone())
+                        .zip(chosen.iter().cloned())
+                        .collect(),
+                });
+            }
+
+            return;
+        }
+
+        for interval in intervals[i].iter() {
+            chosen.push(interval.clone());
+
+            let holds = constraints[i]
+                .iter()
+                .all(|(a, b, constraint)| constraint.holds(&chosen[*a], &chosen[*b], frames));
+
+            if holds {
+                self.search(frames, intervals, constraints, chosen, composites);
+            }
+
+            chosen.pop();
+        }
+    }
+}
+
+/// The timestamp of the [`Frame`] at a position, where the position after the
+/// last [`Frame`] (i.e., of an empty interval at the end) takes its timestamp.
+fn timestamp<F: Borrow<Frame>>(frames: &[F], position: usize) -> f64 {
+    frames
+        .get(position)
+        .or(frames.last())
+        .map(|frame| frame.borrow().timestamp)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryError {
+    msg: String,
+}
+
+impl From<&str> for QueryError {
+    fn from(msg: &str) -> Self {
+        QueryError {
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl From<String> for QueryError {
+    fn from(msg: String) -> Self {
+        QueryError { msg }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "query: {}", self.msg)
+    }
+}
+
+impl Error for QueryError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::datastream::frame::sample::detections::{
+        Annotation, BoundingBox, DetectionRecord, Geometry, Point,
+    };
+    use crate::datastream::frame::sample::Sample;
+    use crate::datastream::frame::Frame;
+    use crate::matcher::Match;
+    use crate::spre::SpRE;
+
+    use super::{Constraint, Query, Relation};
+
+    fn frame(index: usize, labels: &[&str]) -> Frame {
+        let mut record = DetectionRecord::new(String::from("cam"), index as f64, None);
+
+        for label in labels {
+            let bbox = BoundingBox::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+            let annotation = Annotation::new(label.to_string(), 1.0, Geometry::BoundingBox(bbox));
+
+            record
+                .annotations
+                .entry(label.to_string())
+                .or_default()
+                .push(annotation);
+        }
+
+        let mut frame = Frame::new(index, index as f64);
+        frame.samples.push(Sample::ObjectDetection(record));
+
+        frame
+    }
+
+    #[test]
+    fn query() {
+        let frames = [
+            frame(0, &["car"]),
+            frame(1, &["car", "pedestrian"]),
+            frame(2, &["pedestrian"]),
+            frame(3, &["pedestrian", "brake"]),
+            frame(4, &["pedestrian", "brake"]),
+            frame(5, &["pedestrian"]),
+            frame(6, &["car"]),
+            frame(7, &["car", "brake"]),
+            frame(8, &["brake"]),
+        ];
+
+        let query = |relation, bounds: Option<(f64, f64)>| {
+            let mut constraint = Constraint::new("braking", relation, "crossing");
+
+            if let Some((min, max)) = bounds {
+                constraint = constraint.within(min, max);
+            }
+
+            Query::new()
+                .pattern("crossing", SpRE::new("[[:pedestrian:]]{1,}").unwrap())
+                .pattern("braking", SpRE::new("[[:brake:]]{1,}").unwrap())
+                .constraint(constraint)
+                .find(&frames)
+                .unwrap()
+        };
+
+        // The braking during the crossing.
+        let composites = query(Relation::During, None);
+
+        assert_eq!(1, composites.len());
+        assert_eq!(Match::new(1, 6), composites[0].span);
+        assert_eq!(Some(&Match::new(3, 5)), composites[0].get("braking"));
+
+        // The braking starts two seconds after the crossing starts.
+        assert!(query(Relation::During, Some((0.0, 1.0))).is_empty());
+        assert_eq!(1, query(Relation::During, Some((0.0, 2.0))).len());
+
+        assert!(query(Relation::Before, None).is_empty());
+        assert!(query(Relation::Equals, None).is_empty());
+
+        // The crossing meets the cars that follow it, and the cars before it
+        // overlap it.
+        let composites = Query::new()
+            .pattern("crossing", SpRE::new("[[:pedestrian:]]{1,}").unwrap())
+            .pattern("cars", SpRE::new("[[:car:]]{1,}").unwrap())
+            .constraint(Constraint::new("crossing", Relation::Meets, "cars"))
+            .find(&frames)
+            .unwrap();
+
+        assert_eq!(Match::new(1, 8), composites[0].span);
+
+        let composites = Query::new()
+            .pattern("crossing", SpRE::new("[[:pedestrian:]]{1,}").unwrap())
+            .pattern("cars", SpRE::new("[[:car:]]{1,}").unwrap())
+            .constraint(Constraint::new("cars", Relation::Overlaps, "crossing"))
+            .find(&frames)
+            .unwrap();
+
+        assert_eq!(Match::new(0, 6), composites[0].span);
+
+        assert!(Query::new()
+            .constraint(Constraint::new(
+                "cars",
+                "before".parse().unwrap(),
+                "crossing"
+            ))
+            .find(&frames)
+            .is_err());
+    }
+
+    #[test]
+    fn end_of_stream() {
+        let mut frames = [
+            frame(0, &["pedestrian", "brake"]),
+            frame(1, &["pedestrian", "brake"]),
+            frame(2, &["pedestrian"]),
+            frame(3, &["pedestrian"]),
+            frame(4, &["pedestrian"]),
+        ];
+
+        // The last frame is delayed (e.g., by a dropped frame).
+        frames[4].timestamp = 10.0;
+
+        let query = |min, max| {
+            Query::new()
+                .pattern("crossing", SpRE::new("[[:pedestrian:]]{1,}").unwrap())
+                .pattern("braking", SpRE::new("[[:brake:]]{1,}").unwrap())
+                .constraint(
+                    Constraint::new("braking", Relation::Starts, "crossing").within(min, max),
+                )
+                .find(&frames)
+                .unwrap()
+        };
+
+        // The end of each interval is the time of its last frame, including
+        // that of the crossing at the end of the stream.
+        assert_eq!(1, query(9.0, 9.0).len());
+        assert!(query(8.0, 8.0).is_empty());
+    }
+
+    #[test]
+    fn bounds() {
+        let frames = [
+            frame(0, &["pedestrian", "brake"]),
+            frame(1, &["pedestrian", "brake"]),
+            frame(2, &["pedestrian", "cyclist"]),
+            frame(3, &["pedestrian", "cyclist", "car"]),
+            frame(4, &["pedestrian", "cyclist", "car"]),
+            frame(5, &["car"]),
+        ];
+
+        let query = |a, relation, b, min, max| {
+            Query::new()
+                .pattern("crossing", SpRE::new("[[:pedestrian:]]{1,}").unwrap())
+                .pattern("braking", SpRE::new("[[:brake:]]{1,}").unwrap())
+                .pattern("cycling", SpRE::new("[[:cyclist:]]{1,}").unwrap())
+                .pattern("cars", SpRE::new("[[:car:]]{1,}").unwrap())
+                .constraint(Constraint::new(a, relation, b).within(min, max))
+                .find(&frames)
+        };
+
+        // The cars start three seconds after the crossing starts.
+        assert_eq!(
+            1,
+            query("crossing", Relation::Overlaps, "cars", 3.0, 3.0)
+                .unwrap()
+                .len()
+        );
+        assert!(query("crossing", Relation::Overlaps, "cars", 0.0, 2.0)
+            .unwrap()
+            .is_empty());
+
+        // The crossing ends three seconds after the braking ends.
+        assert_eq!(
+            1,
+            query("braking", Relation::Starts, "crossing", 3.0, 3.0)
+                .unwrap()
+                .len()
+        );
+        assert!(query("braking", Relation::Starts, "crossing", 0.0, 2.0)
+            .unwrap()
+            .is_empty());
+
+        // The cycling starts two seconds after the crossing starts.
+        assert_eq!(
+            1,
+            query("cycling", Relation::Finishes, "crossing", 2.0, 2.0)
+                .unwrap()
+                .len()
+        );
+        assert!(query("cycling", Relation::Finishes, "crossing", 3.0, 4.0)
+            .unwrap()
+            .is_empty());
+
+        // There is no time between the endpoints to bound.
+        assert!(query("crossing", Relation::Meets, "cars", 0.0, 1.0).is_err());
+        assert!(query("cycling", Relation::Equals, "cycling", 0.0, 1.0).is_err());
+    }
+}
@@ -0,0 +1,562 @@
+//! A high-level interface to matching with SpREs.
+//!
+//! This mirrors the interface of a regular expression library: a pattern is
+//! compiled once into a [`SpRE`], which is then searched against any number of
+//! sequences of [`Frame`]. The results are owned, so they may outlive both the
+//! [`SpRE`] and the [`Frame`](s) searched.
+//!
+//! A search consumes the [`Frame`](s) one at a time (i.e., online). So, a
+//! sequence may be either a slice of [`Frame`] or any iterator of [`Frame`]
+//! (e.g., a stream that never ends), and only the [`Frame`](s) a match may
+//! still span are kept in memory.
+
+use std::borrow::Borrow;
+use std::collections::VecDeque;
+use std::error::Error;
+
+use crate::compiler::Compiler;
+use crate::datastream::frame::Frame;
+use crate::matcher::automata::dfa::forward::{self, DeterministicFiniteAutomata};
+use crate::matcher::automata::Options;
+use crate::matcher::record::Record;
+use crate::matcher::symbols::{Evaluator, SymbolSet};
+use crate::matcher::threads::Threads;
+use crate::matcher::{Match, MatchKind};
+use crate::monitor::zones::Zones;
+use crate::monitor::Monitor;
+use crate::symbolizer::ast::SymbolicAbstractSyntaxTree;
+
+/// A compiled Spatial-based Regular Expression (SpRE).
+///
+/// By default, the leftmost-longest non-overlapping matches are reported (i.e.,
+/// [`MatchKind::LeftmostLongest`]).
+pub struct SpRE {
+    ast: SymbolicAbstractSyntaxTree,
+    dfa: DeterministicFiniteAutomata,
+    monitor: Monitor,
+    kind: MatchKind,
+    window: Option<usize>,
+}
+
+impl SpRE {
+    /// Compile a new [`SpRE`] from a pattern.
+    pub fn new(pattern: &str) -> Result<Self, Box<dyn Error>> {
+        SpRE::with_options(pattern, &Options::default())
+    }
+
+    /// Compile a new [`SpRE`] from a pattern with the [`Options`] provided
+    /// (e.g., to select the backend of the DFA).
+    pub fn with_options(pattern: &str, options: &Options) -> Result<Self, Box<dyn Error>> {
+        let ast = Compiler::new().compile(pattern)?;
+        let dfa = forward::build_all(&ast, options)?;
+
+        Ok(SpRE {
+            ast,
+            dfa,
+            monitor: Monitor::new(),
+            kind: MatchKind::LeftmostLongest,
+            window: None,
+        })
+    }
+
+    /// Set the [`Zones`] that spatial formulas may reference.
+    pub fn zones(mut self, zones: Zones) -> Self {
+        self.monitor = self.monitor.zones(zones);
+        self
+    }
+
+    /// Set the [`MatchKind`] to report.
+    pub fn kind(mut self, kind: MatchKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the maximum number of [`Frame`](s) a match may span.
+    pub fn window(mut self, size: usize) -> Self {
+        self.window = Some(size);
+        self
+    }
+
+    /// Check whether any match exists.
+    ///
+    /// This stops at the first [`Frame`] that ends a match, regardless of the
+    /// [`MatchKind`].
+    pub fn is_match<I, F>(&self, frames: I) -> Result<bool, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = F>,
+        F: Borrow<Frame>,
+    {
+        let mut search = Search::new(self, frames.into_iter(), MatchKind::AllEnds);
+        Ok(search.next().transpose()?.is_some())
+    }
+
+    /// Find the first [`Match`].
+    pub fn find<I, F>(&self, frames: I) -> Result<Option<Match>, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = F>,
+        F: Borrow<Frame>,
+    {
+        self.find_iter(frames).next().transpose()
+    }
+
+    /// Find every [`Match`] of the [`MatchKind`].
+    ///
+    /// The indices of a [`Match`] are the positions of the [`Frame`](s) within
+    /// the sequence provided (i.e., not their [`Frame::index`]).
+    pub fn find_iter<I, F>(&self, frames: I) -> FindIter<'_, I::IntoIter, F>
+    where
+        I: IntoIterator<Item = F>,
+        F: Borrow<Frame>,
+    {
+        FindIter {
+            search: Search::new(self, frames.into_iter(), self.kind),
+        }
+    }
+
+    /// Find the first match resolved into a [`Record`].
+    pub fn captures<I, F>(&self, frames: I) -> Result<Option<Record>, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = F>,
+        F: Borrow<Frame>,
+    {
+        self.captures_iter(frames).next().transpose()
+    }
+
+    /// Find every match of the [`MatchKind`] resolved into a [`Record`].
+    ///
+    /// Unlike a [`Match`], a [`Record`] holds the [`Frame::index`] of each
+    /// [`Frame`] along with the spatial formula (and its witnesses) taken.
+    pub fn captures_iter<I, F>(&self, frames: I) -> CapturesIter<'_, I::IntoIter, F>
+    where
+        I: IntoIterator<Item = F>,
+        F: Borrow<Frame>,
+    {
+        CapturesIter {
+            search: Search::new(self, frames.into_iter(), self.kind),
+        }
+    }
+}
+
+/// An iterator over every [`Match`] of a [`SpRE`].
+///
+/// This is created by [`SpRE::find_iter`].
+pub struct FindIter<'s, I, F> {
+    search: Search<'s, I, F>,
+}
+
+impl<I, F> Iterator for FindIter<'_, I, F>
+where
+    I: Iterator<Item = F>,
+    F: Borrow<Frame>,
+{
+    type Item = Result<Match, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.search.next()
+    }
+}
+
+/// An iterator over every match of a [`SpRE`] resolved into a [`Record`].
+///
+/// This is created by [`SpRE::captures_iter`].
+pub struct CapturesIter<'s, I, F> {
+    search: Search<'s, I, F>,
+}
+
+impl<I, F> Iterator for CapturesIter<'_, I, F>
+where
+    I: Iterator<Item = F>,
+    F: Borrow<Frame>,
+{
+    type Item = Result<Record, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.search.next()? {
+            Ok(m) => Some(self.search.record(&m)),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// The state of a search over a sequence of [`Frame`].
+struct Search<'s, I, F> {
+    spre: &'s SpRE,
+    evaluator: Evaluator<'s>,
+    frames: I,
+    threads: Threads,
+
+    /// The [`Frame`](s) (and their symbols) a match may still span.
+    buffer: VecDeque<F>,
+    symbols: VecDeque<SymbolSet>,
+
+    /// The position of the first [`Frame`] of the `buffer`.
+    offset: usize,
+
+    /// The matches found but not yet returned.
+    queue: VecDeque<Match>,
+    finished: bool,
+
+    /// An error of the [`SpRE`] itself (e.g., an unknown zone), which is
+    /// returned before any [`Frame`] is searched.
+    error: Option<Box<dyn Error>>,
+}
+
+impl<'s, I, F> Search<'s, I, F>
+where
+    I: Iterator<Item = F>,
+    F: Borrow<Frame>,
+{
+    /// Create a new [`Search`] for a [`MatchKind`].
+    fn new(spre: &'s SpRE, frames: I, kind: MatchKind) -> Self {
+        let mut evaluator = Evaluator::from(&spre.ast);
+        evaluator.monitor = spre.monitor.clone();
+
+        let mut threads = Threads::new(kind);
+
+        if let Some(size) = spre.window {
+            threads = threads.window(size);
+        }
+
+        let error = spre
+            .ast
+            .fmap()
+            .into_iter()
+            .find_map(|x| spre.monitor.validate(&x.formula).err());
+
+        Search {
+            spre,
+            evaluator,
+            frames,
+            threads,
+            buffer: VecDeque::new(),
+            symbols: VecDeque::new(),
+            offset: 0,
+            queue: VecDeque::new(),
+            finished: false,
+            error,
+        }
+    }
+
+    /// Find the next [`Match`].
+    ///
+    /// The [`Frame`](s) of the [`Match`] returned remain in the `buffer` until
+    /// the next call.
+    fn next(&mut self) -> Option<Result<Match, Box<dyn Error>>> {
+        if let Some(e) = self.error.take() {
+            self.finished = true;
+            return Some(Err(e));
+        }
+
+        loop {
+            if let Some(m) = self.queue.pop_front() {
+                return Some(Ok(m));
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            // Discard the [`Frame`](s) that are never part of a future match.
+            let earliest = self.threads.earliest();
+
+            while self.offset < earliest && !self.buffer.is_empty() {
+                self.buffer.pop_front();
+                self.symbols.pop_front();
+                self.offset += 1;
+            }
+
+            match self.frames.next() {
+                Some(frame) => {
+                    let symbols = self.evaluator.evaluate(frame.borrow());
+
+                    self.buffer.push_back(frame);
+                    self.symbols.push_back(symbols);
+
+                    match self.threads.advance(&self.spre.dfa, &symbols, true) {
+                        Ok(mats) => self.queue.extend(mats),
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                None => {
+                    self.queue.extend(self.threads.finish());
+                    self.finished = true;
+                }
+            }
+        }
+    }
+
+    /// Resolve the last [`Match`] returned into a [`Record`].
+    fn record(&mut self, m: &Match) -> Result<Record, Box<dyn Error>> {
+        let range = (m.start - self.offset)..(m.end - self.offset);
+
+        let frames = &self.buffer.make_contiguous()[range.clone()];
+        let symbols = &self.symbols.make_contiguous()[range];
+
+        Record::new(&self.spre.dfa, &self.evaluator, frames, symbols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+    use crate::datastream::frame::sample::detections::{
+        Annotation, BoundingBox, DetectionRecord, Geometry, Image, ImageSource, Point, Polygon,
+    };
+    use crate::datastream::frame::sample::scenes::SceneRecord;
+    use crate::datastream::frame::sample::Sample;
+    use crate::datastream::frame::Frame;
+    use crate::matcher::automata::{Backend, Options};
+    use crate::matcher::{Match, MatchKind};
+    use crate::monitor::zones::{Zone, ZoneUnits, Zones};
+
+    use super::SpRE;
+
+    fn frame(index: usize, labels: &[&str]) -> Frame {
+        let mut record = DetectionRecord::new(String::from("cam"), index as f64, None);
+
+        for label in labels {
+            let bbox = BoundingBox::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+            let annotation = Annotation::new(label.to_string(), 1.0, Geometry::BoundingBox(bbox));
+
+            record
+                .annotations
+                .entry(label.to_string())
+                .or_default()
+                .push(annotation);
+        }
+
+        let mut frame = Frame::new(index, index as f64);
+        frame.samples.push(Sample::ObjectDetection(record));
+
+        frame
+    }
+
+    #[test]
+    fn spre() {
+        let spre = SpRE::new("[[:car:]]{1,}[[:pedestrian:]]").unwrap();
+
+        let frames = [
+            frame(10, &["car"]),
+            frame(11, &["car"]),
+            frame(12, &["pedestrian"]),
+            frame(13, &[]),
+            frame(14, &["car"]),
+            frame(15, &["pedestrian"]),
+        ];
+
+        assert!(spre.is_match(&frames).unwrap());
+        assert!(!spre.is_match(&frames[..2]).unwrap());
+
+        let mats = spre
+            .find_iter(&frames)
+            .map(|m| m.map(|m| (m.start, m.end)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(vec![(0, 3), (4, 6)], mats);
+
+        // Any iterator of owned [`Frame`](s) may be searched, as well.
+        let records = spre
+            .captures_iter(frames.clone())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            vec![(10, 13), (14, 16)],
+            records.iter().map(|r| (r.start, r.end)).collect::<Vec<_>>()
+        );
+
+        let spre = spre.kind(MatchKind::AllOverlapping);
+        let m = spre.find(frames.iter()).unwrap().unwrap();
+
+        assert_eq!((0, 3), (m.start, m.end));
+    }
+
+    #[test]
+    fn sync() {
+        fn assert<T: Send + Sync>() {}
+
+        // A compiled [`SpRE`] may be shared across threads.
+        assert::<SpRE>();
+    }
+
+    #[test]
+    fn approximate() {
+        // The pedestrian is missed by the detector for a few frames.
+        let frames = [
+            frame(0, &["car"]),
+            frame(1, &["pedestrian"]),
+            frame(2, &["pedestrian"]),
+            frame(3, &[]),
+            frame(4, &["pedestrian"]),
+            frame(5, &[]),
+            frame(6, &["pedestrian"]),
+            frame(7, &["car"]),
+        ];
+
+        for backend in [Backend::Dense, Backend::Lazy, Backend::Counting] {
+            let options = Options::new().backend(backend);
+
+            let spre =
+                SpRE::with_options("[[:car:]][[:pedestrian:]]{6}[[:car:]]", &options).unwrap();
+            assert!(!spre.is_match(&frames).unwrap());
+
+            let spre =
+                SpRE::with_options("[[:car:]]([[:pedestrian:]]{6})~1[[:car:]]", &options).unwrap();
+            assert!(!spre.is_match(&frames).unwrap());
+
+            let spre =
+                SpRE::with_options("[[:car:]]([[:pedestrian:]]{6})~2[[:car:]]", &options).unwrap();
+            let record = spre.captures(&frames).unwrap().unwrap();
+
+            assert_eq!((0, 8), (record.start, record.end));
+            assert_eq!(2, record.errors);
+            assert_eq!(
+                vec![3, 5],
+                record
+                    .steps
+                    .iter()
+                    .filter(|step| step.mismatch)
+                    .map(|step| step.index)
+                    .collect::<Vec<_>>()
+            );
+
+            // The frames before and after the pedestrian must match exactly.
+            let spre = SpRE::with_options("[[:car:]]{2}([[:pedestrian:]]{5})~2[[:car:]]", &options)
+                .unwrap();
+            assert!(!spre.is_match(&frames).unwrap());
+        }
+    }
+
+    #[test]
+    fn boolean() {
+        let frames = [
+            frame(0, &["car"]),
+            frame(1, &["car"]),
+            frame(2, &["car", "pedestrian"]),
+            frame(3, &["car"]),
+            frame(4, &["car"]),
+            frame(5, &[]),
+        ];
+
+        for backend in [Backend::Dense, Backend::Lazy, Backend::Counting] {
+            let options = Options::new().backend(backend);
+
+            // The cars without any pedestrian.
+            let spre =
+                SpRE::with_options("[[:car:]]{2,} && ~(.*[[:pedestrian:]].*)", &options).unwrap();
+
+            let mats = spre
+                .find_iter(&frames)
+                .map(|m| m.map(|m| (m.start, m.end)))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            assert_eq!(vec![(0, 2), (3, 5)], mats);
+
+            // The cars with a pedestrian somewhere along the way.
+            let spre =
+                SpRE::with_options("[[:car:]]{1,} && .*[[:pedestrian:]].*", &options).unwrap();
+            let record = spre.captures(&frames).unwrap().unwrap();
+
+            assert_eq!((0, 5), (record.start, record.end));
+
+            // A frame that satisfies no formula is matched as any frame.
+            let spre = SpRE::with_options("[[:car:]] ~([[:car:]]{1,})", &options).unwrap();
+            let record = spre.captures(&frames[4..]).unwrap().unwrap();
+
+            assert_eq!((4, 6), (record.start, record.end));
+            assert_eq!('_', record.steps[1].symbol);
+        }
+    }
+
+    #[test]
+    fn distance() {
+        // Two distinct cars with identical boxes are zero pixels apart, while
+        // a single car is never compared against itself.
+        let frames = [frame(0, &["car"]), frame(1, &["car", "car"])];
+
+        let spre = SpRE::new("[<dist>([:car:], [:car:]) < 1]").unwrap();
+        assert_eq!(Some(Match::new(1, 2)), spre.find(&frames).unwrap());
+    }
+
+    #[test]
+    fn complement() {
+        // The boxes of both annotations cover the same 10x10 pixels of the
+        // 20x20 image; so, the area of their complement is 300 pixels.
+        let mut frames = [frame(0, &["car", "pedestrian"])];
+
+        if let Sample::ObjectDetection(record) = &mut frames[0].samples[0] {
+            record.image = Some(Image::new(ImageSource::File("cam.png".into()), 20.0, 20.0));
+        }
+
+        let spre = SpRE::new("[<area>(!([:car:] | [:pedestrian:])) > 299]").unwrap();
+        assert!(spre.is_match(&frames).unwrap());
+
+        let spre = SpRE::new("[<area>(!([:car:] | [:pedestrian:])) > 300]").unwrap();
+        assert!(!spre.is_match(&frames).unwrap());
+
+        let spre = SpRE::new("[<width>(!([:car:])) >= 20]").unwrap();
+        assert!(spre.is_match(&frames).unwrap());
+    }
+
+    #[test]
+    fn tags() {
+        let frames = [0.2, 0.9].map(|score| {
+            let mut record = SceneRecord::new(String::from("weather"), 0.0);
+            record.tags.insert(String::from("rain"), score);
+
+            let mut frame = Frame::new(0, 0.0);
+            frame.samples.push(Sample::Scene(record));
+            frame
+        });
+
+        let spre = SpRE::new("[[:@rain:]]").unwrap();
+        assert_eq!(Some(Match::new(0, 1)), spre.find(&frames).unwrap());
+
+        // A tag with a low score does not satisfy a comparison against it.
+        let spre = SpRE::new("[[:@rain:] > 0.5]").unwrap();
+        assert_eq!(Some(Match::new(1, 2)), spre.find(&frames).unwrap());
+
+        let spre = SpRE::new("[[:@rain:] > 0.95]").unwrap();
+        assert_eq!(None, spre.find(&frames).unwrap());
+    }
+
+    #[test]
+    fn malformed() {
+        // A malformed pattern is an error, rather than exiting the process.
+        assert!(SpRE::new("[[:car:]").is_err());
+        assert!(SpRE::new("[<nope>[:car:] > 1]").is_err());
+        assert!(Compiler::new().compile_temporal("G(car").is_err());
+
+        assert!(SpRE::new("[[:car:]]").is_ok());
+    }
+
+    #[test]
+    fn zones() {
+        let frames = [frame(0, &["car"])];
+
+        let spre = SpRE::new("[<nonempty>([:car:] & @road)]").unwrap();
+        let error = spre.find(&frames).unwrap_err();
+        assert_eq!("zones: unknown zone `road`", error.to_string());
+
+        let road = Zone::new(
+            String::from("road"),
+            Polygon::from(&BoundingBox::new(
+                Point::new(0.0, 0.0),
+                Point::new(20.0, 20.0),
+            )),
+            ZoneUnits::Pixel,
+        );
+
+        let mut zones = Zones::new();
+        zones
+            .channels
+            .entry(String::from("cam"))
+            .or_default()
+            .insert(String::from("road"), road);
+
+        let spre = spre.zones(zones);
+        assert_eq!(Some(Match::new(0, 1)), spre.find(&frames).unwrap());
+    }
+}
@@ -3,12 +3,13 @@
 //! This Intermediate Representation (IR) of the SpRE is a necessary step to
 //! perform matching with the underlying library.
 
-use crate::compiler::ir::{ast::SpatialFormula, Node};
+use crate::compiler::ir::{ast::SpatialFormula, Node, Operator, RangeKind, RegexOperatorKind};
 
 /// A symbolically-linked spatial formula.
 ///
 /// This maps a [`SpatialFormula`] to a unique symbol that is used when
 /// performing matching.
+#[derive(Clone)]
 pub struct SymbolicFormula {
     pub symbol: char,
     pub formula: SpatialFormula,
@@ -59,4 +60,189 @@ impl SymbolicAbstractSyntaxTree {
             }
         }
     }
+
+    /// From the symbolic-AST, return the sub-patterns matched approximately.
+    ///
+    /// These are listed from the outermost to the innermost. So, a symbol
+    /// within nested sub-patterns belongs to several [`Approximation`](s).
+    pub fn approximations(&self) -> Vec<Approximation> {
+        let mut approximations = Vec::new();
+
+        if let Some(root) = &self.root {
+            SymbolicAbstractSyntaxTree::approximationsit(root, &mut approximations);
+        }
+
+        approximations
+    }
+
+    /// The recursive helper function to collect the approximate sub-patterns.
+    fn approximationsit(node: &Node<SymbolicFormula>, approximations: &mut Vec<Approximation>) {
+        match node {
+            Node::Operand(..) => {}
+            Node::UnaryExpr { op, child } => {
+                if let Operator::RegexOperator(RegexOperatorKind::Approximate(budget)) = op {
+                    approximations.push(Approximation {
+                        budget: *budget,
+                        symbols: SymbolicAbstractSyntaxTree::fmapit(child)
+                            .into_iter()
+                            .map(|x| x.symbol)
+                            .collect(),
+                    });
+                }
+
+                SymbolicAbstractSyntaxTree::approximationsit(child, approximations);
+            }
+            Node::BinaryExpr { left, right, .. } => {
+                SymbolicAbstractSyntaxTree::approximationsit(left, approximations);
+                SymbolicAbstractSyntaxTree::approximationsit(right, approximations);
+            }
+        }
+    }
+
+    /// Check whether the symbolic-AST intersects or complements a sub-pattern.
+    ///
+    /// Such a pattern cannot be converted into an RE (see
+    /// [`automata::boolean`](crate::matcher::automata::boolean)).
+    pub fn is_boolean(&self) -> bool {
+        self.root
+            .as_ref()
+            .is_some_and(SymbolicAbstractSyntaxTree::booleanit)
+    }
+
+    /// The recursive helper function to check for intersections and
+    /// complements.
+    pub(crate) fn booleanit(node: &Node<SymbolicFormula>) -> bool {
+        match node {
+            Node::Operand(..) => false,
+            Node::UnaryExpr { op, child } => {
+                matches!(op, Operator::RegexOperator(RegexOperatorKind::Complement))
+                    || SymbolicAbstractSyntaxTree::booleanit(child)
+            }
+            Node::BinaryExpr { op, left, right } => {
+                matches!(op, Operator::RegexOperator(RegexOperatorKind::Intersection))
+                    || SymbolicAbstractSyntaxTree::booleanit(left)
+                    || SymbolicAbstractSyntaxTree::booleanit(right)
+            }
+        }
+    }
+
+    /// From the symbolic-AST, return the bounds on the length of a match.
+    ///
+    /// The length is measured in frames. This is a static analysis over the
+    /// pattern alone, so no match found is ever shorter than the minimum or
+    /// longer than the maximum.
+    pub fn length(&self) -> Length {
+        if let Some(root) = &self.root {
+            return SymbolicAbstractSyntaxTree::lengthit(root);
+        }
+
+        Length::new(0, Some(0))
+    }
+
+    /// The recursive helper function to compute the bounds on the length.
+    pub(crate) fn lengthit(node: &Node<SymbolicFormula>) -> Length {
+        match node {
+            Node::Operand(..) => Length::new(1, Some(1)),
+            Node::UnaryExpr { op, child } => {
+                let child = SymbolicAbstractSyntaxTree::lengthit(child);
+
+                match op {
+                    Operator::RegexOperator(RegexOperatorKind::KleeneStar) => child.repeat(0, None),
+                    Operator::RegexOperator(RegexOperatorKind::Range(kind)) => match kind {
+                        RangeKind::Exactly(size) => child.repeat(*size, Some(*size)),
+                        RangeKind::AtLeast(min) => child.repeat(*min, None),
+                        RangeKind::Between(min, max) => child.repeat(*min, Some(*max)),
+                    },
+                    Operator::RegexOperator(RegexOperatorKind::Approximate(..)) => child,
+                    Operator::RegexOperator(RegexOperatorKind::Complement) => Length::new(0, None),
+                    _ => Length::new(0, Some(0)),
+                }
+            }
+            Node::BinaryExpr { op, left, right } => {
+                let left = SymbolicAbstractSyntaxTree::lengthit(left);
+                let right = SymbolicAbstractSyntaxTree::lengthit(right);
+
+                match op {
+                    Operator::RegexOperator(RegexOperatorKind::Concatenation) => Length::new(
+                        left.min.saturating_add(right.min),
+                        left.max.zip(right.max).map(|(l, r)| l.saturating_add(r)),
+                    ),
+                    Operator::RegexOperator(RegexOperatorKind::Alternation) => Length::new(
+                        left.min.min(right.min),
+                        left.max.zip(right.max).map(|(l, r)| l.max(r)),
+                    ),
+                    Operator::RegexOperator(RegexOperatorKind::Intersection) => Length::new(
+                        left.min.max(right.min),
+                        match (left.max, right.max) {
+                            (Some(l), Some(r)) => Some(l.min(r)),
+                            (max, None) | (None, max) => max,
+                        },
+                    ),
+                    _ => Length::new(0, Some(0)),
+                }
+            }
+        }
+    }
+}
+
+/// A sub-pattern where up to a `budget` of frames may violate the spatial
+/// formula expected of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Approximation {
+    pub budget: usize,
+
+    /// The symbols of the sub-pattern.
+    pub symbols: Vec<char>,
+}
+
+/// The bounds on the length of a match (in frames).
+///
+/// A `max` of `None` denotes an unbounded match (e.g., from `*` or `{n,}`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Length {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Length {
+    /// Create a new [`Length`].
+    pub fn new(min: usize, max: Option<usize>) -> Self {
+        Length { min, max }
+    }
+
+    /// The [`Length`] of repeating a match between `min` and `max` times.
+    fn repeat(self, min: usize, max: Option<usize>) -> Length {
+        let max = match (self.max, max) {
+            // Repeating an empty match any number of times is still empty.
+            (Some(0), _) => Some(0),
+            (Some(length), Some(max)) => Some(length.saturating_mul(max)),
+            _ => None,
+        };
+
+        Length::new(self.min.saturating_mul(min), max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+
+    use super::Length;
+
+    #[test]
+    fn length() {
+        let cases = [
+            ("[[:a:]][[:b:]]{2,4}", Length::new(3, Some(5))),
+            ("([[:a:]]|[[:b:]]{3})[[:c:]]", Length::new(2, Some(4))),
+            ("[[:a:]]([[:b:]]{2})*", Length::new(1, None)),
+            ("([[:a:]][[:b:]]){2,}", Length::new(4, None)),
+            ("[[:a:]]{2,5} && .*[[:b:]]{3}", Length::new(3, Some(5))),
+            ("[[:a:]] ~([[:b:]])", Length::new(1, None)),
+        ];
+
+        for (pattern, length) in cases {
+            let ast = Compiler::new().compile(pattern).unwrap();
+            assert_eq!(length, ast.length());
+        }
+    }
 }